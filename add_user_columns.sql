@@ -0,0 +1,15 @@
+-- Script de Migração - Alpha Bank Database
+-- Execute este script no MySQL Workbench em bancos criados antes das colunas
+-- de fuso horário da tabela users
+
+USE alpha_bank;
+
+-- Fuso horário usado no relatório de fluxo de caixa (padrão: horário de Brasília)
+ALTER TABLE users
+ADD COLUMN timezone VARCHAR(6) NOT NULL DEFAULT '-03:00' AFTER phone;
+
+-- Verificar se funcionou
+SELECT 'Users migrados:' as status;
+SELECT id, email, timezone FROM users LIMIT 5;
+
+-- Pronto! Agora o relatório de fluxo de caixa usa o fuso de cada usuário
@@ -0,0 +1,524 @@
+-- Script de Migração - Alpha Bank Database
+-- Execute este script no MySQL Workbench em bancos criados com uma versão
+-- anterior do schema.sql. Ele leva o banco do schema.sql original até o
+-- schema.sql atual, na ordem em que as tabelas e colunas foram adicionadas.
+--
+-- Pré-requisito: banco criado com o schema.sql original e já migrado com
+-- add_user_columns.sql. Rode o script inteiro uma única vez; bancos criados
+-- com o schema.sql atual não precisam dele.
+
+USE alpha_bank;
+
+-- 1. Busca textual nas descrições das transações
+ALTER TABLE transactions
+ADD FULLTEXT INDEX ft_description (description);
+
+-- 2. Orçamentos mensais por categoria e alertas de gastos
+CREATE TABLE notification_events (
+    user_id CHAR(36) NOT NULL,
+    event_key VARCHAR(191) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, event_key),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE budgets (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    user_id CHAR(36) NOT NULL,
+    category_id CHAR(36) NOT NULL,
+    amount DECIMAL(12, 2) NOT NULL CHECK (amount > 0),
+    rollover BOOLEAN DEFAULT FALSE,
+    start_month DATE NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE,
+    UNIQUE KEY unique_user_budget (user_id, category_id),
+    INDEX idx_user_id (user_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- 3. Orçamento base zero (envelopes)
+ALTER TABLE users
+ADD COLUMN envelope_start DATE NULL AFTER timezone;
+
+CREATE TABLE envelope_assignments (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    user_id CHAR(36) NOT NULL,
+    category_id CHAR(36) NOT NULL,
+    month DATE NOT NULL,
+    amount DECIMAL(12, 2) NOT NULL CHECK (amount <> 0),
+    note VARCHAR(255),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE,
+    INDEX idx_user_month (user_id, month),
+    INDEX idx_category_id (category_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- 4. Recorrências com data âncora e intervalo
+-- As regras antigas passam a contar a partir da última geração (ou da
+-- criação), e last_generated passa a guardar só a data
+ALTER TABLE recurring_transactions
+ADD COLUMN interval_count INT UNSIGNED NOT NULL DEFAULT 1 CHECK (interval_count >= 1) AFTER frequency,
+ADD COLUMN start_date DATE NULL AFTER interval_count,
+ADD COLUMN last_generated_date DATE NULL AFTER last_generated;
+
+UPDATE recurring_transactions
+SET start_date = DATE(COALESCE(last_generated, created_at)),
+    last_generated_date = DATE(last_generated);
+
+ALTER TABLE recurring_transactions
+MODIFY start_date DATE NOT NULL,
+DROP COLUMN last_generated;
+
+ALTER TABLE recurring_transactions
+RENAME COLUMN last_generated_date TO last_generated;
+
+-- 5. Ocorrências geradas uma única vez por data
+ALTER TABLE transactions
+ADD COLUMN occurrence_date DATE NULL AFTER recurring_id,
+ADD UNIQUE KEY unique_recurring_occurrence (recurring_id, occurrence_date);
+
+-- 6. Fim e limite de ocorrências das recorrências
+ALTER TABLE recurring_transactions
+ADD COLUMN end_date DATE NULL AFTER start_date,
+ADD COLUMN max_occurrences INT UNSIGNED NULL CHECK (max_occurrences >= 1) AFTER end_date;
+
+-- 7. Exceções por ocorrência (puladas, remarcadas ou alteradas)
+CREATE TABLE recurring_exceptions (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    recurring_id CHAR(36) NOT NULL,
+    user_id CHAR(36) NOT NULL,
+    occurrence_date DATE NOT NULL,
+    skipped BOOLEAN NOT NULL DEFAULT FALSE,
+    new_date DATE NULL,
+    amount DECIMAL(12, 2) NULL CHECK (amount > 0),
+    description VARCHAR(255) NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (recurring_id) REFERENCES recurring_transactions(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    UNIQUE KEY unique_recurring_exception (recurring_id, occurrence_date),
+    INDEX idx_user_id (user_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- 8. Regras RRULE (subconjunto do iCalendar)
+ALTER TABLE recurring_transactions
+ADD COLUMN rrule VARCHAR(255) NULL AFTER interval_count;
+
+-- 9. Ajuste para dias úteis
+ALTER TABLE recurring_transactions
+ADD COLUMN business_day_shift VARCHAR(10) NOT NULL DEFAULT 'none' CHECK (business_day_shift IN ('none', 'forward', 'backward')) AFTER max_occurrences;
+
+-- 10. Compras parceladas
+CREATE TABLE installment_plans (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    user_id CHAR(36) NOT NULL,
+    description VARCHAR(255) NOT NULL,
+    total_amount DECIMAL(12, 2) NOT NULL CHECK (total_amount > 0),
+    installment_count INT UNSIGNED NOT NULL CHECK (installment_count >= 2),
+    type VARCHAR(20) NOT NULL DEFAULT 'expense' CHECK (type IN ('income', 'expense')),
+    category_id CHAR(36),
+    first_due_date DATE NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'active' CHECK (status IN ('active', 'prepaid', 'cancelled')),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE SET NULL,
+    INDEX idx_user_id (user_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+ALTER TABLE transactions
+ADD COLUMN installment_plan_id CHAR(36) NULL AFTER occurrence_date,
+ADD COLUMN installment_number INT UNSIGNED NULL AFTER installment_plan_id,
+ADD FOREIGN KEY (installment_plan_id) REFERENCES installment_plans(id) ON DELETE CASCADE;
+
+-- 11. Contas e faturas de cartão de crédito
+CREATE TABLE accounts (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    user_id CHAR(36) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    type VARCHAR(20) NOT NULL CHECK (type IN ('checking', 'savings', 'credit_card', 'cash')),
+    closing_day TINYINT UNSIGNED NULL CHECK (closing_day BETWEEN 1 AND 31),
+    due_day TINYINT UNSIGNED NULL CHECK (due_day BETWEEN 1 AND 31),
+    credit_limit DECIMAL(12, 2) NULL CHECK (credit_limit >= 0),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    INDEX idx_user_id (user_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+ALTER TABLE transactions
+ADD COLUMN account_id CHAR(36) NULL AFTER installment_number,
+ADD FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE SET NULL,
+ADD INDEX idx_account_date (account_id, date);
+
+CREATE TABLE card_statement_payments (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    user_id CHAR(36) NOT NULL,
+    card_account_id CHAR(36) NOT NULL,
+    statement_month DATE NOT NULL,
+    from_account_id CHAR(36) NOT NULL,
+    amount DECIMAL(12, 2) NOT NULL CHECK (amount > 0),
+    paid_at DATE NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (card_account_id) REFERENCES accounts(id) ON DELETE CASCADE,
+    FOREIGN KEY (from_account_id) REFERENCES accounts(id) ON DELETE CASCADE,
+    INDEX idx_card_month (card_account_id, statement_month)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- 12. Histórico de aportes das metas (retiradas são negativas)
+CREATE TABLE goal_contributions (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    goal_id CHAR(36) NOT NULL,
+    user_id CHAR(36) NOT NULL,
+    amount DECIMAL(12, 2) NOT NULL CHECK (amount <> 0),
+    note VARCHAR(255),
+    transaction_id CHAR(36) NULL,
+    contributed_at DATE NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (goal_id) REFERENCES goals(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (transaction_id) REFERENCES transactions(id) ON DELETE SET NULL,
+    INDEX idx_goal_date (goal_id, contributed_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- 13. Regras de poupança automática para as metas
+CREATE TABLE goal_autosave_rules (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    goal_id CHAR(36) NOT NULL,
+    user_id CHAR(36) NOT NULL,
+    rule_type VARCHAR(20) NOT NULL CHECK (rule_type IN ('round_up', 'percent', 'fixed')),
+    -- Múltiplo do arredondamento (round_up) ou valor por ocorrência (fixed)
+    amount DECIMAL(12, 2) NULL,
+    percent DECIMAL(5, 2) NULL,
+    transaction_type VARCHAR(10) NULL CHECK (transaction_type IN ('income', 'expense')),
+    category_id CHAR(36) NULL,
+    recurring_id CHAR(36) NULL,
+    active BOOLEAN DEFAULT TRUE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (goal_id) REFERENCES goals(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE,
+    FOREIGN KEY (recurring_id) REFERENCES recurring_transactions(id) ON DELETE CASCADE,
+    INDEX idx_user_active (user_id, active)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+ALTER TABLE goal_contributions
+ADD COLUMN autosave_rule_id CHAR(36) NULL AFTER transaction_id,
+ADD FOREIGN KEY (autosave_rule_id) REFERENCES goal_autosave_rules(id) ON DELETE SET NULL,
+ADD UNIQUE KEY unique_rule_transaction (autosave_rule_id, transaction_id);
+
+-- 14. Rendimento simulado das metas e taxas de referência
+-- Administradores mantêm as taxas de referência (reference_rates)
+ALTER TABLE users
+ADD COLUMN is_admin BOOLEAN NOT NULL DEFAULT FALSE AFTER envelope_start;
+
+ALTER TABLE goals
+ADD COLUMN investment_type VARCHAR(20) NULL CHECK (investment_type IN ('cdi_percent', 'fixed', 'poupanca')) AFTER icon,
+ADD COLUMN investment_rate DECIMAL(7, 2) NULL AFTER investment_type;
+
+CREATE TABLE reference_rates (
+    code VARCHAR(10) PRIMARY KEY CHECK (code IN ('cdi', 'selic', 'tr')),
+    annual_rate DECIMAL(8, 4) NOT NULL,
+    reference_date DATE NOT NULL,
+    updated_by CHAR(36) NULL,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (updated_by) REFERENCES users(id) ON DELETE SET NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+INSERT INTO reference_rates (code, annual_rate, reference_date) VALUES
+('selic', 15.0000, '2025-06-18'),
+('cdi', 14.9000, '2025-06-18'),
+('tr', 1.9000, '2025-06-18');
+
+-- 15. Metas compartilhadas com convidados
+CREATE TABLE goal_members (
+    goal_id CHAR(36) NOT NULL,
+    user_id CHAR(36) NOT NULL,
+    invited_by CHAR(36) NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (goal_id, user_id),
+    FOREIGN KEY (goal_id) REFERENCES goals(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (invited_by) REFERENCES users(id) ON DELETE SET NULL,
+    INDEX idx_user_id (user_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE goal_invitations (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    goal_id CHAR(36) NOT NULL,
+    email VARCHAR(255) NOT NULL,
+    status VARCHAR(10) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'accepted', 'declined', 'revoked')),
+    invited_by CHAR(36) NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    responded_at TIMESTAMP NULL,
+    FOREIGN KEY (goal_id) REFERENCES goals(id) ON DELETE CASCADE,
+    FOREIGN KEY (invited_by) REFERENCES users(id) ON DELETE SET NULL,
+    INDEX idx_email_status (email, status),
+    INDEX idx_goal_id (goal_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- 16. Espaços de trabalho compartilhados
+-- Cada usuário ganha o espaço pessoal (mesmo id do usuário) e os dados
+-- financeiros dele passam para esse espaço
+
+-- Tabelas dos espaços de trabalho
+CREATE TABLE workspaces (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    name VARCHAR(100) NOT NULL,
+    personal BOOLEAN NOT NULL DEFAULT FALSE,
+    created_by CHAR(36) NULL,
+    envelope_start DATE NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE workspace_members (
+    workspace_id CHAR(36) NOT NULL,
+    user_id CHAR(36) NOT NULL,
+    role VARCHAR(10) NOT NULL CHECK (role IN ('owner', 'editor', 'viewer')),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    PRIMARY KEY (workspace_id, user_id),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    INDEX idx_user_id (user_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE workspace_invitations (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    workspace_id CHAR(36) NOT NULL,
+    email VARCHAR(255) NOT NULL,
+    role VARCHAR(10) NOT NULL CHECK (role IN ('owner', 'editor', 'viewer')),
+    status VARCHAR(10) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'accepted', 'declined', 'revoked')),
+    invited_by CHAR(36) NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    responded_at TIMESTAMP NULL,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (invited_by) REFERENCES users(id) ON DELETE SET NULL,
+    INDEX idx_email_status (email, status),
+    INDEX idx_workspace_id (workspace_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Espaço pessoal e participação como owner para cada usuário; o início do
+-- modo envelope passa do usuário para o espaço
+INSERT INTO workspaces (id, name, personal, created_by, envelope_start)
+SELECT id, 'Pessoal', TRUE, id, envelope_start FROM users;
+
+INSERT INTO workspace_members (workspace_id, user_id, role)
+SELECT id, id, 'owner' FROM users;
+
+ALTER TABLE users DROP COLUMN envelope_start;
+
+-- Coluna workspace_id, ainda opcional, preenchida com o espaço pessoal do dono
+ALTER TABLE categories ADD COLUMN workspace_id CHAR(36) NULL AFTER id;
+ALTER TABLE accounts ADD COLUMN workspace_id CHAR(36) NULL AFTER id;
+ALTER TABLE installment_plans ADD COLUMN workspace_id CHAR(36) NULL AFTER id;
+ALTER TABLE transactions ADD COLUMN workspace_id CHAR(36) NULL AFTER id;
+ALTER TABLE goals ADD COLUMN workspace_id CHAR(36) NULL AFTER id;
+ALTER TABLE recurring_transactions ADD COLUMN workspace_id CHAR(36) NULL AFTER id;
+ALTER TABLE goal_autosave_rules ADD COLUMN workspace_id CHAR(36) NULL AFTER goal_id;
+ALTER TABLE budgets ADD COLUMN workspace_id CHAR(36) NULL AFTER id;
+ALTER TABLE envelope_assignments ADD COLUMN workspace_id CHAR(36) NULL AFTER id;
+
+-- Categorias padrão (sem user_id) continuam globais
+UPDATE categories SET workspace_id = user_id WHERE user_id IS NOT NULL;
+UPDATE accounts SET workspace_id = user_id;
+UPDATE installment_plans SET workspace_id = user_id;
+UPDATE transactions SET workspace_id = user_id;
+UPDATE goals SET workspace_id = user_id;
+UPDATE recurring_transactions SET workspace_id = user_id;
+-- As regras de poupança usam as transações de quem as criou
+UPDATE goal_autosave_rules SET workspace_id = user_id;
+UPDATE budgets SET workspace_id = user_id;
+UPDATE envelope_assignments SET workspace_id = user_id;
+
+-- Só agora as restrições: NOT NULL, chaves estrangeiras e índices
+ALTER TABLE categories
+ADD FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+ADD UNIQUE KEY unique_workspace_category (workspace_id, name),
+DROP INDEX unique_user_category;
+
+-- O autor da categoria pode sair do espaço sem apagá-la. Se o nome da chave
+-- for outro, confira com: SHOW CREATE TABLE categories;
+ALTER TABLE categories DROP FOREIGN KEY categories_ibfk_1;
+ALTER TABLE categories
+ADD FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE SET NULL;
+
+ALTER TABLE accounts
+MODIFY workspace_id CHAR(36) NOT NULL,
+ADD FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+ADD INDEX idx_workspace_id (workspace_id);
+
+ALTER TABLE installment_plans
+MODIFY workspace_id CHAR(36) NOT NULL,
+ADD FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+ADD INDEX idx_workspace_id (workspace_id);
+
+ALTER TABLE transactions
+MODIFY workspace_id CHAR(36) NOT NULL,
+ADD FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+ADD INDEX idx_workspace_id (workspace_id);
+
+ALTER TABLE goals
+MODIFY workspace_id CHAR(36) NOT NULL,
+ADD FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+ADD INDEX idx_workspace_id (workspace_id);
+
+ALTER TABLE recurring_transactions
+MODIFY workspace_id CHAR(36) NOT NULL,
+ADD FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+ADD INDEX idx_workspace_id (workspace_id);
+
+ALTER TABLE goal_autosave_rules
+MODIFY workspace_id CHAR(36) NOT NULL,
+ADD FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+ADD INDEX idx_workspace_active (workspace_id, active);
+
+ALTER TABLE budgets
+MODIFY workspace_id CHAR(36) NOT NULL,
+ADD FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+ADD UNIQUE KEY unique_workspace_budget (workspace_id, category_id),
+ADD INDEX idx_workspace_id (workspace_id),
+DROP INDEX unique_user_budget;
+
+ALTER TABLE envelope_assignments
+MODIFY workspace_id CHAR(36) NOT NULL,
+ADD FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+ADD INDEX idx_workspace_month (workspace_id, month);
+
+-- 17. Divisão de despesas e acertos
+CREATE TABLE split_contacts (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    user_id CHAR(36) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    UNIQUE KEY unique_user_contact (user_id, name)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE expense_splits (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    transaction_id CHAR(36) NOT NULL,
+    -- Quem pagou; os demais participantes devem a ele a própria parte
+    paid_by CHAR(36) NOT NULL,
+    method VARCHAR(20) NOT NULL CHECK (method IN ('equal', 'exact', 'percent')),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (transaction_id) REFERENCES transactions(id) ON DELETE CASCADE,
+    FOREIGN KEY (paid_by) REFERENCES users(id) ON DELETE CASCADE,
+    UNIQUE KEY unique_transaction_split (transaction_id),
+    INDEX idx_paid_by (paid_by)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE expense_split_shares (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    split_id CHAR(36) NOT NULL,
+    user_id CHAR(36) NULL,
+    contact_id CHAR(36) NULL,
+    amount DECIMAL(12, 2) NOT NULL CHECK (amount >= 0),
+    percent DECIMAL(5, 2) NULL,
+    FOREIGN KEY (split_id) REFERENCES expense_splits(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (contact_id) REFERENCES split_contacts(id) ON DELETE CASCADE,
+    INDEX idx_split_id (split_id),
+    INDEX idx_user_id (user_id),
+    INDEX idx_contact_id (contact_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE split_settlements (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    from_user_id CHAR(36) NULL,
+    from_contact_id CHAR(36) NULL,
+    to_user_id CHAR(36) NULL,
+    to_contact_id CHAR(36) NULL,
+    amount DECIMAL(12, 2) NOT NULL CHECK (amount > 0),
+    -- Receita ou despesa registrada para o acerto
+    transaction_id CHAR(36) NULL,
+    created_by CHAR(36) NOT NULL,
+    -- Pagamentos informados pelo devedor aguardam a confirmação do credor
+    status VARCHAR(10) NOT NULL DEFAULT 'confirmed' CHECK (status IN ('pending', 'confirmed', 'declined')),
+    settled_at DATE NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    responded_at TIMESTAMP NULL,
+    FOREIGN KEY (from_user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (from_contact_id) REFERENCES split_contacts(id) ON DELETE CASCADE,
+    FOREIGN KEY (to_user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (to_contact_id) REFERENCES split_contacts(id) ON DELETE CASCADE,
+    FOREIGN KEY (transaction_id) REFERENCES transactions(id) ON DELETE SET NULL,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE CASCADE,
+    INDEX idx_from_user (from_user_id),
+    INDEX idx_to_user (to_user_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- 18. Transações divididas entre categorias
+CREATE TABLE transaction_splits (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    transaction_id CHAR(36) NOT NULL,
+    category_id CHAR(36),
+    amount DECIMAL(12, 2) NOT NULL CHECK (amount > 0),
+    memo VARCHAR(255),
+    position INT UNSIGNED NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (transaction_id) REFERENCES transactions(id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE SET NULL,
+    INDEX idx_transaction_position (transaction_id, position),
+    INDEX idx_category_id (category_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Valores por categoria usados por relatórios e orçamentos
+CREATE VIEW transaction_lines AS
+SELECT t.id AS transaction_id, t.workspace_id, t.user_id, t.type, t.date, t.account_id,
+       t.category_id, t.amount, CAST(NULL AS CHAR(255)) AS memo
+FROM transactions t
+WHERE NOT EXISTS (SELECT 1 FROM transaction_splits s WHERE s.transaction_id = t.id)
+UNION ALL
+SELECT t.id, t.workspace_id, t.user_id, t.type, t.date, t.account_id,
+       s.category_id, s.amount, s.memo
+FROM transaction_splits s
+JOIN transactions t ON t.id = s.transaction_id;
+
+-- 19. Tags e observações das transações
+ALTER TABLE transactions
+ADD COLUMN notes TEXT AFTER description;
+
+CREATE TABLE tags (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    workspace_id CHAR(36) NOT NULL,
+    name VARCHAR(50) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    UNIQUE KEY unique_workspace_tag (workspace_id, name)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE transaction_tags (
+    transaction_id CHAR(36) NOT NULL,
+    tag_id CHAR(36) NOT NULL,
+    PRIMARY KEY (transaction_id, tag_id),
+    FOREIGN KEY (transaction_id) REFERENCES transactions(id) ON DELETE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE,
+    INDEX idx_tag_id (tag_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Verificar se funcionou
+SELECT 'Recorrências migradas:' as status;
+SELECT id, description, frequency, interval_count, start_date, last_generated
+FROM recurring_transactions LIMIT 5;
+
+SELECT 'Transactions migradas:' as status;
+SELECT id, description, occurrence_date, installment_plan_id, account_id FROM transactions LIMIT 5;
+
+-- As duas contagens abaixo devem ser zero
+SELECT 'Usuários sem espaço pessoal:' as status;
+SELECT COUNT(*) FROM users u
+WHERE NOT EXISTS (SELECT 1 FROM workspace_members m WHERE m.workspace_id = u.id AND m.user_id = u.id);
+
+SELECT 'Transações fora do espaço do dono:' as status;
+SELECT COUNT(*) FROM transactions WHERE workspace_id <> user_id;
+
+-- Pronto! Reinicie o servidor Rust depois da migração. Para promover um administrador:
+-- UPDATE users SET is_admin = TRUE WHERE email = 'admin@exemplo.com';
@@ -14,15 +14,61 @@ CREATE TABLE users (
     cpf VARCHAR(14) UNIQUE NOT NULL,
     birth_date DATE NOT NULL,
     phone VARCHAR(15),
+    timezone VARCHAR(6) NOT NULL DEFAULT '-03:00',
+    -- Administradores mantêm as taxas de referência (reference_rates)
+    is_admin BOOLEAN NOT NULL DEFAULT FALSE,
     created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
     updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
     INDEX idx_email (email),
     INDEX idx_cpf (cpf)
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
 
+-- Workspaces Table (donos dos dados financeiros; o espaço pessoal de cada
+-- usuário tem o mesmo id do usuário)
+CREATE TABLE workspaces (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    name VARCHAR(100) NOT NULL,
+    personal BOOLEAN NOT NULL DEFAULT FALSE,
+    created_by CHAR(36) NULL,
+    envelope_start DATE NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Workspace Members Table
+CREATE TABLE workspace_members (
+    workspace_id CHAR(36) NOT NULL,
+    user_id CHAR(36) NOT NULL,
+    role VARCHAR(10) NOT NULL CHECK (role IN ('owner', 'editor', 'viewer')),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    PRIMARY KEY (workspace_id, user_id),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    INDEX idx_user_id (user_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Workspace Invitations Table (convites por e-mail, aceitos pelo convidado)
+CREATE TABLE workspace_invitations (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    workspace_id CHAR(36) NOT NULL,
+    email VARCHAR(255) NOT NULL,
+    role VARCHAR(10) NOT NULL CHECK (role IN ('owner', 'editor', 'viewer')),
+    status VARCHAR(10) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'accepted', 'declined', 'revoked')),
+    invited_by CHAR(36) NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    responded_at TIMESTAMP NULL,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (invited_by) REFERENCES users(id) ON DELETE SET NULL,
+    INDEX idx_email_status (email, status),
+    INDEX idx_workspace_id (workspace_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
 -- Categories Table
 CREATE TABLE categories (
     id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    workspace_id CHAR(36),
     user_id CHAR(36),
     name VARCHAR(100) NOT NULL,
     icon VARCHAR(10) DEFAULT '💵',
@@ -31,69 +77,274 @@ CREATE TABLE categories (
     is_default BOOLEAN DEFAULT FALSE,
     created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
     updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
-    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
-    UNIQUE KEY unique_user_category (user_id, name),
-    INDEX idx_user_id (user_id),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE SET NULL,
+    UNIQUE KEY unique_workspace_category (workspace_id, name),
     INDEX idx_type (type)
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
 
+-- Accounts Table (contas e cartões de crédito)
+CREATE TABLE accounts (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    workspace_id CHAR(36) NOT NULL,
+    -- Quem criou o registro
+    user_id CHAR(36) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    type VARCHAR(20) NOT NULL CHECK (type IN ('checking', 'savings', 'credit_card', 'cash')),
+    closing_day TINYINT UNSIGNED NULL CHECK (closing_day BETWEEN 1 AND 31),
+    due_day TINYINT UNSIGNED NULL CHECK (due_day BETWEEN 1 AND 31),
+    credit_limit DECIMAL(12, 2) NULL CHECK (credit_limit >= 0),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    INDEX idx_workspace_id (workspace_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Installment Plans Table (compras parceladas)
+CREATE TABLE installment_plans (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    workspace_id CHAR(36) NOT NULL,
+    -- Quem criou o registro
+    user_id CHAR(36) NOT NULL,
+    description VARCHAR(255) NOT NULL,
+    total_amount DECIMAL(12, 2) NOT NULL CHECK (total_amount > 0),
+    installment_count INT UNSIGNED NOT NULL CHECK (installment_count >= 2),
+    type VARCHAR(20) NOT NULL DEFAULT 'expense' CHECK (type IN ('income', 'expense')),
+    category_id CHAR(36),
+    first_due_date DATE NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'active' CHECK (status IN ('active', 'prepaid', 'cancelled')),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE SET NULL,
+    INDEX idx_workspace_id (workspace_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
 -- Transactions Table
 CREATE TABLE transactions (
     id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    workspace_id CHAR(36) NOT NULL,
+    -- Quem criou o registro
     user_id CHAR(36) NOT NULL,
     description VARCHAR(255) NOT NULL,
+    notes TEXT,
     amount DECIMAL(12, 2) NOT NULL CHECK (amount > 0),
     type VARCHAR(20) NOT NULL CHECK (type IN ('income', 'expense')),
     category_id CHAR(36),
     date TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
     recurring BOOLEAN DEFAULT FALSE,
     recurring_id CHAR(36),
+    occurrence_date DATE NULL,
+    installment_plan_id CHAR(36) NULL,
+    installment_number INT UNSIGNED NULL,
+    account_id CHAR(36) NULL,
     created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
     updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
     FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
     FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE SET NULL,
-    INDEX idx_user_id (user_id),
+    FOREIGN KEY (installment_plan_id) REFERENCES installment_plans(id) ON DELETE CASCADE,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE SET NULL,
+    UNIQUE KEY unique_recurring_occurrence (recurring_id, occurrence_date),
+    INDEX idx_workspace_id (workspace_id),
     INDEX idx_date (date DESC),
     INDEX idx_type (type),
+    INDEX idx_category_id (category_id),
+    INDEX idx_account_date (account_id, date),
+    FULLTEXT INDEX ft_description (description)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Tags Table (marcadores livres do espaço de trabalho, como "férias 2026")
+CREATE TABLE tags (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    workspace_id CHAR(36) NOT NULL,
+    name VARCHAR(50) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    UNIQUE KEY unique_workspace_tag (workspace_id, name)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Transaction Tags Table
+CREATE TABLE transaction_tags (
+    transaction_id CHAR(36) NOT NULL,
+    tag_id CHAR(36) NOT NULL,
+    PRIMARY KEY (transaction_id, tag_id),
+    FOREIGN KEY (transaction_id) REFERENCES transactions(id) ON DELETE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE,
+    INDEX idx_tag_id (tag_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Transaction Splits Table (linhas de uma transação dividida entre categorias;
+-- somam exatamente o valor da transação)
+CREATE TABLE transaction_splits (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    transaction_id CHAR(36) NOT NULL,
+    category_id CHAR(36),
+    amount DECIMAL(12, 2) NOT NULL CHECK (amount > 0),
+    memo VARCHAR(255),
+    position INT UNSIGNED NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (transaction_id) REFERENCES transactions(id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE SET NULL,
+    INDEX idx_transaction_position (transaction_id, position),
     INDEX idx_category_id (category_id)
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
 
+-- Transaction Lines View (valores por categoria: as linhas das transações
+-- divididas e as demais transações inteiras; usada por relatórios e orçamentos)
+CREATE VIEW transaction_lines AS
+SELECT t.id AS transaction_id, t.workspace_id, t.user_id, t.type, t.date, t.account_id,
+       t.category_id, t.amount, CAST(NULL AS CHAR(255)) AS memo
+FROM transactions t
+WHERE NOT EXISTS (SELECT 1 FROM transaction_splits s WHERE s.transaction_id = t.id)
+UNION ALL
+SELECT t.id, t.workspace_id, t.user_id, t.type, t.date, t.account_id,
+       s.category_id, s.amount, s.memo
+FROM transaction_splits s
+JOIN transactions t ON t.id = s.transaction_id;
+
 -- Goals Table
 CREATE TABLE goals (
     id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    workspace_id CHAR(36) NOT NULL,
+    -- Quem criou o registro
     user_id CHAR(36) NOT NULL,
     name VARCHAR(255) NOT NULL,
     target_amount DECIMAL(12, 2) NOT NULL CHECK (target_amount > 0),
     current_amount DECIMAL(12, 2) DEFAULT 0 CHECK (current_amount >= 0),
     deadline DATE NOT NULL,
     icon VARCHAR(10) DEFAULT '🎯',
+    investment_type VARCHAR(20) NULL CHECK (investment_type IN ('cdi_percent', 'fixed', 'poupanca')),
+    investment_rate DECIMAL(7, 2) NULL,
     created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
     updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
     FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
-    INDEX idx_user_id (user_id),
+    INDEX idx_workspace_id (workspace_id),
     INDEX idx_deadline (deadline)
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
 
 -- Recurring Transactions Table
 CREATE TABLE recurring_transactions (
     id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    workspace_id CHAR(36) NOT NULL,
+    -- Quem criou o registro
     user_id CHAR(36) NOT NULL,
     description VARCHAR(255) NOT NULL,
     amount DECIMAL(12, 2) NOT NULL CHECK (amount > 0),
     type VARCHAR(20) NOT NULL CHECK (type IN ('income', 'expense')),
     category_id CHAR(36),
     frequency VARCHAR(20) NOT NULL CHECK (frequency IN ('daily', 'weekly', 'monthly', 'yearly')),
+    interval_count INT UNSIGNED NOT NULL DEFAULT 1 CHECK (interval_count >= 1),
+    rrule VARCHAR(255) NULL,
+    start_date DATE NOT NULL,
+    end_date DATE NULL,
+    max_occurrences INT UNSIGNED NULL CHECK (max_occurrences >= 1),
+    business_day_shift VARCHAR(10) NOT NULL DEFAULT 'none' CHECK (business_day_shift IN ('none', 'forward', 'backward')),
     active BOOLEAN DEFAULT TRUE,
-    last_generated TIMESTAMP NULL,
+    last_generated DATE NULL,
     created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
     updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
     FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
     FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE SET NULL,
-    INDEX idx_user_id (user_id),
+    INDEX idx_workspace_id (workspace_id),
     INDEX idx_active (active),
     INDEX idx_frequency (frequency)
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
 
+-- Recurring Exceptions Table (ocorrências puladas, remarcadas ou alteradas)
+CREATE TABLE recurring_exceptions (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    recurring_id CHAR(36) NOT NULL,
+    user_id CHAR(36) NOT NULL,
+    occurrence_date DATE NOT NULL,
+    skipped BOOLEAN NOT NULL DEFAULT FALSE,
+    new_date DATE NULL,
+    amount DECIMAL(12, 2) NULL CHECK (amount > 0),
+    description VARCHAR(255) NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (recurring_id) REFERENCES recurring_transactions(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    UNIQUE KEY unique_recurring_exception (recurring_id, occurrence_date),
+    INDEX idx_user_id (user_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Goal Members Table (metas compartilhadas com usuários de fora do espaço de trabalho da meta)
+CREATE TABLE goal_members (
+    goal_id CHAR(36) NOT NULL,
+    user_id CHAR(36) NOT NULL,
+    invited_by CHAR(36) NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (goal_id, user_id),
+    FOREIGN KEY (goal_id) REFERENCES goals(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (invited_by) REFERENCES users(id) ON DELETE SET NULL,
+    INDEX idx_user_id (user_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Goal Invitations Table (convites por e-mail para metas, aceitos pelo convidado)
+CREATE TABLE goal_invitations (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    goal_id CHAR(36) NOT NULL,
+    email VARCHAR(255) NOT NULL,
+    status VARCHAR(10) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'accepted', 'declined', 'revoked')),
+    invited_by CHAR(36) NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    responded_at TIMESTAMP NULL,
+    FOREIGN KEY (goal_id) REFERENCES goals(id) ON DELETE CASCADE,
+    FOREIGN KEY (invited_by) REFERENCES users(id) ON DELETE SET NULL,
+    INDEX idx_email_status (email, status),
+    INDEX idx_goal_id (goal_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Goal Auto-save Rules Table
+CREATE TABLE goal_autosave_rules (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    goal_id CHAR(36) NOT NULL,
+    -- Espaço de trabalho cujas transações alimentam a regra
+    workspace_id CHAR(36) NOT NULL,
+    user_id CHAR(36) NOT NULL,
+    rule_type VARCHAR(20) NOT NULL CHECK (rule_type IN ('round_up', 'percent', 'fixed')),
+    -- Múltiplo do arredondamento (round_up) ou valor por ocorrência (fixed)
+    amount DECIMAL(12, 2) NULL,
+    percent DECIMAL(5, 2) NULL,
+    transaction_type VARCHAR(10) NULL CHECK (transaction_type IN ('income', 'expense')),
+    category_id CHAR(36) NULL,
+    recurring_id CHAR(36) NULL,
+    active BOOLEAN DEFAULT TRUE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (goal_id) REFERENCES goals(id) ON DELETE CASCADE,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE,
+    FOREIGN KEY (recurring_id) REFERENCES recurring_transactions(id) ON DELETE CASCADE,
+    INDEX idx_workspace_active (workspace_id, active)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Goal Contributions Table (histórico de aportes; retiradas são negativas)
+CREATE TABLE goal_contributions (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    goal_id CHAR(36) NOT NULL,
+    user_id CHAR(36) NOT NULL,
+    amount DECIMAL(12, 2) NOT NULL CHECK (amount <> 0),
+    note VARCHAR(255),
+    transaction_id CHAR(36) NULL,
+    autosave_rule_id CHAR(36) NULL,
+    contributed_at DATE NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (goal_id) REFERENCES goals(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (transaction_id) REFERENCES transactions(id) ON DELETE SET NULL,
+    FOREIGN KEY (autosave_rule_id) REFERENCES goal_autosave_rules(id) ON DELETE SET NULL,
+    INDEX idx_goal_date (goal_id, contributed_at),
+    UNIQUE KEY unique_rule_transaction (autosave_rule_id, transaction_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
 -- Notifications Table
 CREATE TABLE notifications (
     id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
@@ -109,7 +360,152 @@ CREATE TABLE notifications (
     INDEX idx_created_at (created_at DESC)
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
 
--- Insert default categories (sem user_id para serem globais)
+-- Notification Events Table (evita notificações automáticas repetidas)
+CREATE TABLE notification_events (
+    user_id CHAR(36) NOT NULL,
+    event_key VARCHAR(191) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, event_key),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Budgets Table
+CREATE TABLE budgets (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    workspace_id CHAR(36) NOT NULL,
+    -- Quem criou o registro
+    user_id CHAR(36) NOT NULL,
+    category_id CHAR(36) NOT NULL,
+    amount DECIMAL(12, 2) NOT NULL CHECK (amount > 0),
+    rollover BOOLEAN DEFAULT FALSE,
+    start_month DATE NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE,
+    UNIQUE KEY unique_workspace_budget (workspace_id, category_id),
+    INDEX idx_workspace_id (workspace_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Envelope Assignments Table (orçamento base zero)
+CREATE TABLE envelope_assignments (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    workspace_id CHAR(36) NOT NULL,
+    -- Quem criou o registro
+    user_id CHAR(36) NOT NULL,
+    category_id CHAR(36) NOT NULL,
+    month DATE NOT NULL,
+    amount DECIMAL(12, 2) NOT NULL CHECK (amount <> 0),
+    note VARCHAR(255),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE,
+    INDEX idx_workspace_month (workspace_id, month),
+    INDEX idx_category_id (category_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Card Statement Payments Table (pagamentos de fatura; não são transações
+-- para que as compras no cartão não sejam contadas duas vezes)
+CREATE TABLE card_statement_payments (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    user_id CHAR(36) NOT NULL,
+    card_account_id CHAR(36) NOT NULL,
+    statement_month DATE NOT NULL,
+    from_account_id CHAR(36) NOT NULL,
+    amount DECIMAL(12, 2) NOT NULL CHECK (amount > 0),
+    paid_at DATE NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (card_account_id) REFERENCES accounts(id) ON DELETE CASCADE,
+    FOREIGN KEY (from_account_id) REFERENCES accounts(id) ON DELETE CASCADE,
+    INDEX idx_card_month (card_account_id, statement_month)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Split Contacts Table (pessoas sem cadastro que participam de despesas divididas)
+CREATE TABLE split_contacts (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    user_id CHAR(36) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    UNIQUE KEY unique_user_contact (user_id, name)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Expense Splits Table (uma despesa paga por um usuário e dividida entre participantes)
+CREATE TABLE expense_splits (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    transaction_id CHAR(36) NOT NULL,
+    -- Quem pagou; os demais participantes devem a ele a própria parte
+    paid_by CHAR(36) NOT NULL,
+    method VARCHAR(20) NOT NULL CHECK (method IN ('equal', 'exact', 'percent')),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (transaction_id) REFERENCES transactions(id) ON DELETE CASCADE,
+    FOREIGN KEY (paid_by) REFERENCES users(id) ON DELETE CASCADE,
+    UNIQUE KEY unique_transaction_split (transaction_id),
+    INDEX idx_paid_by (paid_by)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Expense Split Shares Table (parte de cada participante: usuário ou contato)
+CREATE TABLE expense_split_shares (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    split_id CHAR(36) NOT NULL,
+    user_id CHAR(36) NULL,
+    contact_id CHAR(36) NULL,
+    amount DECIMAL(12, 2) NOT NULL CHECK (amount >= 0),
+    percent DECIMAL(5, 2) NULL,
+    FOREIGN KEY (split_id) REFERENCES expense_splits(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (contact_id) REFERENCES split_contacts(id) ON DELETE CASCADE,
+    INDEX idx_split_id (split_id),
+    INDEX idx_user_id (user_id),
+    INDEX idx_contact_id (contact_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Split Settlements Table (acertos: `from` pagou `amount` a `to`)
+CREATE TABLE split_settlements (
+    id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+    from_user_id CHAR(36) NULL,
+    from_contact_id CHAR(36) NULL,
+    to_user_id CHAR(36) NULL,
+    to_contact_id CHAR(36) NULL,
+    amount DECIMAL(12, 2) NOT NULL CHECK (amount > 0),
+    -- Receita ou despesa registrada para o acerto
+    transaction_id CHAR(36) NULL,
+    created_by CHAR(36) NOT NULL,
+    -- Pagamentos informados pelo devedor aguardam a confirmação do credor
+    status VARCHAR(10) NOT NULL DEFAULT 'confirmed' CHECK (status IN ('pending', 'confirmed', 'declined')),
+    settled_at DATE NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    responded_at TIMESTAMP NULL,
+    FOREIGN KEY (from_user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (from_contact_id) REFERENCES split_contacts(id) ON DELETE CASCADE,
+    FOREIGN KEY (to_user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (to_contact_id) REFERENCES split_contacts(id) ON DELETE CASCADE,
+    FOREIGN KEY (transaction_id) REFERENCES transactions(id) ON DELETE SET NULL,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE CASCADE,
+    INDEX idx_from_user (from_user_id),
+    INDEX idx_to_user (to_user_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Reference Rates Table (taxas anuais em %, mantidas pelos administradores)
+CREATE TABLE reference_rates (
+    code VARCHAR(10) PRIMARY KEY CHECK (code IN ('cdi', 'selic', 'tr')),
+    annual_rate DECIMAL(8, 4) NOT NULL,
+    reference_date DATE NOT NULL,
+    updated_by CHAR(36) NULL,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (updated_by) REFERENCES users(id) ON DELETE SET NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+INSERT INTO reference_rates (code, annual_rate, reference_date) VALUES
+('selic', 15.0000, '2025-06-18'),
+('cdi', 14.9000, '2025-06-18'),
+('tr', 1.9000, '2025-06-18');
+
+-- Insert default categories (sem workspace_id para serem globais)
 INSERT INTO categories (id, user_id, name, icon, color, type, is_default) VALUES
 (UUID(), NULL, 'Alimentação', '🍔', '#ff6b6b', 'expense', TRUE),
 (UUID(), NULL, 'Transporte', '🚗', '#4ecdc4', 'expense', TRUE),
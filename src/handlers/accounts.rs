@@ -0,0 +1,826 @@
+use actix_web::{web, HttpResponse, Responder};
+use chrono::{Datelike, Months, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, MySqlPool};
+use validator::Validate;
+
+use crate::handlers::notifications::notify_workspace_once;
+use crate::middleware::workspace::Workspace;
+use crate::models::Transaction;
+use crate::utils::parse_month;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Account {
+    pub id: String,
+    pub workspace_id: String,
+    pub user_id: String,
+    pub name: String,
+    #[sqlx(rename = "type")]
+    pub account_type: String,
+    // Somente cartões de crédito: dia de fechamento e de vencimento da fatura
+    pub closing_day: Option<u32>,
+    pub due_day: Option<u32>,
+    pub credit_limit: Option<Decimal>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct AccountWithBalance {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub account: Account,
+    // Em cartões, o saldo negativo é o valor devido
+    pub balance: Decimal,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateAccount {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(custom = "validate_account_type")]
+    pub account_type: String,
+    #[validate(range(min = 1, max = 31))]
+    pub closing_day: Option<u32>,
+    #[validate(range(min = 1, max = 31))]
+    pub due_day: Option<u32>,
+    pub credit_limit: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateAccount {
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    #[validate(range(min = 1, max = 31))]
+    pub closing_day: Option<u32>,
+    #[validate(range(min = 1, max = 31))]
+    pub due_day: Option<u32>,
+    pub credit_limit: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatementsQuery {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PayStatement {
+    pub from_account_id: String,
+    pub amount: Option<f64>,
+    pub date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct StatementPayment {
+    pub id: String,
+    pub from_account_id: String,
+    pub amount: Decimal,
+    pub paid_at: NaiveDate,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+// Datas de uma fatura: as compras de [period_start, closing_date) entram nela
+#[derive(Debug, Clone, Copy)]
+pub struct StatementDates {
+    pub due_month: NaiveDate,
+    pub period_start: NaiveDate,
+    pub closing_date: NaiveDate,
+    pub due_date: NaiveDate,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Statement {
+    // Mês de vencimento (YYYY-MM), que identifica a fatura
+    pub month: String,
+    pub period_start: NaiveDate,
+    pub closing_date: NaiveDate,
+    pub due_date: NaiveDate,
+    pub expenses: Decimal,
+    // Estornos e créditos lançados no cartão
+    pub credits: Decimal,
+    pub total: Decimal,
+    pub paid: Decimal,
+    pub remaining: Decimal,
+    // open, closed, paid ou overdue
+    pub status: &'static str,
+    pub transaction_count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatementDetail {
+    #[serde(flatten)]
+    pub statement: Statement,
+    pub transactions: Vec<Transaction>,
+    pub payments: Vec<StatementPayment>,
+}
+
+const ACCOUNT_TYPES: [&str; 4] = ["checking", "savings", "credit_card", "cash"];
+const MAX_STATEMENTS: usize = 24;
+const DEFAULT_STATEMENTS: u32 = 6;
+// Antecedência do aviso de vencimento da fatura
+const DUE_REMINDER_DAYS: i64 = 3;
+
+fn validate_account_type(value: &str) -> Result<(), validator::ValidationError> {
+    if ACCOUNT_TYPES.contains(&value) {
+        Ok(())
+    } else {
+        Err(validator::ValidationError::new("invalid_account_type"))
+    }
+}
+
+// Dia `day` do mês iniciado em `month`; meses mais curtos usam o último dia
+fn day_in_month(month: NaiveDate, day: u32) -> NaiveDate {
+    (1..=day)
+        .rev()
+        .find_map(|day| month.with_day(day))
+        .unwrap_or(month)
+}
+
+impl Account {
+    pub fn is_credit_card(&self) -> bool {
+        self.account_type == "credit_card"
+    }
+
+    // Fatura que vence no mês `due_month` (primeiro dia do mês)
+    pub fn statement_dates(&self, due_month: NaiveDate) -> Option<StatementDates> {
+        let (closing_day, due_day) = (self.closing_day?, self.due_day?);
+
+        // Vencimento depois do fechamento no mesmo mês, ou no mês seguinte
+        let closing_month = if due_day > closing_day {
+            due_month
+        } else {
+            due_month - Months::new(1)
+        };
+
+        Some(StatementDates {
+            due_month,
+            period_start: day_in_month(closing_month - Months::new(1), closing_day),
+            closing_date: day_in_month(closing_month, closing_day),
+            due_date: day_in_month(due_month, due_day),
+        })
+    }
+
+    // Fatura em que entra uma compra feita em `date`. Compras no dia do
+    // fechamento já vão para a fatura seguinte.
+    pub fn statement_for_date(&self, date: NaiveDate) -> Option<StatementDates> {
+        let (closing_day, due_day) = (self.closing_day?, self.due_day?);
+        let month = date.with_day(1)?;
+
+        let closing_month = if date < day_in_month(month, closing_day) {
+            month
+        } else {
+            month + Months::new(1)
+        };
+        let due_month = if due_day > closing_day {
+            closing_month
+        } else {
+            closing_month + Months::new(1)
+        };
+
+        self.statement_dates(due_month)
+    }
+}
+
+fn database_error(e: sqlx::Error) -> HttpResponse {
+    eprintln!("Database error: {}", e);
+    HttpResponse::InternalServerError().json(serde_json::json!({
+        "error": "Database error"
+    }))
+}
+
+fn account_not_found() -> HttpResponse {
+    HttpResponse::NotFound().json(serde_json::json!({
+        "error": "Account not found"
+    }))
+}
+
+pub async fn account_exists(
+    pool: &MySqlPool,
+    workspace_id: &str,
+    account_id: &str,
+) -> Result<bool, sqlx::Error> {
+    let count = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM accounts WHERE id = ? AND workspace_id = ?"
+    )
+    .bind(account_id)
+    .bind(workspace_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count > 0)
+}
+
+async fn find_account(
+    pool: &MySqlPool,
+    account_id: &str,
+    workspace_id: &str,
+) -> Result<Option<Account>, sqlx::Error> {
+    sqlx::query_as::<_, Account>("SELECT * FROM accounts WHERE id = ? AND workspace_id = ?")
+        .bind(account_id)
+        .bind(workspace_id)
+        .fetch_optional(pool)
+        .await
+}
+
+// Monta a fatura com as transações e os pagamentos do período
+async fn load_statement(
+    pool: &MySqlPool,
+    account: &Account,
+    dates: StatementDates,
+    today: NaiveDate,
+) -> Result<StatementDetail, sqlx::Error> {
+    let transactions = sqlx::query_as::<_, Transaction>(
+        "SELECT * FROM transactions
+         WHERE account_id = ? AND date >= ? AND date < ?
+         ORDER BY date"
+    )
+    .bind(&account.id)
+    .bind(dates.period_start.and_hms_opt(0, 0, 0).unwrap())
+    .bind(dates.closing_date.and_hms_opt(0, 0, 0).unwrap())
+    .fetch_all(pool)
+    .await?;
+
+    let payments = sqlx::query_as::<_, StatementPayment>(
+        "SELECT id, from_account_id, amount, paid_at, created_at FROM card_statement_payments
+         WHERE card_account_id = ? AND statement_month = ?
+         ORDER BY paid_at"
+    )
+    .bind(&account.id)
+    .bind(dates.due_month)
+    .fetch_all(pool)
+    .await?;
+
+    let expenses: Decimal = transactions
+        .iter()
+        .filter(|t| t.transaction_type == "expense")
+        .map(|t| t.amount)
+        .sum();
+    let credits: Decimal = transactions
+        .iter()
+        .filter(|t| t.transaction_type == "income")
+        .map(|t| t.amount)
+        .sum();
+    let paid: Decimal = payments.iter().map(|p| p.amount).sum();
+    let total = expenses - credits;
+    let remaining = (total - paid).max(Decimal::ZERO);
+
+    let status = if today < dates.closing_date {
+        "open"
+    } else if remaining.is_zero() {
+        "paid"
+    } else if today > dates.due_date {
+        "overdue"
+    } else {
+        "closed"
+    };
+
+    Ok(StatementDetail {
+        statement: Statement {
+            month: dates.due_month.format("%Y-%m").to_string(),
+            period_start: dates.period_start,
+            closing_date: dates.closing_date,
+            due_date: dates.due_date,
+            expenses,
+            credits,
+            total,
+            paid,
+            remaining,
+            status,
+            transaction_count: transactions.len(),
+        },
+        transactions,
+        payments,
+    })
+}
+
+// GET /api/accounts - Listar contas com saldo
+pub async fn get_all(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+) -> impl Responder {
+    let accounts = sqlx::query_as::<_, AccountWithBalance>(
+        "SELECT a.*,
+                COALESCE((SELECT SUM(CASE WHEN t.type = 'income' THEN t.amount ELSE -t.amount END)
+                          FROM transactions t WHERE t.account_id = a.id), 0)
+                - COALESCE((SELECT SUM(p.amount) FROM card_statement_payments p
+                            WHERE p.from_account_id = a.id), 0)
+                + COALESCE((SELECT SUM(p.amount) FROM card_statement_payments p
+                            WHERE p.card_account_id = a.id), 0) AS balance
+         FROM accounts a
+         WHERE a.workspace_id = ?
+         ORDER BY a.name"
+    )
+    .bind(&workspace.id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match accounts {
+        Ok(accounts) => HttpResponse::Ok().json(accounts),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to fetch accounts"
+            }))
+        }
+    }
+}
+
+// POST /api/accounts - Criar conta
+pub async fn create(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    account_data: web::Json<CreateAccount>,
+) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    if let Err(errors) = account_data.validate() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Validation failed",
+            "details": errors.to_string()
+        }));
+    }
+
+    let is_card = account_data.account_type == "credit_card";
+    if is_card && (account_data.closing_day.is_none() || account_data.due_day.is_none()) {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Credit cards require closing_day and due_day"
+        }));
+    }
+
+    let credit_limit = match account_data.credit_limit {
+        Some(value) => match Decimal::from_f64_retain(value) {
+            Some(limit) if value >= 0.0 => Some(limit.round_dp(2)),
+            _ => {
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": "Invalid credit_limit"
+                }));
+            }
+        },
+        None => None,
+    };
+
+    let account_id = uuid::Uuid::new_v4().to_string();
+
+    // Fechamento, vencimento e limite só fazem sentido para cartões
+    let result = sqlx::query(
+        "INSERT INTO accounts (id, workspace_id, user_id, name, type, closing_day, due_day, credit_limit)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+    )
+    .bind(&account_id)
+    .bind(&workspace.id)
+    .bind(&workspace.user_id)
+    .bind(&account_data.name)
+    .bind(&account_data.account_type)
+    .bind(account_data.closing_day.filter(|_| is_card))
+    .bind(account_data.due_day.filter(|_| is_card))
+    .bind(credit_limit.filter(|_| is_card))
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(_) => match find_account(pool.get_ref(), &account_id, &workspace.id).await {
+            Ok(Some(account)) => HttpResponse::Created().json(account),
+            Ok(None) => account_not_found(),
+            Err(e) => database_error(e),
+        },
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to create account"
+            }))
+        }
+    }
+}
+
+// PUT /api/accounts/{id} - Atualizar conta
+pub async fn update(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    account_id: web::Path<String>,
+    update_data: web::Json<UpdateAccount>,
+) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    if let Err(errors) = update_data.validate() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Validation failed",
+            "details": errors.to_string()
+        }));
+    }
+
+    let account = match find_account(pool.get_ref(), &account_id, &workspace.id).await {
+        Ok(Some(account)) => account,
+        Ok(None) => return account_not_found(),
+        Err(e) => return database_error(e),
+    };
+
+    let card_fields = update_data.closing_day.is_some()
+        || update_data.due_day.is_some()
+        || update_data.credit_limit.is_some();
+    if card_fields && !account.is_credit_card() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "closing_day, due_day and credit_limit apply only to credit cards"
+        }));
+    }
+
+    let credit_limit = match update_data.credit_limit {
+        Some(value) => match Decimal::from_f64_retain(value) {
+            Some(limit) if value >= 0.0 => Some(limit.round_dp(2)),
+            _ => {
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": "Invalid credit_limit"
+                }));
+            }
+        },
+        None => None,
+    };
+
+    let result = sqlx::query(
+        "UPDATE accounts
+         SET name = COALESCE(?, name), closing_day = COALESCE(?, closing_day),
+             due_day = COALESCE(?, due_day), credit_limit = COALESCE(?, credit_limit)
+         WHERE id = ? AND workspace_id = ?"
+    )
+    .bind(&update_data.name)
+    .bind(update_data.closing_day)
+    .bind(update_data.due_day)
+    .bind(credit_limit)
+    .bind(&account.id)
+    .bind(&workspace.id)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(_) => HttpResponse::Ok().json(serde_json::json!({
+            "message": "Account updated successfully"
+        })),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to update account"
+            }))
+        }
+    }
+}
+
+// DELETE /api/accounts/{id} - Excluir conta (as transações ficam sem conta)
+pub async fn delete(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    account_id: web::Path<String>,
+) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    let result = sqlx::query("DELETE FROM accounts WHERE id = ? AND workspace_id = ?")
+        .bind(account_id.into_inner())
+        .bind(&workspace.id)
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(result) if result.rows_affected() > 0 => {
+            HttpResponse::Ok().json(serde_json::json!({
+                "message": "Account deleted successfully"
+            }))
+        }
+        Ok(_) => account_not_found(),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to delete account"
+            }))
+        }
+    }
+}
+
+fn missing_card_days() -> HttpResponse {
+    HttpResponse::BadRequest().json(serde_json::json!({
+        "error": "Credit card has no closing_day or due_day"
+    }))
+}
+
+async fn find_card(
+    pool: &MySqlPool,
+    account_id: &str,
+    workspace_id: &str,
+) -> Result<Account, HttpResponse> {
+    match find_account(pool, account_id, workspace_id).await {
+        Ok(Some(account)) if account.is_credit_card() => Ok(account),
+        Ok(Some(_)) => Err(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Account is not a credit card"
+        }))),
+        Ok(None) => Err(account_not_found()),
+        Err(e) => Err(database_error(e)),
+    }
+}
+
+fn statement_month(month: &str) -> Result<NaiveDate, HttpResponse> {
+    parse_month(month).map(|(start, _)| start).ok_or_else(|| {
+        HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Invalid month, expected YYYY-MM"
+        }))
+    })
+}
+
+// GET /api/accounts/{id}/statements?from=YYYY-MM&to=YYYY-MM - Faturas do cartão
+pub async fn statements(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    account_id: web::Path<String>,
+    query: web::Query<StatementsQuery>,
+) -> impl Responder {
+    let account = match find_card(pool.get_ref(), &account_id, &workspace.id).await {
+        Ok(account) => account,
+        Err(response) => return response,
+    };
+
+    let today = Utc::now().date_naive();
+    // Padrão: a fatura aberta e as cinco anteriores
+    let Some(current) = account.statement_for_date(today).map(|dates| dates.due_month) else {
+        return missing_card_days();
+    };
+
+    let to = match &query.to {
+        Some(month) => match statement_month(month) {
+            Ok(month) => month,
+            Err(response) => return response,
+        },
+        None => current,
+    };
+    let from = match &query.from {
+        Some(month) => match statement_month(month) {
+            Ok(month) => month,
+            Err(response) => return response,
+        },
+        None => to - Months::new(DEFAULT_STATEMENTS - 1),
+    };
+
+    if from > to {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "'from' must not be after 'to'"
+        }));
+    }
+
+    let months: Vec<NaiveDate> = (0..)
+        .map(|n| from + Months::new(n))
+        .take_while(|month| *month <= to)
+        .take(MAX_STATEMENTS + 1)
+        .collect();
+    if months.len() > MAX_STATEMENTS {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Too many statements requested, maximum is 24"
+        }));
+    }
+
+    let mut statements = Vec::new();
+    for month in months.into_iter().rev() {
+        let Some(dates) = account.statement_dates(month) else {
+            continue;
+        };
+        match load_statement(pool.get_ref(), &account, dates, today).await {
+            Ok(detail) => statements.push(detail.statement),
+            Err(e) => return database_error(e),
+        }
+    }
+
+    HttpResponse::Ok().json(statements)
+}
+
+// GET /api/accounts/{id}/statements/{month} - Detalhe da fatura
+pub async fn statement(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let (account_id, month) = path.into_inner();
+
+    let account = match find_card(pool.get_ref(), &account_id, &workspace.id).await {
+        Ok(account) => account,
+        Err(response) => return response,
+    };
+    let month = match statement_month(&month) {
+        Ok(month) => month,
+        Err(response) => return response,
+    };
+
+    let Some(dates) = account.statement_dates(month) else {
+        return missing_card_days();
+    };
+
+    match load_statement(pool.get_ref(), &account, dates, Utc::now().date_naive()).await {
+        Ok(detail) => HttpResponse::Ok().json(detail),
+        Err(e) => database_error(e),
+    }
+}
+
+// Saldo restante da fatura com a linha do cartão bloqueada (FOR UPDATE), para
+// que pagamentos concorrentes não paguem a mesma fatura duas vezes
+async fn locked_statement_remaining(
+    tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+    card_id: &str,
+    dates: StatementDates,
+) -> Result<Decimal, sqlx::Error> {
+    sqlx::query("SELECT id FROM accounts WHERE id = ? FOR UPDATE")
+        .bind(card_id)
+        .execute(&mut **tx)
+        .await?;
+
+    let total = sqlx::query_scalar::<_, Decimal>(
+        "SELECT COALESCE(SUM(CASE WHEN type = 'expense' THEN amount ELSE -amount END), 0)
+         FROM transactions
+         WHERE account_id = ? AND date >= ? AND date < ?"
+    )
+    .bind(card_id)
+    .bind(dates.period_start.and_hms_opt(0, 0, 0).unwrap())
+    .bind(dates.closing_date.and_hms_opt(0, 0, 0).unwrap())
+    .fetch_one(&mut **tx)
+    .await?;
+
+    let paid = sqlx::query_scalar::<_, Decimal>(
+        "SELECT COALESCE(SUM(amount), 0) FROM card_statement_payments
+         WHERE card_account_id = ? AND statement_month = ?"
+    )
+    .bind(card_id)
+    .bind(dates.due_month)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    Ok((total - paid).max(Decimal::ZERO))
+}
+
+// POST /api/accounts/{id}/statements/{month}/pay - Pagar a fatura com outra conta
+pub async fn pay_statement(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    path: web::Path<(String, String)>,
+    payment_data: web::Json<PayStatement>,
+) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    let (account_id, month) = path.into_inner();
+
+    let card = match find_card(pool.get_ref(), &account_id, &workspace.id).await {
+        Ok(account) => account,
+        Err(response) => return response,
+    };
+    let month = match statement_month(&month) {
+        Ok(month) => month,
+        Err(response) => return response,
+    };
+
+    match find_account(pool.get_ref(), &payment_data.from_account_id, &workspace.id).await {
+        Ok(Some(source)) if source.is_credit_card() || source.id == card.id => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Statements must be paid from a non credit card account"
+            }));
+        }
+        Ok(Some(_)) => {}
+        Ok(None) => return account_not_found(),
+        Err(e) => return database_error(e),
+    }
+
+    let today = Utc::now().date_naive();
+    let Some(dates) = card.statement_dates(month) else {
+        return missing_card_days();
+    };
+    let detail = match load_statement(pool.get_ref(), &card, dates, today).await {
+        Ok(detail) => detail,
+        Err(e) => return database_error(e),
+    };
+
+    let remaining = detail.statement.remaining;
+    if remaining.is_zero() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Statement is already paid"
+        }));
+    }
+
+    // Sem valor, paga o saldo restante da fatura
+    let amount = match payment_data.amount {
+        Some(value) => match Decimal::from_f64_retain(value) {
+            Some(amount) if value > 0.0 => amount.round_dp(2),
+            _ => {
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": "Invalid amount"
+                }));
+            }
+        },
+        None => remaining,
+    };
+
+    if amount > remaining {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Amount exceeds the statement balance"
+        }));
+    }
+
+    // O saldo é conferido de novo dentro da transação, com o cartão bloqueado
+    let result = async {
+        let mut tx = pool.begin().await?;
+
+        if amount > locked_statement_remaining(&mut tx, &card.id, dates).await? {
+            return Ok(false);
+        }
+
+        sqlx::query(
+            "INSERT INTO card_statement_payments (id, user_id, card_account_id, statement_month, from_account_id, amount, paid_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(&workspace.user_id)
+        .bind(&card.id)
+        .bind(dates.due_month)
+        .bind(&payment_data.from_account_id)
+        .bind(amount)
+        .bind(payment_data.date.unwrap_or(today))
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(true)
+    }
+    .await;
+
+    match result {
+        Ok(true) => {}
+        Ok(false) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Amount exceeds the statement balance"
+            }));
+        }
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to pay statement"
+            }));
+        }
+    }
+
+    match load_statement(pool.get_ref(), &card, dates, today).await {
+        Ok(detail) => HttpResponse::Ok().json(detail),
+        Err(e) => database_error(e),
+    }
+}
+
+// Avisa uma única vez sobre cada fatura em aberto que vence nos próximos dias.
+// Retorna quantos avisos foram criados.
+pub async fn send_due_reminders(pool: &MySqlPool, today: NaiveDate) -> Result<u32, sqlx::Error> {
+    let cards = sqlx::query_as::<_, Account>(
+        "SELECT * FROM accounts WHERE type = 'credit_card'"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut sent = 0;
+    for card in &cards {
+        let Some(current) = card.statement_for_date(today) else {
+            continue;
+        };
+
+        // A fatura fechada mais recente vence antes da que está aberta
+        for due_month in [current.due_month - Months::new(1), current.due_month] {
+            let Some(dates) = card.statement_dates(due_month) else {
+                continue;
+            };
+            let days_left = (dates.due_date - today).num_days();
+            if !(0..=DUE_REMINDER_DAYS).contains(&days_left) {
+                continue;
+            }
+
+            let detail = load_statement(pool, card, dates, today).await?;
+            if detail.statement.remaining.is_zero() {
+                continue;
+            }
+
+            let event_key = format!("card_due:{}:{}", card.id, detail.statement.month);
+            let message = format!(
+                "A fatura de {} no valor de R$ {} vence em {}.",
+                card.name,
+                detail.statement.remaining,
+                dates.due_date.format("%d/%m/%Y")
+            );
+
+            sent += notify_workspace_once(
+                pool,
+                &card.workspace_id,
+                &event_key,
+                "Fatura próxima do vencimento",
+                &message,
+                "warning",
+            )
+            .await?;
+        }
+    }
+
+    Ok(sent)
+}
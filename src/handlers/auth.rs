@@ -4,8 +4,9 @@ use serde::Deserialize;
 use sqlx::MySqlPool;
 use validator::Validate;
 
+use crate::middleware::workspace::ensure_personal_workspace;
 use crate::models::{LoginRequest, RegisterRequest, User};
-use crate::utils::{create_jwt, hash_password, validate_cpf, verify_password};
+use crate::utils::{create_jwt, hash_password, parse_utc_offset, validate_cpf, verify_password};
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateProfile {
@@ -13,6 +14,7 @@ pub struct UpdateProfile {
     pub email: Option<String>,
     pub phone: Option<String>,
     pub birth_date: Option<NaiveDate>,
+    pub timezone: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -95,6 +97,11 @@ pub async fn register(
     .execute(pool.get_ref())
     .await;
 
+    let result = match result {
+        Ok(_) => ensure_personal_workspace(pool.get_ref(), &user_id).await,
+        Err(e) => Err(e),
+    };
+
     match result {
         Ok(_) => {
             let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = ?")
@@ -225,6 +232,14 @@ pub async fn update_profile(
     if let Some(birth_date) = update_data.birth_date {
         updates.push(format!("birth_date = '{}'", birth_date));
     }
+    if let Some(timezone) = &update_data.timezone {
+        if parse_utc_offset(timezone).is_none() {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Invalid timezone, expected an offset like -03:00"
+            }));
+        }
+        updates.push(format!("timezone = '{}'", timezone));
+    }
 
     if updates.is_empty() {
         return HttpResponse::BadRequest().json(serde_json::json!({
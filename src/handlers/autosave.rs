@@ -0,0 +1,530 @@
+use actix_web::{web, HttpResponse, Responder};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, MySqlPool};
+use validator::Validate;
+
+use crate::handlers::goals::{
+    check_milestones, find_goal, record_contribution, record_opening_balance,
+    refresh_current_amount, ContributionOutcome, Goal, NewContribution,
+};
+use crate::middleware::workspace::Workspace;
+use crate::models::Transaction;
+
+// Regras de "poupar automaticamente" ligadas a uma meta:
+// - round_up: arredonda cada despesa para o próximo múltiplo de `amount` (padrão R$ 1)
+// - percent: guarda `percent`% de cada receita (ou despesa, com transaction_type)
+// - fixed: guarda `amount` a cada ocorrência de transação recorrente
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct AutosaveRule {
+    pub id: String,
+    pub goal_id: String,
+    // Espaço de trabalho cujas transações alimentam a regra
+    pub workspace_id: String,
+    pub user_id: String,
+    pub rule_type: String,
+    pub amount: Option<Decimal>,
+    pub percent: Option<Decimal>,
+    pub transaction_type: Option<String>,
+    pub category_id: Option<String>,
+    pub recurring_id: Option<String>,
+    pub active: bool,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateAutosaveRule {
+    #[validate(custom = "validate_rule_type")]
+    pub rule_type: String,
+    pub amount: Option<f64>,
+    pub percent: Option<f64>,
+    #[validate(custom = "validate_transaction_type")]
+    pub transaction_type: Option<String>,
+    pub category_id: Option<String>,
+    pub recurring_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateAutosaveRule {
+    pub amount: Option<f64>,
+    pub percent: Option<f64>,
+    pub active: Option<bool>,
+}
+
+const RULE_TYPES: [&str; 3] = ["round_up", "percent", "fixed"];
+const DEFAULT_ROUND_UP_STEP: i64 = 1;
+
+fn validate_rule_type(value: &str) -> Result<(), validator::ValidationError> {
+    if RULE_TYPES.contains(&value) {
+        Ok(())
+    } else {
+        Err(validator::ValidationError::new("invalid_rule_type"))
+    }
+}
+
+fn validate_transaction_type(value: &str) -> Result<(), validator::ValidationError> {
+    if value == "income" || value == "expense" {
+        Ok(())
+    } else {
+        Err(validator::ValidationError::new("invalid_transaction_type"))
+    }
+}
+
+fn positive_decimal(value: Option<f64>) -> Result<Option<Decimal>, ()> {
+    match value {
+        Some(value) => match Decimal::from_f64_retain(value) {
+            Some(decimal) if value > 0.0 && !decimal.round_dp(2).is_zero() => Ok(Some(decimal.round_dp(2))),
+            _ => Err(()),
+        },
+        None => Ok(None),
+    }
+}
+
+// Confere os campos exigidos por cada tipo de regra e preenche os padrões
+fn rule_values(
+    rule_type: &str,
+    amount: Option<Decimal>,
+    percent: Option<Decimal>,
+) -> Result<(Option<Decimal>, Option<Decimal>), &'static str> {
+    match rule_type {
+        "round_up" if percent.is_some() => Err("percent does not apply to round_up rules"),
+        "round_up" => Ok((Some(amount.unwrap_or(Decimal::from(DEFAULT_ROUND_UP_STEP))), None)),
+        "percent" if amount.is_some() => Err("amount does not apply to percent rules"),
+        "percent" => match percent {
+            Some(percent) if percent <= Decimal::from(100) => Ok((None, Some(percent))),
+            Some(_) => Err("percent must be at most 100"),
+            None => Err("percent is required for percent rules"),
+        },
+        _ if percent.is_some() => Err("percent does not apply to fixed rules"),
+        _ => match amount {
+            Some(amount) => Ok((Some(amount), None)),
+            None => Err("amount is required for fixed rules"),
+        },
+    }
+}
+
+impl AutosaveRule {
+    // Valor a guardar para a transação, ou None se a regra não se aplica
+    pub fn amount_for(&self, transaction: &Transaction) -> Option<Decimal> {
+        if let Some(transaction_type) = &self.transaction_type {
+            if transaction_type != &transaction.transaction_type {
+                return None;
+            }
+        }
+        if self.category_id.is_some() && self.category_id != transaction.category_id {
+            return None;
+        }
+
+        let value = transaction.amount.abs();
+        let saved = match self.rule_type.as_str() {
+            "round_up" => {
+                let step = self.amount.filter(|step| *step > Decimal::ZERO)?;
+                let rest = value % step;
+                if rest.is_zero() {
+                    return None;
+                }
+                step - rest
+            }
+            "percent" => (value * self.percent? / Decimal::from(100))
+                .round_dp_with_strategy(2, RoundingStrategy::ToZero),
+            "fixed" => {
+                let recurring_id = transaction.recurring_id.as_ref()?;
+                if self.recurring_id.as_ref().is_some_and(|id| id != recurring_id) {
+                    return None;
+                }
+                self.amount?
+            }
+            _ => return None,
+        };
+
+        Some(saved).filter(|saved| *saved > Decimal::ZERO)
+    }
+}
+
+// Aplica as regras ativas do espaço de trabalho a uma transação recém-criada. Cada regra
+// gera no máximo um aporte por transação (garantido pela chave única do histórico);
+// metas já atingidas são ignoradas.
+// Retorna quantos aportes foram registrados.
+pub async fn apply_rules(pool: &MySqlPool, transaction: &Transaction) -> Result<u32, sqlx::Error> {
+    let rules = sqlx::query_as::<_, AutosaveRule>(
+        "SELECT r.* FROM goal_autosave_rules r
+         JOIN goals g ON g.id = r.goal_id
+         WHERE r.workspace_id = ? AND r.active = TRUE AND g.current_amount < g.target_amount
+         ORDER BY r.created_at ASC"
+    )
+    .bind(&transaction.workspace_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut recorded = 0;
+    for rule in &rules {
+        let Some(amount) = rule.amount_for(transaction) else {
+            continue;
+        };
+
+        let note = format!("Poupança automática: {}", transaction.description);
+        let contribution = NewContribution {
+            amount,
+            note: Some(&note),
+            transaction_id: Some(&transaction.id),
+            autosave_rule_id: Some(&rule.id),
+            date: transaction.date.date_naive(),
+        };
+
+        if let ContributionOutcome::Recorded(saved) =
+            record_contribution(pool, &rule.user_id, &rule.goal_id, contribution).await?
+        {
+            check_milestones(pool, &saved.goal).await?;
+            recorded += 1;
+        }
+    }
+
+    Ok(recorded)
+}
+
+// Desfaz os aportes automáticos de uma transação que está sendo excluída e
+// recalcula o saldo das metas. Se a meta já teve retiradas e ficaria negativa,
+// os aportes são mantidos (sem a transação, que o banco desvincula).
+pub async fn revert_contributions(
+    tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+    transaction_id: &str,
+) -> Result<(), sqlx::Error> {
+    let saved = sqlx::query_as::<_, (String, Decimal)>(
+        "SELECT goal_id, SUM(amount) FROM goal_contributions
+         WHERE transaction_id = ? AND autosave_rule_id IS NOT NULL
+         GROUP BY goal_id"
+    )
+    .bind(transaction_id)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    for (goal_id, amount) in saved {
+        let goal = sqlx::query_as::<_, Goal>("SELECT * FROM goals WHERE id = ? FOR UPDATE")
+            .bind(&goal_id)
+            .fetch_one(&mut **tx)
+            .await?;
+        if goal.current_amount - amount < Decimal::ZERO {
+            continue;
+        }
+
+        record_opening_balance(tx, &goal).await?;
+        sqlx::query(
+            "DELETE FROM goal_contributions
+             WHERE goal_id = ? AND transaction_id = ? AND autosave_rule_id IS NOT NULL"
+        )
+        .bind(&goal_id)
+        .bind(transaction_id)
+        .execute(&mut **tx)
+        .await?;
+        refresh_current_amount(tx, &goal_id).await?;
+    }
+
+    Ok(())
+}
+
+fn database_error(e: sqlx::Error) -> HttpResponse {
+    eprintln!("Database error: {}", e);
+    HttpResponse::InternalServerError().json(serde_json::json!({
+        "error": "Database error"
+    }))
+}
+
+fn bad_request(error: &str) -> HttpResponse {
+    HttpResponse::BadRequest().json(serde_json::json!({
+        "error": error
+    }))
+}
+
+// Membros de metas compartilhadas também podem criar as próprias regras
+async fn goal_exists(pool: &MySqlPool, workspace: &Workspace, goal_id: &str) -> Result<bool, sqlx::Error> {
+    Ok(find_goal(pool, goal_id, workspace).await?.is_some())
+}
+
+// Cada usuário gerencia as próprias regras dentro do espaço em que as criou
+async fn find_rule(
+    pool: &MySqlPool,
+    workspace: &Workspace,
+    goal_id: &str,
+    rule_id: &str,
+) -> Result<Option<AutosaveRule>, sqlx::Error> {
+    sqlx::query_as::<_, AutosaveRule>(
+        "SELECT * FROM goal_autosave_rules
+         WHERE id = ? AND goal_id = ? AND workspace_id = ? AND user_id = ?"
+    )
+    .bind(rule_id)
+    .bind(goal_id)
+    .bind(&workspace.id)
+    .bind(&workspace.user_id)
+    .fetch_optional(pool)
+    .await
+}
+
+fn rule_not_found() -> HttpResponse {
+    HttpResponse::NotFound().json(serde_json::json!({
+        "error": "Auto-save rule not found"
+    }))
+}
+
+// GET /api/goals/{id}/autosave - Listar regras de poupança automática da meta
+pub async fn get_all(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    goal_id: web::Path<String>,
+) -> impl Responder {
+    match goal_exists(pool.get_ref(), &workspace, &goal_id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Goal not found"
+            }));
+        }
+        Err(e) => return database_error(e),
+    }
+
+    let rules = sqlx::query_as::<_, AutosaveRule>(
+        "SELECT * FROM goal_autosave_rules
+         WHERE goal_id = ? AND workspace_id = ? AND user_id = ?
+         ORDER BY created_at ASC"
+    )
+    .bind(goal_id.into_inner())
+    .bind(&workspace.id)
+    .bind(&workspace.user_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match rules {
+        Ok(rules) => HttpResponse::Ok().json(rules),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to fetch auto-save rules"
+            }))
+        }
+    }
+}
+
+// POST /api/goals/{id}/autosave - Criar regra de poupança automática
+pub async fn create(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    goal_id: web::Path<String>,
+    rule_data: web::Json<CreateAutosaveRule>,
+) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    if let Err(errors) = rule_data.validate() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Validation failed",
+            "details": errors.to_string()
+        }));
+    }
+
+    let Ok(amount) = positive_decimal(rule_data.amount) else {
+        return bad_request("Invalid amount");
+    };
+    let Ok(percent) = positive_decimal(rule_data.percent) else {
+        return bad_request("Invalid percent");
+    };
+    let (amount, percent) = match rule_values(&rule_data.rule_type, amount, percent) {
+        Ok(values) => values,
+        Err(error) => return bad_request(error),
+    };
+
+    if rule_data.recurring_id.is_some() && rule_data.rule_type != "fixed" {
+        return bad_request("recurring_id applies only to fixed rules");
+    }
+
+    // Arredondamento vale para despesas e percentual para receitas, salvo indicação
+    let transaction_type = rule_data.transaction_type.as_deref().or(match rule_data.rule_type.as_str() {
+        "round_up" => Some("expense"),
+        "percent" => Some("income"),
+        _ => None,
+    });
+
+    let goal_id = goal_id.into_inner();
+
+    match goal_exists(pool.get_ref(), &workspace, &goal_id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Goal not found"
+            }));
+        }
+        Err(e) => return database_error(e),
+    }
+
+    if let Some(category_id) = &rule_data.category_id {
+        let count = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM categories WHERE id = ? AND (workspace_id = ? OR is_default = TRUE)"
+        )
+        .bind(category_id)
+        .bind(&workspace.id)
+        .fetch_one(pool.get_ref())
+        .await;
+
+        match count {
+            Ok(0) => {
+                return HttpResponse::NotFound().json(serde_json::json!({
+                    "error": "Category not found"
+                }));
+            }
+            Ok(_) => {}
+            Err(e) => return database_error(e),
+        }
+    }
+
+    if let Some(recurring_id) = &rule_data.recurring_id {
+        let count = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM recurring_transactions WHERE id = ? AND workspace_id = ?"
+        )
+        .bind(recurring_id)
+        .bind(&workspace.id)
+        .fetch_one(pool.get_ref())
+        .await;
+
+        match count {
+            Ok(0) => {
+                return HttpResponse::NotFound().json(serde_json::json!({
+                    "error": "Recurring transaction not found"
+                }));
+            }
+            Ok(_) => {}
+            Err(e) => return database_error(e),
+        }
+    }
+
+    let rule_id = uuid::Uuid::new_v4().to_string();
+    let result = sqlx::query(
+        "INSERT INTO goal_autosave_rules
+         (id, goal_id, workspace_id, user_id, rule_type, amount, percent, transaction_type, category_id, recurring_id)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+    )
+    .bind(&rule_id)
+    .bind(&goal_id)
+    .bind(&workspace.id)
+    .bind(&workspace.user_id)
+    .bind(&rule_data.rule_type)
+    .bind(amount)
+    .bind(percent)
+    .bind(transaction_type)
+    .bind(&rule_data.category_id)
+    .bind(&rule_data.recurring_id)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(_) => match find_rule(pool.get_ref(), &workspace, &goal_id, &rule_id).await {
+            Ok(Some(rule)) => HttpResponse::Created().json(rule),
+            Ok(None) => rule_not_found(),
+            Err(e) => database_error(e),
+        },
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to create auto-save rule"
+            }))
+        }
+    }
+}
+
+// PUT /api/goals/{id}/autosave/{rule_id} - Alterar valores ou pausar a regra
+pub async fn update(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    path: web::Path<(String, String)>,
+    update_data: web::Json<UpdateAutosaveRule>,
+) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    let (goal_id, rule_id) = path.into_inner();
+
+    let rule = match find_rule(pool.get_ref(), &workspace, &goal_id, &rule_id).await {
+        Ok(Some(rule)) => rule,
+        Ok(None) => return rule_not_found(),
+        Err(e) => return database_error(e),
+    };
+
+    let Ok(amount) = positive_decimal(update_data.amount) else {
+        return bad_request("Invalid amount");
+    };
+    let Ok(percent) = positive_decimal(update_data.percent) else {
+        return bad_request("Invalid percent");
+    };
+    let (amount, percent) = match rule_values(
+        &rule.rule_type,
+        amount.or(rule.amount),
+        percent.or(rule.percent),
+    ) {
+        Ok(values) => values,
+        Err(error) => return bad_request(error),
+    };
+
+    let result = sqlx::query(
+        "UPDATE goal_autosave_rules SET amount = ?, percent = ?, active = COALESCE(?, active)
+         WHERE id = ?"
+    )
+    .bind(amount)
+    .bind(percent)
+    .bind(update_data.active)
+    .bind(&rule.id)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(_) => match find_rule(pool.get_ref(), &workspace, &goal_id, &rule_id).await {
+            Ok(Some(rule)) => HttpResponse::Ok().json(rule),
+            Ok(None) => rule_not_found(),
+            Err(e) => database_error(e),
+        },
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to update auto-save rule"
+            }))
+        }
+    }
+}
+
+// DELETE /api/goals/{id}/autosave/{rule_id} - Excluir regra (os aportes já feitos são mantidos)
+pub async fn delete(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    let (goal_id, rule_id) = path.into_inner();
+
+    let result = sqlx::query(
+        "DELETE FROM goal_autosave_rules
+         WHERE id = ? AND goal_id = ? AND workspace_id = ? AND user_id = ?"
+    )
+    .bind(&rule_id)
+    .bind(&goal_id)
+    .bind(&workspace.id)
+    .bind(&workspace.user_id)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(result) if result.rows_affected() > 0 => {
+            HttpResponse::Ok().json(serde_json::json!({
+                "message": "Auto-save rule deleted successfully"
+            }))
+        }
+        Ok(_) => rule_not_found(),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to delete auto-save rule"
+            }))
+        }
+    }
+}
@@ -0,0 +1,485 @@
+use actix_web::{web, HttpResponse, Responder};
+use chrono::{Datelike, Months, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, MySqlPool};
+use std::collections::HashMap;
+
+use crate::handlers::notifications::notify_workspace_once;
+use crate::middleware::workspace::Workspace;
+use crate::utils::parse_month;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Budget {
+    pub id: String,
+    pub workspace_id: String,
+    pub user_id: String,
+    pub category_id: String,
+    pub amount: Decimal,
+    pub rollover: bool,
+    pub start_month: NaiveDate,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateBudget {
+    pub category_id: String,
+    pub amount: f64,
+    pub rollover: Option<bool>,
+    pub start_month: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateBudget {
+    pub amount: Option<f64>,
+    pub rollover: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatusQuery {
+    pub month: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct BudgetRow {
+    #[sqlx(flatten)]
+    budget: Budget,
+    category_name: String,
+    category_icon: String,
+    category_color: String,
+}
+
+#[derive(Debug, FromRow)]
+struct MonthlySpending {
+    category_id: String,
+    month: NaiveDate,
+    spent: Decimal,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BudgetStatus {
+    pub budget_id: String,
+    pub category_id: String,
+    pub category_name: String,
+    pub category_icon: String,
+    pub category_color: String,
+    pub limit: Decimal,
+    pub rollover_amount: Decimal,
+    pub available: Decimal,
+    pub spent: Decimal,
+    pub remaining: Decimal,
+    pub percentage: Decimal,
+    pub status: &'static str,
+}
+
+// Limites (em %) que disparam notificações automáticas
+const ALERT_THRESHOLDS: [u32; 2] = [80, 100];
+
+fn current_month() -> NaiveDate {
+    Utc::now().date_naive().with_day(1).unwrap()
+}
+
+// GET /api/budgets - Listar orçamentos
+pub async fn get_all(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+) -> impl Responder {
+    let budgets = sqlx::query_as::<_, Budget>(
+        "SELECT * FROM budgets WHERE workspace_id = ? ORDER BY created_at ASC"
+    )
+    .bind(&workspace.id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match budgets {
+        Ok(budgets) => HttpResponse::Ok().json(budgets),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to fetch budgets"
+            }))
+        }
+    }
+}
+
+// POST /api/budgets - Criar orçamento mensal para uma categoria de despesa
+pub async fn create(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    budget_data: web::Json<CreateBudget>,
+) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    let amount = Decimal::from_f64_retain(budget_data.amount);
+    if amount.is_none() || budget_data.amount <= 0.0 {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Invalid amount"
+        }));
+    }
+
+    let start_month = match &budget_data.start_month {
+        Some(month) => match parse_month(month) {
+            Some((start, _)) => start,
+            None => {
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": "Invalid start_month, expected YYYY-MM"
+                }));
+            }
+        },
+        None => current_month(),
+    };
+
+    let category_type = sqlx::query_scalar::<_, String>(
+        "SELECT type FROM categories WHERE id = ? AND (workspace_id = ? OR is_default = TRUE)"
+    )
+    .bind(&budget_data.category_id)
+    .bind(&workspace.id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match category_type {
+        Ok(Some(t)) if t == "expense" || t == "both" => {}
+        Ok(Some(_)) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Budgets can only be set for expense categories"
+            }));
+        }
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Category not found"
+            }));
+        }
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            }));
+        }
+    }
+
+    let budget_id = uuid::Uuid::new_v4().to_string();
+
+    let result = sqlx::query(
+        "INSERT INTO budgets (id, workspace_id, user_id, category_id, amount, rollover, start_month)
+         VALUES (?, ?, ?, ?, ?, ?, ?)"
+    )
+    .bind(&budget_id)
+    .bind(&workspace.id)
+    .bind(&workspace.user_id)
+    .bind(&budget_data.category_id)
+    .bind(amount.unwrap())
+    .bind(budget_data.rollover.unwrap_or(false))
+    .bind(start_month)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(_) => {
+            let budget = sqlx::query_as::<_, Budget>("SELECT * FROM budgets WHERE id = ?")
+                .bind(&budget_id)
+                .fetch_one(pool.get_ref())
+                .await
+                .unwrap();
+
+            HttpResponse::Created().json(budget)
+        }
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            HttpResponse::Conflict().json(serde_json::json!({
+                "error": "A budget already exists for this category"
+            }))
+        }
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to create budget"
+            }))
+        }
+    }
+}
+
+// PUT /api/budgets/{id} - Atualizar orçamento
+pub async fn update(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    budget_id: web::Path<String>,
+    update_data: web::Json<UpdateBudget>,
+) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    if update_data.amount.is_none() && update_data.rollover.is_none() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "No fields to update"
+        }));
+    }
+
+    let amount = match update_data.amount {
+        Some(value) => match Decimal::from_f64_retain(value) {
+            Some(amount) if value > 0.0 => Some(amount),
+            _ => {
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": "Invalid amount"
+                }));
+            }
+        },
+        None => None,
+    };
+
+    let result = sqlx::query(
+        "UPDATE budgets
+         SET amount = COALESCE(?, amount), rollover = COALESCE(?, rollover)
+         WHERE id = ? AND workspace_id = ?"
+    )
+    .bind(amount)
+    .bind(update_data.rollover)
+    .bind(budget_id.into_inner())
+    .bind(&workspace.id)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(result) if result.rows_affected() > 0 => {
+            HttpResponse::Ok().json(serde_json::json!({
+                "message": "Budget updated successfully"
+            }))
+        }
+        Ok(_) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Budget not found"
+        })),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to update budget"
+            }))
+        }
+    }
+}
+
+// DELETE /api/budgets/{id} - Deletar orçamento
+pub async fn delete(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    budget_id: web::Path<String>,
+) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    let result = sqlx::query("DELETE FROM budgets WHERE id = ? AND workspace_id = ?")
+        .bind(budget_id.into_inner())
+        .bind(&workspace.id)
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(result) if result.rows_affected() > 0 => {
+            HttpResponse::Ok().json(serde_json::json!({
+                "message": "Budget deleted successfully"
+            }))
+        }
+        Ok(_) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Budget not found"
+        })),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to delete budget"
+            }))
+        }
+    }
+}
+
+// GET /api/budgets/status?month=YYYY-MM - Orçado x realizado no mês
+pub async fn status(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    query: web::Query<StatusQuery>,
+) -> impl Responder {
+    let month = match &query.month {
+        Some(month) => match parse_month(month) {
+            Some((start, _)) => start,
+            None => {
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": "Invalid month, expected YYYY-MM"
+                }));
+            }
+        },
+        None => current_month(),
+    };
+
+    match budget_statuses(pool.get_ref(), &workspace.id, month, None).await {
+        Ok(budgets) => {
+            let total_available: Decimal = budgets.iter().map(|b| b.available).sum();
+            let total_spent: Decimal = budgets.iter().map(|b| b.spent).sum();
+
+            HttpResponse::Ok().json(serde_json::json!({
+                "month": month.format("%Y-%m").to_string(),
+                "total_available": total_available,
+                "total_spent": total_spent,
+                "total_remaining": total_available - total_spent,
+                "budgets": budgets
+            }))
+        }
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to compute budget status"
+            }))
+        }
+    }
+}
+
+// Calcula o orçado x realizado de cada orçamento no mês. Com rollover, a
+// sobra de cada mês desde start_month é somada ao limite do mês seguinte.
+async fn budget_statuses(
+    pool: &MySqlPool,
+    workspace_id: &str,
+    month: NaiveDate,
+    category_id: Option<&str>,
+) -> Result<Vec<BudgetStatus>, sqlx::Error> {
+    let month_end = month + Months::new(1);
+
+    let budgets = sqlx::query_as::<_, BudgetRow>(
+        "SELECT b.*, c.name AS category_name,
+                COALESCE(c.icon, '💵') AS category_icon,
+                COALESCE(c.color, '#636e72') AS category_color
+         FROM budgets b
+         JOIN categories c ON c.id = b.category_id
+         WHERE b.workspace_id = ? AND b.start_month <= ? AND (? IS NULL OR b.category_id = ?)
+         ORDER BY c.name ASC"
+    )
+    .bind(workspace_id)
+    .bind(month)
+    .bind(category_id)
+    .bind(category_id)
+    .fetch_all(pool)
+    .await?;
+
+    let Some(first_month) = budgets.iter().map(|row| row.budget.start_month).min() else {
+        return Ok(Vec::new());
+    };
+
+    let spending = sqlx::query_as::<_, MonthlySpending>(
+        "SELECT t.category_id,
+                CAST(DATE_FORMAT(t.date, '%Y-%m-01') AS DATE) AS month,
+                SUM(t.amount) AS spent
+         FROM transaction_lines t
+         JOIN budgets b ON b.category_id = t.category_id AND b.workspace_id = t.workspace_id
+         WHERE t.workspace_id = ? AND t.type = 'expense' AND t.date >= ? AND t.date < ?
+         GROUP BY t.category_id, month"
+    )
+    .bind(workspace_id)
+    .bind(first_month)
+    .bind(month_end)
+    .fetch_all(pool)
+    .await?;
+
+    let spent_by_month: HashMap<(String, NaiveDate), Decimal> = spending
+        .into_iter()
+        .map(|row| ((row.category_id, row.month), row.spent))
+        .collect();
+
+    let statuses = budgets
+        .into_iter()
+        .map(|row| {
+            let budget = row.budget;
+            let spent_in = |m: NaiveDate| {
+                spent_by_month
+                    .get(&(budget.category_id.clone(), m))
+                    .copied()
+                    .unwrap_or_default()
+            };
+
+            let mut carry = Decimal::ZERO;
+            if budget.rollover {
+                let mut current = budget.start_month;
+                while current < month {
+                    let available = budget.amount + carry;
+                    carry = (available - spent_in(current)).max(Decimal::ZERO);
+                    current = current + Months::new(1);
+                }
+            }
+
+            let available = budget.amount + carry;
+            let spent = spent_in(month);
+            let percentage = (spent * Decimal::from(100) / available).round_dp(2);
+            let status = if percentage >= Decimal::from(100) {
+                "exceeded"
+            } else if percentage >= Decimal::from(ALERT_THRESHOLDS[0]) {
+                "warning"
+            } else {
+                "ok"
+            };
+
+            BudgetStatus {
+                budget_id: budget.id,
+                category_id: budget.category_id,
+                category_name: row.category_name,
+                category_icon: row.category_icon,
+                category_color: row.category_color,
+                limit: budget.amount,
+                rollover_amount: carry,
+                available,
+                spent,
+                remaining: available - spent,
+                percentage,
+                status,
+            }
+        })
+        .collect();
+
+    Ok(statuses)
+}
+
+// Verifica o orçamento da categoria no mês da despesa e cria avisos ao
+// cruzar 80% e 100% do limite (uma única vez por mês e limite), para todos
+// os membros do espaço de trabalho.
+pub async fn check_alerts(
+    pool: &MySqlPool,
+    workspace_id: &str,
+    category_id: &str,
+    date: NaiveDate,
+) -> Result<(), sqlx::Error> {
+    let month = date.with_day(1).unwrap();
+    let statuses = budget_statuses(pool, workspace_id, month, Some(category_id)).await?;
+
+    for status in statuses {
+        for threshold in ALERT_THRESHOLDS {
+            if status.percentage < Decimal::from(threshold) {
+                continue;
+            }
+
+            let event_key = format!(
+                "budget:{}:{}:{}",
+                status.budget_id,
+                month.format("%Y-%m"),
+                threshold
+            );
+            let (title, message) = if threshold >= 100 {
+                (
+                    "Orçamento estourado".to_string(),
+                    format!(
+                        "Você gastou R$ {} de R$ {} em {} neste mês.",
+                        status.spent, status.available, status.category_name
+                    ),
+                )
+            } else {
+                (
+                    "Orçamento quase no limite".to_string(),
+                    format!(
+                        "Você já usou {}% do orçamento de {} neste mês.",
+                        status.percentage, status.category_name
+                    ),
+                )
+            };
+
+            notify_workspace_once(pool, workspace_id, &event_key, &title, &message, "warning").await?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use actix_web::{web, HttpResponse, Responder};
+use chrono::{Datelike, Months, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::holidays::{self, Holiday};
+
+#[derive(Debug, Deserialize)]
+pub struct BusinessDaysQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BusinessDaysResponse {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub business_day_count: usize,
+    pub business_days: Vec<NaiveDate>,
+    pub holidays: Vec<Holiday>,
+}
+
+const MAX_RANGE_DAYS: i64 = 731;
+
+// GET /api/calendar/business-days?from=&to= - Dias úteis e feriados nacionais do período
+pub async fn business_days(query: web::Query<BusinessDaysQuery>) -> impl Responder {
+    // Padrão: mês atual
+    let today = Utc::now().date_naive();
+    let from = query.from.unwrap_or_else(|| today.with_day(1).unwrap());
+    let to = query
+        .to
+        .unwrap_or_else(|| (from.with_day(1).unwrap() + Months::new(1)).pred_opt().unwrap());
+
+    if from > to {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "'from' must not be after 'to'"
+        }));
+    }
+    if (to - from).num_days() >= MAX_RANGE_DAYS {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Date range too large, maximum is 731 days"
+        }));
+    }
+
+    let holidays: Vec<Holiday> = (from.year()..=to.year())
+        .flat_map(holidays::national_holidays)
+        .filter(|holiday| holiday.date >= from && holiday.date <= to)
+        .collect();
+
+    let business_days: Vec<NaiveDate> = from
+        .iter_days()
+        .take_while(|date| *date <= to)
+        .filter(|date| holidays::is_business_day(*date))
+        .collect();
+
+    HttpResponse::Ok().json(BusinessDaysResponse {
+        from,
+        to,
+        business_day_count: business_days.len(),
+        business_days,
+        holidays,
+    })
+}
@@ -2,18 +2,36 @@ use actix_web::{web, HttpResponse, Responder};
 use sqlx::MySqlPool;
 use validator::Validate;
 
+use crate::middleware::workspace::Workspace;
 use crate::models::{Category, CreateCategory};
 
+// Categorias do espaço de trabalho ou padrão do sistema
+pub async fn category_exists(
+    pool: &MySqlPool,
+    workspace_id: &str,
+    category_id: &str,
+) -> Result<bool, sqlx::Error> {
+    let count = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM categories WHERE id = ? AND (workspace_id = ? OR is_default = TRUE)"
+    )
+    .bind(category_id)
+    .bind(workspace_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count > 0)
+}
+
 pub async fn get_all(
     pool: web::Data<MySqlPool>,
-    user_id: web::ReqData<String>,
+    workspace: Workspace,
 ) -> impl Responder {
     let result = sqlx::query_as::<_, Category>(
-        "SELECT id, user_id, name, icon, color, type as category_type, is_default, created_at 
+        "SELECT id, workspace_id, user_id, name, icon, color, type as category_type, is_default, created_at 
          FROM categories 
-         WHERE user_id = ? OR is_default = true"
+         WHERE workspace_id = ? OR is_default = true"
     )
-    .bind(&*user_id)
+    .bind(&workspace.id)
     .fetch_all(pool.get_ref())
     .await;
 
@@ -32,9 +50,13 @@ pub async fn get_all(
 
 pub async fn create(
     pool: web::Data<MySqlPool>,
-    user_id: web::ReqData<String>,
+    workspace: Workspace,
     category_data: web::Json<CreateCategory>,
 ) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
     if let Err(errors) = category_data.validate() {
         return HttpResponse::BadRequest().json(serde_json::json!({
             "error": "Validation failed",
@@ -57,11 +79,12 @@ pub async fn create(
     let category_id = uuid::Uuid::new_v4().to_string();
 
     let result = sqlx::query(
-        "INSERT INTO categories (id, user_id, name, icon, color, type)
-         VALUES (?, ?, ?, ?, ?, ?)"
+        "INSERT INTO categories (id, workspace_id, user_id, name, icon, color, type)
+         VALUES (?, ?, ?, ?, ?, ?, ?)"
     )
     .bind(&category_id)
-    .bind(user_id.into_inner())
+    .bind(&workspace.id)
+    .bind(&workspace.user_id)
     .bind(&category_data.name)
     .bind(icon)
     .bind(color)
@@ -72,7 +95,7 @@ pub async fn create(
     match result {
         Ok(_) => {
             let category = sqlx::query_as::<_, Category>(
-                "SELECT id, user_id, name, icon, color, type as category_type, is_default, created_at 
+                "SELECT id, workspace_id, user_id, name, icon, color, type as category_type, is_default, created_at 
                  FROM categories WHERE id = ?"
             )
             .bind(&category_id)
@@ -93,15 +116,19 @@ pub async fn create(
 
 pub async fn delete(
     pool: web::Data<MySqlPool>,
-    user_id: web::ReqData<String>,
+    workspace: Workspace,
     category_id: web::Path<String>,
 ) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
     let result = sqlx::query(
         "DELETE FROM categories 
-         WHERE id = ? AND user_id = ? AND is_default = FALSE"
+         WHERE id = ? AND workspace_id = ? AND is_default = FALSE"
     )
     .bind(category_id.into_inner())
-    .bind(user_id.into_inner())
+    .bind(&workspace.id)
     .execute(pool.get_ref())
     .await;
 
@@ -126,10 +153,14 @@ pub async fn delete(
 // PUT /api/categories/{id} - Atualizar categoria
 pub async fn update(
     pool: web::Data<MySqlPool>,
-    user_id: web::ReqData<String>,
+    workspace: Workspace,
     category_id: web::Path<String>,
     update_data: web::Json<CreateCategory>,
 ) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
     let mut updates = Vec::new();
 
     if !update_data.name.is_empty() {
@@ -152,10 +183,10 @@ pub async fn update(
     }
 
     let query = format!(
-        "UPDATE categories SET {} WHERE id = '{}' AND user_id = '{}'",
+        "UPDATE categories SET {} WHERE id = '{}' AND workspace_id = '{}'",
         updates.join(", "),
         category_id.into_inner(),
-        user_id.into_inner()
+        workspace.id
     );
 
     let result = sqlx::query(&query).execute(pool.get_ref()).await;
@@ -167,7 +198,7 @@ pub async fn update(
             }))
         }
         Ok(_) => HttpResponse::NotFound().json(serde_json::json!({
-            "error": "Category not found or not owned by user"
+            "error": "Category not found in this workspace"
         })),
         Err(e) => {
             eprintln!("Database error: {}", e);
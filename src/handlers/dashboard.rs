@@ -0,0 +1,177 @@
+use actix_web::{web, HttpResponse, Responder};
+use chrono::{NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, MySqlPool};
+
+use crate::handlers::goals::Goal;
+use crate::handlers::recurring::RecurringTransaction;
+use crate::middleware::workspace::Workspace;
+use crate::models::Transaction;
+use crate::utils::parse_month;
+
+#[derive(Debug, Deserialize)]
+pub struct DashboardQuery {
+    pub month: Option<String>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct MonthTotals {
+    pub total_income: Decimal,
+    pub total_expense: Decimal,
+    pub net: Decimal,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct CategorySpending {
+    pub category_id: Option<String>,
+    pub name: String,
+    pub icon: String,
+    pub color: String,
+    pub total: Decimal,
+    pub percentage: Decimal,
+    pub transaction_count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UpcomingCharge {
+    #[serde(flatten)]
+    pub recurring: RecurringTransaction,
+    pub next_due: NaiveDate,
+}
+
+const TOP_LIMIT: u32 = 5;
+
+// GET /api/dashboard?month=YYYY-MM - Resumo mensal
+pub async fn summary(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    query: web::Query<DashboardQuery>,
+) -> impl Responder {
+    let month = query
+        .month
+        .clone()
+        .unwrap_or_else(|| Utc::now().format("%Y-%m").to_string());
+
+    let (start, end) = match parse_month(&month) {
+        Some(bounds) => bounds,
+        None => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Invalid month, expected YYYY-MM"
+            }));
+        }
+    };
+
+    match load_summary(pool.get_ref(), &workspace, &month, start, end).await {
+        Ok(summary) => HttpResponse::Ok().json(summary),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to build dashboard"
+            }))
+        }
+    }
+}
+
+async fn load_summary(
+    pool: &MySqlPool,
+    workspace: &Workspace,
+    month: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<serde_json::Value, sqlx::Error> {
+    let totals = sqlx::query_as::<_, MonthTotals>(
+        "SELECT COALESCE(SUM(CASE WHEN type = 'income' THEN amount ELSE 0 END), 0) AS total_income,
+                COALESCE(SUM(CASE WHEN type = 'expense' THEN amount ELSE 0 END), 0) AS total_expense,
+                COALESCE(SUM(CASE WHEN type = 'income' THEN amount ELSE -amount END), 0) AS net
+         FROM transactions
+         WHERE workspace_id = ? AND date >= ? AND date < ?"
+    )
+    .bind(&workspace.id)
+    .bind(start)
+    .bind(end)
+    .fetch_one(pool)
+    .await?;
+
+    let spending_by_category = sqlx::query_as::<_, CategorySpending>(
+        "SELECT t.category_id,
+                COALESCE(c.name, 'Sem categoria') AS name,
+                COALESCE(c.icon, '💵') AS icon,
+                COALESCE(c.color, '#636e72') AS color,
+                SUM(t.amount) AS total,
+                ROUND(SUM(t.amount) * 100 / SUM(SUM(t.amount)) OVER (), 2) AS percentage,
+                COUNT(DISTINCT t.transaction_id) AS transaction_count
+         FROM transaction_lines t
+         LEFT JOIN categories c ON c.id = t.category_id
+         WHERE t.workspace_id = ? AND t.type = 'expense' AND t.date >= ? AND t.date < ?
+         GROUP BY t.category_id, c.name, c.icon, c.color
+         ORDER BY total DESC"
+    )
+    .bind(&workspace.id)
+    .bind(start)
+    .bind(end)
+    .fetch_all(pool)
+    .await?;
+
+    let top_expenses = sqlx::query_as::<_, Transaction>(
+        "SELECT * FROM transactions
+         WHERE workspace_id = ? AND type = 'expense' AND date >= ? AND date < ?
+         ORDER BY amount DESC, date DESC
+         LIMIT ?"
+    )
+    .bind(&workspace.id)
+    .bind(start)
+    .bind(end)
+    .bind(TOP_LIMIT)
+    .fetch_all(pool)
+    .await?;
+
+    let upcoming_goals = sqlx::query_as::<_, Goal>(
+        "SELECT * FROM goals
+         WHERE workspace_id = ? AND current_amount < target_amount AND deadline >= ?
+         ORDER BY deadline ASC
+         LIMIT ?"
+    )
+    .bind(&workspace.id)
+    .bind(Utc::now().date_naive())
+    .bind(TOP_LIMIT)
+    .fetch_all(pool)
+    .await?;
+
+    let recurring = sqlx::query_as::<_, RecurringTransaction>(
+        "SELECT * FROM recurring_transactions
+         WHERE workspace_id = ? AND active = TRUE AND type = 'expense'"
+    )
+    .bind(&workspace.id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut upcoming_recurring: Vec<UpcomingCharge> = recurring
+        .into_iter()
+        .filter_map(|recurring| {
+            let next_due = recurring.next_occurrence()?;
+            Some(UpcomingCharge { recurring, next_due })
+        })
+        .collect();
+    upcoming_recurring.sort_by_key(|charge| charge.next_due);
+    upcoming_recurring.truncate(TOP_LIMIT as usize);
+
+    let unread_notifications = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM notifications WHERE user_id = ? AND `read` = FALSE"
+    )
+    .bind(&workspace.user_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(serde_json::json!({
+        "month": month,
+        "total_income": totals.total_income,
+        "total_expense": totals.total_expense,
+        "net": totals.net,
+        "spending_by_category": spending_by_category,
+        "top_expenses": top_expenses,
+        "upcoming_goals": upcoming_goals,
+        "upcoming_recurring": upcoming_recurring,
+        "unread_notifications": unread_notifications
+    }))
+}
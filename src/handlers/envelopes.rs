@@ -0,0 +1,660 @@
+use actix_web::{web, HttpResponse, Responder};
+use chrono::{Datelike, Months, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, MySqlPool};
+use std::collections::HashMap;
+
+use crate::handlers::categories::category_exists;
+use crate::middleware::workspace::Workspace;
+use crate::utils::parse_month;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct EnvelopeAssignment {
+    pub id: String,
+    pub workspace_id: String,
+    pub user_id: String,
+    pub category_id: String,
+    pub month: NaiveDate,
+    pub amount: Decimal,
+    pub note: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MonthQuery {
+    pub month: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EnvelopeSettings {
+    pub enabled: bool,
+    pub start_month: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AssignMoney {
+    pub category_id: String,
+    pub amount: f64,
+    pub month: Option<String>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MoveMoney {
+    pub from_category_id: String,
+    pub to_category_id: String,
+    pub amount: f64,
+    pub month: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct CategoryInfo {
+    id: String,
+    name: String,
+    icon: String,
+    color: String,
+    category_type: String,
+}
+
+#[derive(Debug, FromRow)]
+struct AssignedRow {
+    category_id: String,
+    assigned_month: Decimal,
+    assigned_total: Decimal,
+}
+
+#[derive(Debug, FromRow)]
+struct SpentRow {
+    category_id: Option<String>,
+    spent_month: Decimal,
+    spent_total: Decimal,
+}
+
+#[derive(Debug, FromRow)]
+struct IncomeRow {
+    income_month: Decimal,
+    income_total: Decimal,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Envelope {
+    pub category_id: Option<String>,
+    pub name: String,
+    pub icon: String,
+    pub color: String,
+    pub carried_over: Decimal,
+    pub assigned: Decimal,
+    pub spent: Decimal,
+    pub balance: Decimal,
+    pub overspent: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EnvelopeSummary {
+    pub month: String,
+    pub envelope_start: NaiveDate,
+    pub income_this_month: Decimal,
+    pub total_income: Decimal,
+    pub total_assigned: Decimal,
+    pub left_to_assign: Decimal,
+    pub overspent_total: Decimal,
+    pub envelopes: Vec<Envelope>,
+}
+
+// Primeiro dia do mês informado (padrão: mês atual); None se inválido
+fn month_param(month: &Option<String>) -> Option<NaiveDate> {
+    match month {
+        Some(month) => parse_month(month).map(|(start, _)| start),
+        None => Some(Utc::now().date_naive().with_day(1).unwrap()),
+    }
+}
+
+fn invalid_month() -> HttpResponse {
+    HttpResponse::BadRequest().json(serde_json::json!({
+        "error": "Invalid month, expected YYYY-MM"
+    }))
+}
+
+fn not_enabled() -> HttpResponse {
+    HttpResponse::BadRequest().json(serde_json::json!({
+        "error": "Envelope mode is not enabled"
+    }))
+}
+
+fn database_error(e: sqlx::Error) -> HttpResponse {
+    eprintln!("Database error: {}", e);
+    HttpResponse::InternalServerError().json(serde_json::json!({
+        "error": "Database error"
+    }))
+}
+
+async fn envelope_start(pool: &MySqlPool, workspace_id: &str) -> Result<Option<NaiveDate>, sqlx::Error> {
+    sqlx::query_scalar::<_, Option<NaiveDate>>("SELECT envelope_start FROM workspaces WHERE id = ?")
+        .bind(workspace_id)
+        .fetch_one(pool)
+        .await
+}
+
+// Saldo de um envelope ao final do mês, lido dentro da transação que vai
+// alterá-lo. A linha do espaço fica bloqueada (FOR UPDATE) até o commit, então
+// distribuições e movimentações concorrentes no mesmo espaço são serializadas
+// e não conseguem gastar duas vezes o mesmo saldo.
+async fn locked_envelope_balance(
+    tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+    workspace_id: &str,
+    category_id: &str,
+    start: NaiveDate,
+    month: NaiveDate,
+) -> Result<Decimal, sqlx::Error> {
+    sqlx::query("SELECT id FROM workspaces WHERE id = ? FOR UPDATE")
+        .bind(workspace_id)
+        .execute(&mut **tx)
+        .await?;
+
+    let assigned = sqlx::query_scalar::<_, Decimal>(
+        "SELECT COALESCE(SUM(amount), 0) FROM envelope_assignments
+         WHERE workspace_id = ? AND category_id = ? AND month >= ? AND month <= ?"
+    )
+    .bind(workspace_id)
+    .bind(category_id)
+    .bind(start)
+    .bind(month)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    let spent = sqlx::query_scalar::<_, Decimal>(
+        "SELECT COALESCE(SUM(amount), 0) FROM transaction_lines
+         WHERE workspace_id = ? AND category_id = ? AND type = 'expense' AND date >= ? AND date < ?"
+    )
+    .bind(workspace_id)
+    .bind(category_id)
+    .bind(start)
+    .bind(month + Months::new(1))
+    .fetch_one(&mut **tx)
+    .await?;
+
+    Ok(assigned - spent)
+}
+
+// Saldos dos envelopes ao final do mês. Toda receita registrada desde o
+// início do modo envelope entra no saldo "a distribuir"; as despesas de cada
+// categoria consomem o respectivo envelope, e sobras passam para o mês seguinte.
+async fn load_summary(
+    pool: &MySqlPool,
+    workspace_id: &str,
+    start: NaiveDate,
+    month: NaiveDate,
+) -> Result<EnvelopeSummary, sqlx::Error> {
+    let month_end = month + Months::new(1);
+
+    let categories = sqlx::query_as::<_, CategoryInfo>(
+        "SELECT id, name, COALESCE(icon, '💵') AS icon, COALESCE(color, '#636e72') AS color,
+                type AS category_type
+         FROM categories
+         WHERE workspace_id = ? OR is_default = TRUE
+         ORDER BY name ASC"
+    )
+    .bind(workspace_id)
+    .fetch_all(pool)
+    .await?;
+
+    let assigned = sqlx::query_as::<_, AssignedRow>(
+        "SELECT category_id,
+                COALESCE(SUM(CASE WHEN month = ? THEN amount ELSE 0 END), 0) AS assigned_month,
+                COALESCE(SUM(amount), 0) AS assigned_total
+         FROM envelope_assignments
+         WHERE workspace_id = ? AND month >= ? AND month <= ?
+         GROUP BY category_id"
+    )
+    .bind(month)
+    .bind(workspace_id)
+    .bind(start)
+    .bind(month)
+    .fetch_all(pool)
+    .await?;
+
+    let spent = sqlx::query_as::<_, SpentRow>(
+        "SELECT category_id,
+                COALESCE(SUM(CASE WHEN date >= ? THEN amount ELSE 0 END), 0) AS spent_month,
+                COALESCE(SUM(amount), 0) AS spent_total
+         FROM transaction_lines
+         WHERE workspace_id = ? AND type = 'expense' AND date >= ? AND date < ?
+         GROUP BY category_id"
+    )
+    .bind(month)
+    .bind(workspace_id)
+    .bind(start)
+    .bind(month_end)
+    .fetch_all(pool)
+    .await?;
+
+    let income = sqlx::query_as::<_, IncomeRow>(
+        "SELECT COALESCE(SUM(CASE WHEN date >= ? THEN amount ELSE 0 END), 0) AS income_month,
+                COALESCE(SUM(amount), 0) AS income_total
+         FROM transactions
+         WHERE workspace_id = ? AND type = 'income' AND date >= ? AND date < ?"
+    )
+    .bind(month)
+    .bind(workspace_id)
+    .bind(start)
+    .bind(month_end)
+    .fetch_one(pool)
+    .await?;
+
+    let assigned: HashMap<String, AssignedRow> = assigned
+        .into_iter()
+        .map(|row| (row.category_id.clone(), row))
+        .collect();
+    let spent: HashMap<Option<String>, SpentRow> = spent
+        .into_iter()
+        .map(|row| (row.category_id.clone(), row))
+        .collect();
+
+    let mut envelopes = Vec::new();
+    for category in &categories {
+        let assigned_row = assigned.get(&category.id);
+        let spent_row = spent.get(&Some(category.id.clone()));
+        if category.category_type == "income" && assigned_row.is_none() && spent_row.is_none() {
+            continue;
+        }
+
+        let envelope = build_envelope(
+            Some(category.id.clone()),
+            category.name.clone(),
+            category.icon.clone(),
+            category.color.clone(),
+            assigned_row,
+            spent_row,
+        );
+        envelopes.push(envelope);
+    }
+
+    // Despesas sem categoria não têm envelope e aparecem sempre como estouro
+    if let Some(row) = spent.get(&None) {
+        envelopes.push(build_envelope(
+            None,
+            "Sem categoria".to_string(),
+            "💵".to_string(),
+            "#636e72".to_string(),
+            None,
+            Some(row),
+        ));
+    }
+
+    let total_assigned: Decimal = assigned.values().map(|row| row.assigned_total).sum();
+    let overspent_total: Decimal = envelopes
+        .iter()
+        .filter(|e| e.overspent)
+        .map(|e| -e.balance)
+        .sum();
+
+    Ok(EnvelopeSummary {
+        month: month.format("%Y-%m").to_string(),
+        envelope_start: start,
+        income_this_month: income.income_month,
+        total_income: income.income_total,
+        total_assigned,
+        left_to_assign: income.income_total - total_assigned,
+        overspent_total,
+        envelopes,
+    })
+}
+
+fn build_envelope(
+    category_id: Option<String>,
+    name: String,
+    icon: String,
+    color: String,
+    assigned: Option<&AssignedRow>,
+    spent: Option<&SpentRow>,
+) -> Envelope {
+    let (assigned_month, assigned_total) = assigned
+        .map(|row| (row.assigned_month, row.assigned_total))
+        .unwrap_or_default();
+    let (spent_month, spent_total) = spent
+        .map(|row| (row.spent_month, row.spent_total))
+        .unwrap_or_default();
+    let balance = assigned_total - spent_total;
+
+    Envelope {
+        category_id,
+        name,
+        icon,
+        color,
+        carried_over: balance - assigned_month + spent_month,
+        assigned: assigned_month,
+        spent: spent_month,
+        balance,
+        overspent: balance < Decimal::ZERO,
+    }
+}
+
+// PUT /api/envelopes/settings - Ativar ou desativar o modo envelope
+pub async fn update_settings(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    settings: web::Json<EnvelopeSettings>,
+) -> impl Responder {
+    if let Err(response) = workspace.require_owner() {
+        return response;
+    }
+
+    let start = if settings.enabled {
+        match month_param(&settings.start_month) {
+            Some(month) => Some(month),
+            None => return invalid_month(),
+        }
+    } else {
+        None
+    };
+
+    let result = sqlx::query("UPDATE workspaces SET envelope_start = ? WHERE id = ?")
+        .bind(start)
+        .bind(&workspace.id)
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(_) => HttpResponse::Ok().json(serde_json::json!({
+            "enabled": settings.enabled,
+            "envelope_start": start
+        })),
+        Err(e) => database_error(e),
+    }
+}
+
+// GET /api/envelopes?month=YYYY-MM - Saldos dos envelopes
+pub async fn get_all(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    query: web::Query<MonthQuery>,
+) -> impl Responder {
+    let month = match month_param(&query.month) {
+        Some(month) => month,
+        None => return invalid_month(),
+    };
+
+    let start = match envelope_start(pool.get_ref(), &workspace.id).await {
+        Ok(Some(start)) => start,
+        Ok(None) => return not_enabled(),
+        Err(e) => return database_error(e),
+    };
+
+    match load_summary(pool.get_ref(), &workspace.id, start, month).await {
+        Ok(summary) => HttpResponse::Ok().json(summary),
+        Err(e) => database_error(e),
+    }
+}
+
+// GET /api/envelopes/left-to-assign?month=YYYY-MM - Valor ainda sem destino
+pub async fn left_to_assign(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    query: web::Query<MonthQuery>,
+) -> impl Responder {
+    let month = match month_param(&query.month) {
+        Some(month) => month,
+        None => return invalid_month(),
+    };
+
+    let start = match envelope_start(pool.get_ref(), &workspace.id).await {
+        Ok(Some(start)) => start,
+        Ok(None) => return not_enabled(),
+        Err(e) => return database_error(e),
+    };
+
+    match load_summary(pool.get_ref(), &workspace.id, start, month).await {
+        Ok(summary) => HttpResponse::Ok().json(serde_json::json!({
+            "month": summary.month,
+            "total_income": summary.total_income,
+            "total_assigned": summary.total_assigned,
+            "left_to_assign": summary.left_to_assign,
+            "overspent_total": summary.overspent_total
+        })),
+        Err(e) => database_error(e),
+    }
+}
+
+// GET /api/envelopes/assignments?month=YYYY-MM - Distribuições do mês
+pub async fn get_assignments(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    query: web::Query<MonthQuery>,
+) -> impl Responder {
+    let month = match month_param(&query.month) {
+        Some(month) => month,
+        None => return invalid_month(),
+    };
+
+    let assignments = sqlx::query_as::<_, EnvelopeAssignment>(
+        "SELECT * FROM envelope_assignments
+         WHERE workspace_id = ? AND month = ?
+         ORDER BY created_at DESC"
+    )
+    .bind(&workspace.id)
+    .bind(month)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match assignments {
+        Ok(assignments) => HttpResponse::Ok().json(assignments),
+        Err(e) => database_error(e),
+    }
+}
+
+// POST /api/envelopes/assign - Distribuir dinheiro para um envelope
+// (valores negativos devolvem dinheiro para o saldo a distribuir)
+pub async fn assign(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    assign_data: web::Json<AssignMoney>,
+) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    let amount = match Decimal::from_f64_retain(assign_data.amount) {
+        Some(amount) if !amount.is_zero() => amount.round_dp(2),
+        _ => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Invalid amount"
+            }));
+        }
+    };
+
+    let month = match month_param(&assign_data.month) {
+        Some(month) => month,
+        None => return invalid_month(),
+    };
+
+    let start = match envelope_start(pool.get_ref(), &workspace.id).await {
+        Ok(Some(start)) if month >= start => start,
+        Ok(Some(_)) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Month is before the envelope mode start"
+            }));
+        }
+        Ok(None) => return not_enabled(),
+        Err(e) => return database_error(e),
+    };
+
+    match category_exists(pool.get_ref(), &workspace.id, &assign_data.category_id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Category not found"
+            }));
+        }
+        Err(e) => return database_error(e),
+    }
+
+    let assignment_id = uuid::Uuid::new_v4().to_string();
+
+    // Devoluções (valores negativos) não podem deixar o envelope negativo
+    let result = async {
+        let mut tx = pool.begin().await?;
+
+        if amount < Decimal::ZERO {
+            let balance = locked_envelope_balance(
+                &mut tx,
+                &workspace.id,
+                &assign_data.category_id,
+                start,
+                month,
+            )
+            .await?;
+            if balance + amount < Decimal::ZERO {
+                return Ok(false);
+            }
+        }
+
+        sqlx::query(
+            "INSERT INTO envelope_assignments (id, workspace_id, user_id, category_id, month, amount, note)
+             VALUES (?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(&assignment_id)
+        .bind(&workspace.id)
+        .bind(&workspace.user_id)
+        .bind(&assign_data.category_id)
+        .bind(month)
+        .bind(amount)
+        .bind(&assign_data.note)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(true)
+    }
+    .await;
+
+    match result {
+        Ok(false) => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Insufficient envelope balance"
+        })),
+        Ok(true) => {
+            let assignment = sqlx::query_as::<_, EnvelopeAssignment>(
+                "SELECT * FROM envelope_assignments WHERE id = ?"
+            )
+            .bind(&assignment_id)
+            .fetch_one(pool.get_ref())
+            .await
+            .unwrap();
+
+            HttpResponse::Created().json(assignment)
+        }
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to assign money"
+            }))
+        }
+    }
+}
+
+// POST /api/envelopes/move - Mover dinheiro entre envelopes
+pub async fn move_money(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    move_data: web::Json<MoveMoney>,
+) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    let amount = match Decimal::from_f64_retain(move_data.amount) {
+        Some(amount) if move_data.amount > 0.0 => amount.round_dp(2),
+        _ => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Invalid amount"
+            }));
+        }
+    };
+
+    if move_data.from_category_id == move_data.to_category_id {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Source and destination envelopes must differ"
+        }));
+    }
+
+    let month = match month_param(&move_data.month) {
+        Some(month) => month,
+        None => return invalid_month(),
+    };
+
+    let start = match envelope_start(pool.get_ref(), &workspace.id).await {
+        Ok(Some(start)) if month >= start => start,
+        Ok(Some(_)) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Month is before the envelope mode start"
+            }));
+        }
+        Ok(None) => return not_enabled(),
+        Err(e) => return database_error(e),
+    };
+
+    for category_id in [&move_data.from_category_id, &move_data.to_category_id] {
+        match category_exists(pool.get_ref(), &workspace.id, category_id).await {
+            Ok(true) => {}
+            Ok(false) => {
+                return HttpResponse::NotFound().json(serde_json::json!({
+                    "error": "Category not found"
+                }));
+            }
+            Err(e) => return database_error(e),
+        }
+    }
+
+    let result = async {
+        let mut tx = pool.begin().await?;
+
+        let balance = locked_envelope_balance(
+            &mut tx,
+            &workspace.id,
+            &move_data.from_category_id,
+            start,
+            month,
+        )
+        .await?;
+        if balance < amount {
+            return Ok(false);
+        }
+
+        for (category_id, value) in [
+            (&move_data.from_category_id, -amount),
+            (&move_data.to_category_id, amount),
+        ] {
+            sqlx::query(
+                "INSERT INTO envelope_assignments (id, workspace_id, user_id, category_id, month, amount, note)
+                 VALUES (?, ?, ?, ?, ?, ?, 'Transferência entre envelopes')"
+            )
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(&workspace.id)
+            .bind(&workspace.user_id)
+            .bind(category_id)
+            .bind(month)
+            .bind(value)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(true)
+    }
+    .await;
+
+    match result {
+        Ok(false) => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Insufficient envelope balance"
+        })),
+        Ok(true) => HttpResponse::Ok().json(serde_json::json!({
+            "message": "Money moved successfully"
+        })),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to move money"
+            }))
+        }
+    }
+}
@@ -1,19 +1,32 @@
 use actix_web::{web, HttpResponse, Responder};
-use chrono::NaiveDate;
-use rust_decimal::Decimal;
+use chrono::{NaiveDate, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, MySqlPool};
+use std::collections::HashMap;
 use validator::Validate;
 
+use crate::handlers::notifications::{notify, notify_once, record_event};
+use crate::handlers::rates::{load_reference_rates, ReferenceRates, INVESTMENT_TYPES};
+use crate::middleware::workspace::Workspace;
+use crate::utils::double_option;
+
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct Goal {
     pub id: String,
+    pub workspace_id: String,
+    // Quem criou a meta
     pub user_id: String,
     pub name: String,
     pub target_amount: Decimal,
     pub current_amount: Decimal,
     pub deadline: NaiveDate,
     pub icon: String,
+    // cdi_percent, fixed ou poupanca; nulo quando o dinheiro não rende
+    pub investment_type: Option<String>,
+    // % do CDI (cdi_percent) ou taxa anual em % (fixed)
+    pub investment_rate: Option<Decimal>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
@@ -25,6 +38,8 @@ pub struct CreateGoal {
     pub target_amount: f64,
     pub deadline: NaiveDate,
     pub icon: Option<String>,
+    pub investment_type: Option<String>,
+    pub investment_rate: Option<f64>,
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -33,27 +48,610 @@ pub struct UpdateGoal {
     pub target_amount: Option<f64>,
     pub deadline: Option<NaiveDate>,
     pub icon: Option<String>,
+    // null remove o investimento da meta
+    #[serde(default, deserialize_with = "double_option")]
+    pub investment_type: Option<Option<String>>,
+    pub investment_rate: Option<f64>,
+}
+
+// Limites aceitos para investment_rate, por tipo de investimento
+const MAX_CDI_PERCENT: f64 = 300.0;
+const MAX_FIXED_RATE: f64 = 100.0;
+
+// Confere a taxa exigida por cada tipo de investimento
+fn investment_rate(investment_type: &str, rate: Option<f64>) -> Result<Option<Decimal>, &'static str> {
+    if !INVESTMENT_TYPES.contains(&investment_type) {
+        return Err("Invalid investment_type");
+    }
+
+    let max = match investment_type {
+        "poupanca" if rate.is_some() => return Err("investment_rate does not apply to poupanca"),
+        "poupanca" => return Ok(None),
+        "cdi_percent" => MAX_CDI_PERCENT,
+        _ => MAX_FIXED_RATE,
+    };
+
+    match rate.map(|rate| (rate, Decimal::from_f64_retain(rate))) {
+        Some((rate, Some(decimal))) if rate > 0.0 && rate <= max => Ok(Some(decimal.round_dp(2))),
+        Some(_) => Err("Invalid investment_rate"),
+        None => Err("investment_rate is required for this investment_type"),
+    }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate)]
 pub struct AddProgress {
+    // Valores negativos são retiradas
     pub amount: f64,
+    #[validate(length(max = 255))]
+    pub note: Option<String>,
+    pub transaction_id: Option<String>,
+    pub date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct GoalContribution {
+    pub id: String,
+    pub goal_id: String,
+    pub user_id: String,
+    pub amount: Decimal,
+    pub note: Option<String>,
+    pub transaction_id: Option<String>,
+    // Regra de poupança automática que gerou o aporte
+    pub autosave_rule_id: Option<String>,
+    pub contributed_at: NaiveDate,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+pub struct NewContribution<'a> {
+    pub amount: Decimal,
+    pub note: Option<&'a str>,
+    pub transaction_id: Option<&'a str>,
+    pub autosave_rule_id: Option<&'a str>,
+    pub date: NaiveDate,
+}
+
+pub struct RecordedContribution {
+    pub goal: Goal,
+    pub contribution: GoalContribution,
+}
+
+pub enum ContributionOutcome {
+    Recorded(Box<RecordedContribution>),
+    GoalNotFound,
+    // A retirada deixaria a meta com saldo negativo
+    InsufficientBalance,
+    // A regra de poupança automática já guardou dinheiro para esta transação
+    AlreadyRecorded,
+}
+
+impl Goal {
+    // owner quando a meta pertence ao espaço de trabalho ativo; member quando
+    // ela foi compartilhada com o usuário a partir de outro espaço
+    pub fn role_in(&self, workspace: &Workspace) -> &'static str {
+        if self.workspace_id == workspace.id {
+            "owner"
+        } else {
+            "member"
+        }
+    }
+
+    // Editores do espaço da meta gerenciam membros, regras e lançamentos de todos
+    pub fn managed_by(&self, workspace: &Workspace) -> bool {
+        self.workspace_id == workspace.id && workspace.can_edit()
+    }
+}
+
+// Podem lançar na meta os owners e editores do espaço dela e os membros convidados
+const CONTRIBUTOR_ACCESS: &str =
+    "(EXISTS (
+         SELECT 1 FROM workspace_members w
+         WHERE w.workspace_id = g.workspace_id AND w.user_id = ? AND w.role IN ('owner', 'editor')
+     ) OR EXISTS (
+         SELECT 1 FROM goal_members m WHERE m.goal_id = g.id AND m.user_id = ?
+     ))";
+
+// Registra um aporte (ou retirada) de `user_id`, editor do espaço da meta ou
+// membro dela, no histórico e recalcula current_amount, tudo na mesma
+// transação do banco
+pub async fn record_contribution(
+    pool: &MySqlPool,
+    user_id: &str,
+    goal_id: &str,
+    contribution: NewContribution<'_>,
+) -> Result<ContributionOutcome, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let goal = sqlx::query_as::<_, Goal>(&format!(
+        "SELECT * FROM goals g WHERE g.id = ? AND {} FOR UPDATE",
+        CONTRIBUTOR_ACCESS
+    ))
+    .bind(goal_id)
+    .bind(user_id)
+    .bind(user_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let goal = match goal {
+        Some(goal) => goal,
+        None => return Ok(ContributionOutcome::GoalNotFound),
+    };
+
+    if goal.current_amount + contribution.amount < Decimal::ZERO {
+        return Ok(ContributionOutcome::InsufficientBalance);
+    }
+
+    record_opening_balance(&mut tx, &goal).await?;
+
+    // A chave única (autosave_rule_id, transaction_id) impede que a mesma
+    // regra guarde duas vezes para a mesma transação
+    let contribution_id = uuid::Uuid::new_v4().to_string();
+    let inserted = sqlx::query(
+        "INSERT IGNORE INTO goal_contributions (id, goal_id, user_id, amount, note, transaction_id, autosave_rule_id, contributed_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+    )
+    .bind(&contribution_id)
+    .bind(&goal.id)
+    .bind(user_id)
+    .bind(contribution.amount)
+    .bind(contribution.note)
+    .bind(contribution.transaction_id)
+    .bind(contribution.autosave_rule_id)
+    .bind(contribution.date)
+    .execute(&mut *tx)
+    .await?;
+
+    if inserted.rows_affected() == 0 {
+        return Ok(ContributionOutcome::AlreadyRecorded);
+    }
+
+    let goal = refresh_current_amount(&mut tx, &goal.id).await?;
+
+    let contribution = sqlx::query_as::<_, GoalContribution>(
+        "SELECT * FROM goal_contributions WHERE id = ?"
+    )
+    .bind(&contribution_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(ContributionOutcome::Recorded(Box::new(RecordedContribution {
+        goal,
+        contribution,
+    })))
+}
+
+// Metas anteriores ao histórico de aportes têm saldo sem lançamentos. Antes de
+// recalcular current_amount pelo histórico, a diferença entra como um aporte
+// "Saldo inicial" de quem criou a meta, na data de criação.
+pub async fn record_opening_balance(
+    tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+    goal: &Goal,
+) -> Result<(), sqlx::Error> {
+    let recorded = sqlx::query_scalar::<_, Decimal>(
+        "SELECT COALESCE(SUM(amount), 0) FROM goal_contributions WHERE goal_id = ?"
+    )
+    .bind(&goal.id)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    let opening = goal.current_amount - recorded;
+    if opening.is_zero() {
+        return Ok(());
+    }
+
+    sqlx::query(
+        "INSERT INTO goal_contributions (id, goal_id, user_id, amount, note, contributed_at)
+         VALUES (?, ?, ?, ?, 'Saldo inicial', ?)"
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(&goal.id)
+    .bind(&goal.user_id)
+    .bind(opening)
+    .bind(goal.created_at.date_naive())
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn refresh_current_amount(
+    tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+    goal_id: &str,
+) -> Result<Goal, sqlx::Error> {
+    sqlx::query(
+        "UPDATE goals
+         SET current_amount = (SELECT COALESCE(SUM(amount), 0) FROM goal_contributions WHERE goal_id = ?)
+         WHERE id = ?"
+    )
+    .bind(goal_id)
+    .bind(goal_id)
+    .execute(&mut **tx)
+    .await?;
+
+    sqlx::query_as::<_, Goal>("SELECT * FROM goals WHERE id = ?")
+        .bind(goal_id)
+        .fetch_one(&mut **tx)
+        .await
+}
+
+// Janela usada para medir o ritmo de aportes
+const PACE_WINDOW_DAYS: i64 = 90;
+
+#[derive(Debug, Serialize)]
+pub struct GoalProjection {
+    pub percent_complete: Decimal,
+    pub remaining_amount: Decimal,
+    pub days_remaining: i64,
+    // Nulos quando a meta já foi atingida ou o prazo passou
+    pub required_per_month: Option<Decimal>,
+    pub required_per_week: Option<Decimal>,
+    // Aporte líquido médio por mês nos últimos PACE_WINDOW_DAYS dias
+    pub monthly_pace: Decimal,
+    pub projected_completion_date: Option<NaiveDate>,
+    // Saldo previsto no prazo mantendo o ritmo (e o rendimento, se houver)
+    pub projected_balance_at_deadline: Option<Decimal>,
+    // Rendimento anual efetivo do investimento da meta, em %
+    pub annual_yield_rate: Option<Decimal>,
+    // completed, on_track, at_risk ou overdue
+    pub status: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GoalWithProjection {
+    #[serde(flatten)]
+    pub goal: Goal,
+    // owner ou member (meta compartilhada com o usuário)
+    pub role: &'static str,
+    pub projection: GoalProjection,
+}
+
+fn to_money(value: f64) -> Option<Decimal> {
+    Decimal::from_f64_retain(value).map(|value| value.round_dp(2))
+}
+
+impl Goal {
+    // Taxa mensal (fração) do investimento da meta, se houver
+    pub fn monthly_rate(&self, rates: &ReferenceRates) -> Option<f64> {
+        let investment_type = self.investment_type.as_deref()?;
+        rates
+            .monthly_rate(investment_type, self.investment_rate)
+            .filter(|rate| *rate > 0.0)
+    }
+
+    // `recent_net` é a soma dos aportes da meta desde `pace_window_start(today)`.
+    // Com `monthly_rate`, saldo e aportes rendem juros compostos mensais.
+    pub fn projection(
+        &self,
+        recent_net: Decimal,
+        monthly_rate: Option<f64>,
+        today: NaiveDate,
+    ) -> GoalProjection {
+        // Taxa zero não rende: a projeção é a mesma de uma meta sem investimento
+        let monthly_rate = monthly_rate.filter(|rate| *rate > 0.0);
+        let remaining = (self.target_amount - self.current_amount).max(Decimal::ZERO);
+        let percent_complete = if self.target_amount.is_zero() {
+            Decimal::ZERO
+        } else {
+            (self.current_amount * Decimal::from(100) / self.target_amount).round_dp(2)
+        };
+        let days_remaining = (self.deadline - today).num_days();
+
+        // O ritmo é medido desde a criação da meta quando ela é mais nova que a janela
+        let observed_days = (today - self.created_at.date_naive()).num_days() + 1;
+        let observed_days = observed_days.clamp(1, PACE_WINDOW_DAYS);
+        let daily_pace = recent_net / Decimal::from(observed_days);
+        let monthly_pace = (daily_pace * Decimal::from(365) / Decimal::from(12)).round_dp(2);
+
+        let completed = remaining.is_zero();
+        let round_up = |value: Decimal| value.round_dp_with_strategy(2, RoundingStrategy::AwayFromZero);
+
+        let (required_per_month, required_per_week, projected_completion_date, balance_at_deadline) =
+            match monthly_rate {
+                Some(rate) => {
+                    let current = self.current_amount.to_f64().unwrap_or(0.0);
+                    let target = self.target_amount.to_f64().unwrap_or(0.0);
+                    let pace = monthly_pace.to_f64().unwrap_or(0.0);
+                    let months = days_remaining.max(0) as f64 * 12.0 / 365.0;
+                    let growth = (1.0 + rate).powf(months);
+
+                    // Aporte mensal que, somado ao rendimento do saldo atual, atinge o alvo no prazo
+                    let required_month = if completed || days_remaining <= 0 {
+                        None
+                    } else {
+                        let needed = (target - current * growth) * rate / (growth - 1.0);
+                        to_money(needed.max(0.0)).map(round_up)
+                    };
+                    let required_week = required_month
+                        .map(|month| round_up(month * Decimal::from(12) / Decimal::from(52)));
+
+                    // Meses até o saldo com juros e aportes no ritmo atual alcançar o alvo
+                    let completion = if completed {
+                        Some(today)
+                    } else {
+                        let ratio = (target * rate + pace) / (current * rate + pace);
+                        let months = ratio.ln() / (1.0 + rate).ln();
+                        if current * rate + pace > 0.0 && months.is_finite() && months >= 0.0 {
+                            let days = (months * 365.0 / 12.0).ceil() as i64;
+                            today.checked_add_signed(chrono::Duration::days(days))
+                        } else {
+                            None
+                        }
+                    };
+
+                    let balance = current * growth + pace * (growth - 1.0) / rate;
+                    (required_month, required_week, completion, to_money(balance))
+                }
+                None => {
+                    let (required_month, required_week) = if completed || days_remaining <= 0 {
+                        (None, None)
+                    } else {
+                        let per_day = remaining / Decimal::from(days_remaining);
+                        (
+                            Some(round_up(per_day * Decimal::from(365) / Decimal::from(12))),
+                            Some(round_up(per_day * Decimal::from(7))),
+                        )
+                    };
+
+                    let completion = if completed {
+                        Some(today)
+                    } else if daily_pace > Decimal::ZERO {
+                        let days = (remaining / daily_pace).ceil();
+                        i64::try_from(days)
+                            .ok()
+                            .and_then(|days| today.checked_add_signed(chrono::Duration::days(days)))
+                    } else {
+                        None
+                    };
+
+                    let balance = self.current_amount + daily_pace * Decimal::from(days_remaining.max(0));
+                    (required_month, required_week, completion, Some(balance.round_dp(2)))
+                }
+            };
+
+        let status = if completed {
+            "completed"
+        } else if days_remaining < 0 {
+            "overdue"
+        } else if projected_completion_date.is_some_and(|date| date <= self.deadline) {
+            "on_track"
+        } else {
+            "at_risk"
+        };
+
+        let annual_yield_rate = monthly_rate.and_then(|rate| to_money(((1.0 + rate).powi(12) - 1.0) * 100.0));
+
+        GoalProjection {
+            percent_complete,
+            remaining_amount: remaining,
+            days_remaining,
+            required_per_month,
+            required_per_week,
+            monthly_pace,
+            projected_completion_date,
+            projected_balance_at_deadline: balance_at_deadline.filter(|_| days_remaining >= 0),
+            annual_yield_rate,
+            status,
+        }
+    }
+}
+
+pub fn pace_window_start(today: NaiveDate) -> NaiveDate {
+    today - chrono::Duration::days(PACE_WINDOW_DAYS - 1)
+}
+
+// Soma dos aportes recentes de cada meta, por goal_id. Com `workspace`, só as
+// metas do espaço e as compartilhadas com o usuário; sem, as de todos.
+async fn recent_contributions(
+    pool: &MySqlPool,
+    workspace: Option<&Workspace>,
+    today: NaiveDate,
+) -> Result<HashMap<String, Decimal>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, (String, Decimal)>(
+        "SELECT c.goal_id, COALESCE(SUM(c.amount), 0)
+         FROM goal_contributions c
+         JOIN goals g ON g.id = c.goal_id
+         WHERE (? IS NULL OR g.workspace_id = ? OR EXISTS (
+             SELECT 1 FROM goal_members m WHERE m.goal_id = g.id AND m.user_id = ?
+         ))
+         AND c.contributed_at BETWEEN ? AND ?
+         GROUP BY c.goal_id"
+    )
+    .bind(workspace.map(|w| &w.id))
+    .bind(workspace.map(|w| &w.id))
+    .bind(workspace.map(|w| &w.user_id))
+    .bind(pace_window_start(today))
+    .bind(today)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().collect())
+}
+
+const MILESTONES: [u32; 4] = [25, 50, 75, 100];
+// Avisos de prazo para metas com ritmo insuficiente, do mais distante ao mais próximo
+const DEADLINE_REMINDER_DAYS: [i64; 2] = [30, 7];
+
+// Membros do espaço da meta e convidados, que recebem as notificações dela
+async fn goal_participants(pool: &MySqlPool, goal: &Goal) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>(
+        "SELECT user_id FROM workspace_members WHERE workspace_id = ?
+         UNION
+         SELECT user_id FROM goal_members WHERE goal_id = ?"
+    )
+    .bind(&goal.workspace_id)
+    .bind(&goal.id)
+    .fetch_all(pool)
+    .await
+}
+
+// Avisa (uma única vez por meta e participante) ao cruzar 25/50/75/100% do
+// valor alvo. Um aporte que cruza vários marcos de uma vez avisa só o maior;
+// os menores ficam registrados para não serem avisados depois.
+// Retorna quantas notificações foram criadas.
+pub async fn check_milestones(pool: &MySqlPool, goal: &Goal) -> Result<u32, sqlx::Error> {
+    if goal.target_amount <= Decimal::ZERO {
+        return Ok(0);
+    }
+
+    let percent = goal.current_amount * Decimal::from(100) / goal.target_amount;
+    let crossed: Vec<u32> = MILESTONES
+        .into_iter()
+        .filter(|milestone| percent >= Decimal::from(*milestone))
+        .collect();
+    let Some((&highest, lower)) = crossed.split_last() else {
+        return Ok(0);
+    };
+
+    let participants = goal_participants(pool, goal).await?;
+    let event_key = |milestone: u32| format!("goal:{}:milestone:{}", goal.id, milestone);
+
+    let (title, message) = if highest >= 100 {
+        (
+            "Meta atingida".to_string(),
+            format!("Parabéns! Você atingiu a meta '{}'.", goal.name),
+        )
+    } else {
+        (
+            "Progresso da meta".to_string(),
+            format!("Sua meta '{}' atingiu {}% do progresso!", goal.name, highest),
+        )
+    };
+
+    let mut sent = 0;
+    for participant in &participants {
+        for milestone in lower {
+            record_event(pool, participant, &event_key(*milestone)).await?;
+        }
+        if notify_once(pool, participant, &event_key(highest), &title, &message, "success").await? {
+            sent += 1;
+        }
+    }
+
+    Ok(sent)
+}
+
+// Avisos de prazo: a 30 e 7 dias do fim quando a meta está fora do ritmo, e
+// quando o prazo passa sem a meta ser atingida. Também recupera marcos que
+// não foram avisados no momento do aporte.
+pub async fn send_goal_reminders(pool: &MySqlPool, today: NaiveDate) -> Result<u32, sqlx::Error> {
+    let goals = sqlx::query_as::<_, Goal>("SELECT * FROM goals")
+        .fetch_all(pool)
+        .await?;
+    let recent = recent_contributions(pool, None, today).await?;
+    let rates = load_reference_rates(pool).await?;
+
+    let mut sent = 0;
+    for goal in &goals {
+        sent += check_milestones(pool, goal).await?;
+
+        let recent_net = recent.get(&goal.id).copied().unwrap_or(Decimal::ZERO);
+        let projection = goal.projection(recent_net, goal.monthly_rate(&rates), today);
+
+        let notification = match projection.status {
+            "overdue" => Some((
+                format!("goal:{}:overdue", goal.id),
+                "Prazo da meta encerrado".to_string(),
+                format!(
+                    "O prazo da meta '{}' terminou em {} e faltaram R$ {}.",
+                    goal.name,
+                    goal.deadline.format("%d/%m/%Y"),
+                    projection.remaining_amount
+                ),
+            )),
+            "at_risk" => DEADLINE_REMINDER_DAYS
+                .iter()
+                .rev()
+                .find(|days| projection.days_remaining <= **days)
+                .map(|days| {
+                    let required = projection
+                        .required_per_week
+                        .map(|amount| format!(" Guarde R$ {} por semana para chegar lá.", amount))
+                        .unwrap_or_default();
+                    (
+                        format!("goal:{}:deadline:{}", goal.id, days),
+                        "Meta fora do ritmo".to_string(),
+                        format!(
+                            "Faltam {} dias para o prazo da meta '{}' e ainda faltam R$ {}.{}",
+                            projection.days_remaining, goal.name, projection.remaining_amount, required
+                        ),
+                    )
+                }),
+            _ => None,
+        };
+
+        if let Some((event_key, title, message)) = notification {
+            for participant in goal_participants(pool, goal).await? {
+                if notify_once(pool, &participant, &event_key, &title, &message, "warning").await? {
+                    sent += 1;
+                }
+            }
+        }
+    }
+
+    Ok(sent)
+}
+
+// Busca uma meta do espaço de trabalho ou compartilhada com o usuário
+pub async fn find_goal(
+    pool: &MySqlPool,
+    goal_id: &str,
+    workspace: &Workspace,
+) -> Result<Option<Goal>, sqlx::Error> {
+    sqlx::query_as::<_, Goal>(
+        "SELECT * FROM goals g
+         WHERE g.id = ? AND (g.workspace_id = ? OR EXISTS (
+             SELECT 1 FROM goal_members m WHERE m.goal_id = g.id AND m.user_id = ?
+         ))"
+    )
+    .bind(goal_id)
+    .bind(&workspace.id)
+    .bind(&workspace.user_id)
+    .fetch_optional(pool)
+    .await
 }
 
 // GET /api/goals - Listar todas as metas
 pub async fn get_all(
     pool: web::Data<MySqlPool>,
-    user_id: web::ReqData<String>,
+    workspace: Workspace,
 ) -> impl Responder {
+    let today = Utc::now().date_naive();
+
     let goals = sqlx::query_as::<_, Goal>(
-        "SELECT * FROM goals WHERE user_id = ? ORDER BY deadline ASC"
+        "SELECT * FROM goals g
+         WHERE g.workspace_id = ? OR EXISTS (
+             SELECT 1 FROM goal_members m WHERE m.goal_id = g.id AND m.user_id = ?
+         )
+         ORDER BY g.deadline ASC"
     )
-    .bind(user_id.into_inner())
+    .bind(&workspace.id)
+    .bind(&workspace.user_id)
     .fetch_all(pool.get_ref())
     .await;
 
+    let goals = match goals {
+        Ok(goals) => match recent_contributions(pool.get_ref(), Some(&workspace), today).await {
+            Ok(recent) => load_reference_rates(pool.get_ref())
+                .await
+                .map(|rates| (goals, recent, rates)),
+            Err(e) => Err(e),
+        },
+        Err(e) => Err(e),
+    };
+
     match goals {
-        Ok(goals) => HttpResponse::Ok().json(goals),
+        Ok((goals, recent, rates)) => {
+            let goals: Vec<GoalWithProjection> = goals
+                .into_iter()
+                .map(|goal| {
+                    let recent_net = recent.get(&goal.id).copied().unwrap_or(Decimal::ZERO);
+                    let projection = goal.projection(recent_net, goal.monthly_rate(&rates), today);
+                    let role = goal.role_in(&workspace);
+                    GoalWithProjection { goal, role, projection }
+                })
+                .collect();
+            HttpResponse::Ok().json(goals)
+        }
         Err(e) => {
             eprintln!("Database error: {}", e);
             HttpResponse::InternalServerError().json(serde_json::json!({
@@ -66,16 +664,10 @@ pub async fn get_all(
 // GET /api/goals/{id} - Buscar meta por ID
 pub async fn get_by_id(
     pool: web::Data<MySqlPool>,
-    user_id: web::ReqData<String>,
+    workspace: Workspace,
     goal_id: web::Path<String>,
 ) -> impl Responder {
-    let goal = sqlx::query_as::<_, Goal>(
-        "SELECT * FROM goals WHERE id = ? AND user_id = ?"
-    )
-    .bind(goal_id.into_inner())
-    .bind(user_id.into_inner())
-    .fetch_optional(pool.get_ref())
-    .await;
+    let goal = find_goal(pool.get_ref(), &goal_id, &workspace).await;
 
     match goal {
         Ok(Some(goal)) => HttpResponse::Ok().json(goal),
@@ -91,12 +683,71 @@ pub async fn get_by_id(
     }
 }
 
+// GET /api/goals/{id}/projection - Projeção de conclusão e aporte necessário
+pub async fn projection(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    goal_id: web::Path<String>,
+) -> impl Responder {
+    let today = Utc::now().date_naive();
+
+    let goal = find_goal(pool.get_ref(), &goal_id, &workspace).await;
+
+    let goal = match goal {
+        Ok(Some(goal)) => goal,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Goal not found"
+            }));
+        }
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            }));
+        }
+    };
+
+    let recent_net = sqlx::query_scalar::<_, Decimal>(
+        "SELECT COALESCE(SUM(amount), 0) FROM goal_contributions
+         WHERE goal_id = ? AND contributed_at BETWEEN ? AND ?"
+    )
+    .bind(&goal.id)
+    .bind(pace_window_start(today))
+    .bind(today)
+    .fetch_one(pool.get_ref())
+    .await;
+
+    let recent_net = match recent_net {
+        Ok(recent_net) => load_reference_rates(pool.get_ref())
+            .await
+            .map(|rates| (recent_net, rates)),
+        Err(e) => Err(e),
+    };
+
+    match recent_net {
+        Ok((recent_net, rates)) => {
+            HttpResponse::Ok().json(goal.projection(recent_net, goal.monthly_rate(&rates), today))
+        }
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            }))
+        }
+    }
+}
+
 // POST /api/goals - Criar nova meta
 pub async fn create(
     pool: web::Data<MySqlPool>,
-    user_id: web::ReqData<String>,
+    workspace: Workspace,
     goal_data: web::Json<CreateGoal>,
 ) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
     if let Err(errors) = goal_data.validate() {
         return HttpResponse::BadRequest().json(serde_json::json!({
             "error": "Validation failed",
@@ -111,19 +762,36 @@ pub async fn create(
         }));
     }
 
+    let rate = match &goal_data.investment_type {
+        Some(investment_type) => investment_rate(investment_type, goal_data.investment_rate),
+        None if goal_data.investment_rate.is_some() => Err("investment_rate requires investment_type"),
+        None => Ok(None),
+    };
+    let rate = match rate {
+        Ok(rate) => rate,
+        Err(error) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": error
+            }));
+        }
+    };
+
     let icon = goal_data.icon.as_deref().unwrap_or("🎯");
     let goal_id = uuid::Uuid::new_v4().to_string();
 
     let result = sqlx::query(
-        "INSERT INTO goals (id, user_id, name, target_amount, current_amount, deadline, icon)
-         VALUES (?, ?, ?, ?, 0, ?, ?)"
+        "INSERT INTO goals (id, workspace_id, user_id, name, target_amount, current_amount, deadline, icon, investment_type, investment_rate)
+         VALUES (?, ?, ?, ?, ?, 0, ?, ?, ?, ?)"
     )
     .bind(&goal_id)
-    .bind(user_id.into_inner())
+    .bind(&workspace.id)
+    .bind(&workspace.user_id)
     .bind(&goal_data.name)
     .bind(target.unwrap())
     .bind(goal_data.deadline)
     .bind(icon)
+    .bind(&goal_data.investment_type)
+    .bind(rate)
     .execute(pool.get_ref())
     .await;
 
@@ -149,10 +817,14 @@ pub async fn create(
 // PUT /api/goals/{id} - Atualizar meta
 pub async fn update(
     pool: web::Data<MySqlPool>,
-    user_id: web::ReqData<String>,
+    workspace: Workspace,
     goal_id: web::Path<String>,
     update_data: web::Json<UpdateGoal>,
 ) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
     let mut query = String::from("UPDATE goals SET ");
     let mut updates = Vec::new();
     let mut has_updates = false;
@@ -176,6 +848,63 @@ pub async fn update(
         has_updates = true;
     }
 
+    let investment = match &update_data.investment_type {
+        Some(Some(investment_type)) => investment_rate(investment_type, update_data.investment_rate)
+            .map(|rate| Some((investment_type.clone(), rate))),
+        Some(None) if update_data.investment_rate.is_some() => {
+            Err("investment_rate requires investment_type")
+        }
+        Some(None) => Ok(None),
+        // Só a taxa: validada pelo tipo de investimento já salvo na meta
+        None if update_data.investment_rate.is_some() => {
+            let stored = sqlx::query_scalar::<_, Option<String>>(
+                "SELECT investment_type FROM goals WHERE id = ? AND workspace_id = ?"
+            )
+            .bind(goal_id.as_str())
+            .bind(&workspace.id)
+            .fetch_optional(pool.get_ref())
+            .await;
+
+            match stored {
+                Ok(Some(Some(investment_type))) => investment_rate(&investment_type, update_data.investment_rate)
+                    .map(|rate| Some((investment_type, rate))),
+                Ok(Some(None)) => Err("investment_rate requires investment_type"),
+                Ok(None) => {
+                    return HttpResponse::NotFound().json(serde_json::json!({
+                        "error": "Goal not found"
+                    }));
+                }
+                Err(e) => {
+                    eprintln!("Database error: {}", e);
+                    return HttpResponse::InternalServerError().json(serde_json::json!({
+                        "error": "Failed to update goal"
+                    }));
+                }
+            }
+        }
+        None => Ok(None),
+    };
+    match investment {
+        Ok(Some((investment_type, rate))) => {
+            updates.push(format!("investment_type = '{}'", investment_type));
+            updates.push(match rate {
+                Some(rate) => format!("investment_rate = {}", rate),
+                None => "investment_rate = NULL".to_string(),
+            });
+            has_updates = true;
+        }
+        Ok(None) if update_data.investment_type.is_some() => {
+            updates.push("investment_type = NULL, investment_rate = NULL".to_string());
+            has_updates = true;
+        }
+        Ok(None) => {}
+        Err(error) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": error
+            }));
+        }
+    }
+
     if !has_updates {
         return HttpResponse::BadRequest().json(serde_json::json!({
             "error": "No fields to update"
@@ -183,7 +912,7 @@ pub async fn update(
     }
 
     query.push_str(&updates.join(", "));
-    query.push_str(&format!(" WHERE id = '{}' AND user_id = '{}'", goal_id.into_inner(), user_id.into_inner()));
+    query.push_str(&format!(" WHERE id = '{}' AND workspace_id = '{}'", goal_id.into_inner(), workspace.id));
 
     let result = sqlx::query(&query).execute(pool.get_ref()).await;
 
@@ -205,41 +934,106 @@ pub async fn update(
     }
 }
 
-// POST /api/goals/{id}/progress - Adicionar progresso
+// POST /api/goals/{id}/progress - Adicionar progresso (ou retirar, com valor negativo)
 pub async fn add_progress(
     pool: web::Data<MySqlPool>,
-    user_id: web::ReqData<String>,
+    workspace: Workspace,
     goal_id: web::Path<String>,
     progress_data: web::Json<AddProgress>,
 ) -> impl Responder {
-    if progress_data.amount <= 0.0 {
+    if let Err(errors) = progress_data.validate() {
         return HttpResponse::BadRequest().json(serde_json::json!({
-            "error": "Amount must be positive"
+            "error": "Validation failed",
+            "details": errors.to_string()
         }));
     }
 
-    let amount = Decimal::from_f64_retain(progress_data.amount).unwrap();
+    let amount = match Decimal::from_f64_retain(progress_data.amount) {
+        Some(amount) if !amount.round_dp(2).is_zero() => amount.round_dp(2),
+        _ => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Amount must not be zero"
+            }));
+        }
+    };
 
-    let result = sqlx::query(
-        "UPDATE goals 
-         SET current_amount = current_amount + ? 
-         WHERE id = ? AND user_id = ?"
-    )
-    .bind(amount)
-    .bind(goal_id.into_inner())
-    .bind(user_id.into_inner())
-    .execute(pool.get_ref())
-    .await;
+    // Leitores do espaço não lançam nas metas dele
+    match find_goal(pool.get_ref(), &goal_id, &workspace).await {
+        Ok(Some(goal)) if goal.workspace_id == workspace.id => {
+            if let Err(response) = workspace.require_editor() {
+                return response;
+            }
+        }
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Goal not found"
+            }));
+        }
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            }));
+        }
+    }
+
+    if let Some(transaction_id) = &progress_data.transaction_id {
+        let exists = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM transactions WHERE id = ? AND workspace_id = ?"
+        )
+        .bind(transaction_id)
+        .bind(&workspace.id)
+        .fetch_one(pool.get_ref())
+        .await;
+
+        match exists {
+            Ok(0) => {
+                return HttpResponse::NotFound().json(serde_json::json!({
+                    "error": "Transaction not found"
+                }));
+            }
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("Database error: {}", e);
+                return HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": "Database error"
+                }));
+            }
+        }
+    }
+
+    let contribution = NewContribution {
+        amount,
+        note: progress_data.note.as_deref(),
+        transaction_id: progress_data.transaction_id.as_deref(),
+        autosave_rule_id: None,
+        date: progress_data.date.unwrap_or_else(|| Utc::now().date_naive()),
+    };
+
+    match record_contribution(pool.get_ref(), &workspace.user_id, &goal_id, contribution).await {
+        Ok(ContributionOutcome::Recorded(recorded)) => {
+            if let Err(e) = check_milestones(pool.get_ref(), &recorded.goal).await {
+                eprintln!("Failed to check goal milestones: {}", e);
+            }
 
-    match result {
-        Ok(result) if result.rows_affected() > 0 => {
             HttpResponse::Ok().json(serde_json::json!({
-                "message": "Progress added successfully"
+                "message": "Progress added successfully",
+                "goal": recorded.goal,
+                "contribution": recorded.contribution
             }))
         }
-        Ok(_) => HttpResponse::NotFound().json(serde_json::json!({
+        Ok(ContributionOutcome::GoalNotFound) => HttpResponse::NotFound().json(serde_json::json!({
             "error": "Goal not found"
         })),
+        Ok(ContributionOutcome::InsufficientBalance) => {
+            HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Withdrawal exceeds the goal balance"
+            }))
+        }
+        Ok(ContributionOutcome::AlreadyRecorded) => HttpResponse::Conflict().json(serde_json::json!({
+            "error": "Contribution already recorded"
+        })),
         Err(e) => {
             eprintln!("Database error: {}", e);
             HttpResponse::InternalServerError().json(serde_json::json!({
@@ -249,15 +1043,142 @@ pub async fn add_progress(
     }
 }
 
+// GET /api/goals/{id}/contributions - Histórico de aportes e retiradas
+pub async fn get_contributions(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    goal_id: web::Path<String>,
+) -> impl Responder {
+    let goal = find_goal(pool.get_ref(), &goal_id, &workspace).await;
+
+    match goal {
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Goal not found"
+            }));
+        }
+        Ok(Some(_)) => {}
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            }));
+        }
+    }
+
+    let contributions = sqlx::query_as::<_, GoalContribution>(
+        "SELECT * FROM goal_contributions
+         WHERE goal_id = ?
+         ORDER BY contributed_at DESC, created_at DESC"
+    )
+    .bind(goal_id.into_inner())
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match contributions {
+        Ok(contributions) => HttpResponse::Ok().json(contributions),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to fetch contributions"
+            }))
+        }
+    }
+}
+
+// DELETE /api/goals/{id}/contributions/{contribution_id} - Desfazer um aporte ou retirada
+// (membros só desfazem os próprios lançamentos; editores do espaço da meta, qualquer um)
+pub async fn delete_contribution(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let (goal_id, contribution_id) = path.into_inner();
+    let uid = &workspace.user_id;
+
+    let result = async {
+        let mut tx = pool.begin().await?;
+
+        let goal = sqlx::query_as::<_, Goal>(&format!(
+            "SELECT * FROM goals g WHERE g.id = ? AND {} FOR UPDATE",
+            CONTRIBUTOR_ACCESS
+        ))
+        .bind(&goal_id)
+        .bind(uid)
+        .bind(uid)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(goal) = goal else {
+            return Ok(None);
+        };
+
+        let amount = sqlx::query_scalar::<_, Decimal>(
+            "SELECT amount FROM goal_contributions WHERE id = ? AND goal_id = ? AND (user_id = ? OR ?)"
+        )
+        .bind(&contribution_id)
+        .bind(&goal.id)
+        .bind(uid)
+        .bind(goal.managed_by(&workspace))
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(amount) = amount else {
+            return Ok(None);
+        };
+
+        // Desfazer um aporte não pode deixar a meta com saldo negativo
+        if goal.current_amount - amount < Decimal::ZERO {
+            return Ok(Some(None));
+        }
+
+        record_opening_balance(&mut tx, &goal).await?;
+
+        sqlx::query("DELETE FROM goal_contributions WHERE id = ?")
+            .bind(&contribution_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let goal = refresh_current_amount(&mut tx, &goal.id).await?;
+        tx.commit().await?;
+
+        Ok::<_, sqlx::Error>(Some(Some(goal)))
+    }
+    .await;
+
+    match result {
+        Ok(Some(Some(goal))) => HttpResponse::Ok().json(serde_json::json!({
+            "message": "Contribution deleted successfully",
+            "goal": goal
+        })),
+        Ok(Some(None)) => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Removing this contribution would make the goal balance negative"
+        })),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Contribution not found"
+        })),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to delete contribution"
+            }))
+        }
+    }
+}
+
 // DELETE /api/goals/{id} - Deletar meta
 pub async fn delete(
     pool: web::Data<MySqlPool>,
-    user_id: web::ReqData<String>,
+    workspace: Workspace,
     goal_id: web::Path<String>,
 ) -> impl Responder {
-    let result = sqlx::query("DELETE FROM goals WHERE id = ? AND user_id = ?")
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    let result = sqlx::query("DELETE FROM goals WHERE id = ? AND workspace_id = ?")
         .bind(goal_id.into_inner())
-        .bind(user_id.into_inner())
+        .bind(&workspace.id)
         .execute(pool.get_ref())
         .await;
 
@@ -278,3 +1199,610 @@ pub async fn delete(
         }
     }
 }
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct AddMember {
+    #[validate(email)]
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct GoalMember {
+    pub user_id: String,
+    pub full_name: String,
+    pub email: String,
+    // owner (membro do espaço da meta) ou member (convidado)
+    pub role: String,
+    // Soma dos aportes e retiradas feitos por este participante
+    pub contributed_amount: Decimal,
+    pub joined_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct GoalInvitation {
+    pub id: String,
+    pub goal_id: String,
+    pub goal_name: String,
+    pub email: String,
+    // pending, accepted, declined ou revoked
+    pub status: String,
+    pub invited_by: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub responded_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+const GOAL_INVITATION_COLUMNS: &str =
+    "i.id, i.goal_id, g.name AS goal_name, i.email, i.status, i.invited_by, i.created_at, i.responded_at";
+
+async fn load_members(pool: &MySqlPool, goal_id: &str) -> Result<Vec<GoalMember>, sqlx::Error> {
+    sqlx::query_as::<_, GoalMember>(
+        "SELECT u.id AS user_id, u.full_name, u.email, 'owner' AS role,
+                COALESCE((SELECT SUM(c.amount) FROM goal_contributions c
+                          WHERE c.goal_id = g.id AND c.user_id = u.id), 0) AS contributed_amount,
+                w.created_at AS joined_at
+         FROM goals g
+         JOIN workspace_members w ON w.workspace_id = g.workspace_id
+         JOIN users u ON u.id = w.user_id
+         WHERE g.id = ?
+         UNION ALL
+         SELECT u.id, u.full_name, u.email, 'member',
+                COALESCE((SELECT SUM(c.amount) FROM goal_contributions c
+                          WHERE c.goal_id = m.goal_id AND c.user_id = u.id), 0),
+                m.created_at
+         FROM goal_members m JOIN users u ON u.id = m.user_id
+         WHERE m.goal_id = ?
+         ORDER BY joined_at ASC"
+    )
+    .bind(goal_id)
+    .bind(goal_id)
+    .fetch_all(pool)
+    .await
+}
+
+// GET /api/goals/{id}/members - Participantes da meta e quanto cada um guardou
+pub async fn get_members(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    goal_id: web::Path<String>,
+) -> impl Responder {
+    let goal = match find_goal(pool.get_ref(), &goal_id, &workspace).await {
+        Ok(Some(goal)) => goal,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Goal not found"
+            }));
+        }
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            }));
+        }
+    };
+
+    match load_members(pool.get_ref(), &goal.id).await {
+        Ok(members) => HttpResponse::Ok().json(members),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to fetch members"
+            }))
+        }
+    }
+}
+
+// Meta do espaço ativo que o usuário pode administrar (editores do espaço)
+async fn managed_goal(
+    pool: &MySqlPool,
+    workspace: &Workspace,
+    goal_id: &str,
+) -> Result<Goal, HttpResponse> {
+    workspace.require_editor()?;
+
+    let goal = sqlx::query_as::<_, Goal>("SELECT * FROM goals WHERE id = ? AND workspace_id = ?")
+        .bind(goal_id)
+        .bind(&workspace.id)
+        .fetch_optional(pool)
+        .await;
+
+    match goal {
+        Ok(Some(goal)) => Ok(goal),
+        Ok(None) => Err(HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Goal not found"
+        }))),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            Err(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            })))
+        }
+    }
+}
+
+// POST /api/goals/{id}/members - Convidar usuário para a meta pelo e-mail
+// (somente editores do espaço da meta). O convite fica pendente até ser
+// aceito; e-mails com e sem cadastro recebem a mesma resposta.
+pub async fn invite_member(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    goal_id: web::Path<String>,
+    member_data: web::Json<AddMember>,
+) -> impl Responder {
+    if let Err(errors) = member_data.validate() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Validation failed",
+            "details": errors.to_string()
+        }));
+    }
+
+    let goal = match managed_goal(pool.get_ref(), &workspace, &goal_id).await {
+        Ok(goal) => goal,
+        Err(response) => return response,
+    };
+    let email = member_data.email.trim().to_lowercase();
+
+    // Quem convida já vê os participantes da meta, então avisar que o e-mail
+    // já tem acesso não revela nada novo
+    let invitee = sqlx::query_as::<_, (String, bool)>(
+        "SELECT u.id, (EXISTS(
+             SELECT 1 FROM workspace_members w WHERE w.workspace_id = ? AND w.user_id = u.id
+         ) OR EXISTS(
+             SELECT 1 FROM goal_members m WHERE m.goal_id = ? AND m.user_id = u.id
+         )) AS has_access
+         FROM users u WHERE u.email = ?"
+    )
+    .bind(&goal.workspace_id)
+    .bind(&goal.id)
+    .bind(&email)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    let invitee_id = match invitee {
+        Ok(Some((_, true))) => {
+            return HttpResponse::Conflict().json(serde_json::json!({
+                "error": "User already has access to this goal"
+            }));
+        }
+        Ok(Some((id, false))) => Some(id),
+        Ok(None) => None,
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            }));
+        }
+    };
+
+    let pending = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM goal_invitations WHERE goal_id = ? AND email = ? AND status = 'pending'"
+    )
+    .bind(&goal.id)
+    .bind(&email)
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match pending {
+        Ok(0) => {}
+        Ok(_) => {
+            return HttpResponse::Conflict().json(serde_json::json!({
+                "error": "There is already a pending invitation for this email"
+            }));
+        }
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            }));
+        }
+    }
+
+    let invitation_id = uuid::Uuid::new_v4().to_string();
+
+    let result = sqlx::query(
+        "INSERT INTO goal_invitations (id, goal_id, email, invited_by) VALUES (?, ?, ?, ?)"
+    )
+    .bind(&invitation_id)
+    .bind(&goal.id)
+    .bind(&email)
+    .bind(&workspace.user_id)
+    .execute(pool.get_ref())
+    .await;
+
+    if let Err(e) = result {
+        eprintln!("Database error: {}", e);
+        return HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to create invitation"
+        }));
+    }
+
+    if let Some(invitee_id) = invitee_id {
+        let message = format!(
+            "Você foi convidado para a meta '{}'. Aceite o convite para acompanhar e contribuir.",
+            goal.name
+        );
+        if let Err(e) = notify(pool.get_ref(), &invitee_id, "Convite para meta", &message, "info").await {
+            eprintln!("Failed to notify invited user: {}", e);
+        }
+    }
+
+    let invitation = sqlx::query_as::<_, GoalInvitation>(&format!(
+        "SELECT {} FROM goal_invitations i JOIN goals g ON g.id = i.goal_id WHERE i.id = ?",
+        GOAL_INVITATION_COLUMNS
+    ))
+    .bind(&invitation_id)
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match invitation {
+        Ok(invitation) => HttpResponse::Created().json(invitation),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            }))
+        }
+    }
+}
+
+// GET /api/goals/{id}/invitations - Convites da meta (somente editores do espaço da meta)
+pub async fn get_invitations(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    goal_id: web::Path<String>,
+) -> impl Responder {
+    let goal = match managed_goal(pool.get_ref(), &workspace, &goal_id).await {
+        Ok(goal) => goal,
+        Err(response) => return response,
+    };
+
+    let invitations = sqlx::query_as::<_, GoalInvitation>(&format!(
+        "SELECT {} FROM goal_invitations i JOIN goals g ON g.id = i.goal_id
+         WHERE i.goal_id = ?
+         ORDER BY i.created_at DESC",
+        GOAL_INVITATION_COLUMNS
+    ))
+    .bind(&goal.id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match invitations {
+        Ok(invitations) => HttpResponse::Ok().json(invitations),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to fetch invitations"
+            }))
+        }
+    }
+}
+
+// DELETE /api/goals/{id}/invitations/{invitation_id} - Revogar convite pendente
+pub async fn revoke_invitation(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let (goal_id, invitation_id) = path.into_inner();
+
+    let goal = match managed_goal(pool.get_ref(), &workspace, &goal_id).await {
+        Ok(goal) => goal,
+        Err(response) => return response,
+    };
+
+    let result = sqlx::query(
+        "UPDATE goal_invitations SET status = 'revoked', responded_at = NOW()
+         WHERE id = ? AND goal_id = ? AND status = 'pending'"
+    )
+    .bind(&invitation_id)
+    .bind(&goal.id)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(result) if result.rows_affected() > 0 => HttpResponse::Ok().json(serde_json::json!({
+            "message": "Invitation revoked successfully"
+        })),
+        Ok(_) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Pending invitation not found"
+        })),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to revoke invitation"
+            }))
+        }
+    }
+}
+
+// GET /api/invitations/goals - Convites de metas pendentes para o e-mail do usuário
+pub async fn my_invitations(
+    pool: web::Data<MySqlPool>,
+    user_id: web::ReqData<String>,
+) -> impl Responder {
+    let invitations = sqlx::query_as::<_, GoalInvitation>(&format!(
+        "SELECT {} FROM goal_invitations i
+         JOIN goals g ON g.id = i.goal_id
+         JOIN users u ON u.email = i.email
+         WHERE u.id = ? AND i.status = 'pending'
+         ORDER BY i.created_at DESC",
+        GOAL_INVITATION_COLUMNS
+    ))
+    .bind(user_id.into_inner())
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match invitations {
+        Ok(invitations) => HttpResponse::Ok().json(invitations),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to fetch invitations"
+            }))
+        }
+    }
+}
+
+// Responde a um convite de meta pendente endereçado ao e-mail do usuário. Quem
+// já é membro do espaço da meta não vira membro convidado.
+// Retorna a meta, ou None quando o convite não existe.
+async fn respond_invitation(
+    pool: &MySqlPool,
+    user_id: &str,
+    invitation_id: &str,
+    accept: bool,
+) -> Result<Option<Goal>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let invitation = sqlx::query_as::<_, (String, Option<String>)>(
+        "SELECT i.goal_id, i.invited_by
+         FROM goal_invitations i
+         JOIN users u ON u.email = i.email
+         WHERE i.id = ? AND u.id = ? AND i.status = 'pending'
+         FOR UPDATE"
+    )
+    .bind(invitation_id)
+    .bind(user_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some((goal_id, invited_by)) = invitation else {
+        return Ok(None);
+    };
+
+    if accept {
+        sqlx::query(
+            "INSERT IGNORE INTO goal_members (goal_id, user_id, invited_by)
+             SELECT g.id, ?, ? FROM goals g
+             WHERE g.id = ? AND NOT EXISTS (
+                 SELECT 1 FROM workspace_members w WHERE w.workspace_id = g.workspace_id AND w.user_id = ?
+             )"
+        )
+        .bind(user_id)
+        .bind(&invited_by)
+        .bind(&goal_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query("UPDATE goal_invitations SET status = ?, responded_at = NOW() WHERE id = ?")
+        .bind(if accept { "accepted" } else { "declined" })
+        .bind(invitation_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let goal = sqlx::query_as::<_, Goal>("SELECT * FROM goals WHERE id = ?")
+        .bind(&goal_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(Some(goal))
+}
+
+// POST /api/invitations/goals/{id}/accept - Aceitar convite para meta
+pub async fn accept_invitation(
+    pool: web::Data<MySqlPool>,
+    user_id: web::ReqData<String>,
+    invitation_id: web::Path<String>,
+) -> impl Responder {
+    match respond_invitation(pool.get_ref(), &user_id, &invitation_id, true).await {
+        Ok(Some(goal)) => HttpResponse::Ok().json(goal),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Pending invitation not found"
+        })),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to accept invitation"
+            }))
+        }
+    }
+}
+
+// POST /api/invitations/goals/{id}/decline - Recusar convite para meta
+pub async fn decline_invitation(
+    pool: web::Data<MySqlPool>,
+    user_id: web::ReqData<String>,
+    invitation_id: web::Path<String>,
+) -> impl Responder {
+    match respond_invitation(pool.get_ref(), &user_id, &invitation_id, false).await {
+        Ok(Some(_)) => HttpResponse::Ok().json(serde_json::json!({
+            "message": "Invitation declined"
+        })),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Pending invitation not found"
+        })),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to decline invitation"
+            }))
+        }
+    }
+}
+
+// DELETE /api/goals/{id}/members/{user_id} - Remover membro (editores do
+// espaço da meta removem qualquer membro; um membro pode sair da meta). Os
+// aportes feitos continuam na meta e as regras de poupança automática do
+// membro são excluídas.
+pub async fn remove_member(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let (goal_id, member_id) = path.into_inner();
+
+    let goal = match find_goal(pool.get_ref(), &goal_id, &workspace).await {
+        Ok(Some(goal)) => goal,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Goal not found"
+            }));
+        }
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            }));
+        }
+    };
+
+    if !goal.managed_by(&workspace) && member_id != workspace.user_id {
+        return HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Only editors of the goal's workspace can remove other members"
+        }));
+    }
+
+    let result = async {
+        let mut tx = pool.begin().await?;
+
+        let removed = sqlx::query("DELETE FROM goal_members WHERE goal_id = ? AND user_id = ?")
+            .bind(&goal.id)
+            .bind(&member_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        sqlx::query("DELETE FROM goal_autosave_rules WHERE goal_id = ? AND user_id = ?")
+            .bind(&goal.id)
+            .bind(&member_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(removed)
+    }
+    .await;
+
+    match result {
+        Ok(0) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Member not found"
+        })),
+        Ok(_) => HttpResponse::Ok().json(serde_json::json!({
+            "message": "Member removed successfully"
+        })),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to remove member"
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn goal(target: i64, current: i64, deadline: NaiveDate) -> Goal {
+        let created_at = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        Goal {
+            id: "goal".to_string(),
+            workspace_id: "workspace".to_string(),
+            user_id: "user".to_string(),
+            name: "Viagem".to_string(),
+            target_amount: Decimal::from(target),
+            current_amount: Decimal::from(current),
+            deadline,
+            icon: "🎯".to_string(),
+            investment_type: None,
+            investment_rate: None,
+            created_at,
+            updated_at: created_at,
+        }
+    }
+
+    #[test]
+    fn zero_rate_projects_like_a_goal_without_yield() {
+        let goal = goal(10_000, 1_000, date(2025, 12, 31));
+        let today = date(2025, 1, 1);
+
+        let without = goal.projection(Decimal::from(2_700), None, today);
+        let zero = goal.projection(Decimal::from(2_700), Some(0.0), today);
+
+        assert_eq!(zero.required_per_month, without.required_per_month);
+        assert_eq!(zero.projected_completion_date, without.projected_completion_date);
+        assert_eq!(zero.projected_balance_at_deadline, without.projected_balance_at_deadline);
+        assert_eq!(zero.annual_yield_rate, None);
+        assert_eq!(without.monthly_pace, Decimal::new(91250, 2));
+        assert_eq!(without.status, "on_track");
+    }
+
+    #[test]
+    fn negative_pace_never_completes() {
+        let goal = goal(10_000, 5_000, date(2025, 12, 31));
+        let today = date(2025, 1, 1);
+
+        for rate in [None, Some(0.01)] {
+            let projection = goal.projection(Decimal::from(-900), rate, today);
+
+            assert!(projection.monthly_pace < Decimal::ZERO);
+            assert_eq!(projection.projected_completion_date, None);
+            assert_eq!(projection.status, "at_risk");
+            assert!(projection.required_per_month.is_some_and(|month| month > Decimal::ZERO));
+        }
+
+        let projection = goal.projection(Decimal::from(-900), None, today);
+        assert!(projection
+            .projected_balance_at_deadline
+            .is_some_and(|balance| balance < goal.current_amount));
+    }
+
+    #[test]
+    fn completed_goal_needs_nothing_more() {
+        let goal = goal(10_000, 12_000, date(2025, 12, 31));
+        let today = date(2025, 6, 1);
+
+        for rate in [None, Some(0.01)] {
+            let projection = goal.projection(Decimal::ZERO, rate, today);
+
+            assert_eq!(projection.status, "completed");
+            assert_eq!(projection.percent_complete, Decimal::from(120));
+            assert_eq!(projection.remaining_amount, Decimal::ZERO);
+            assert_eq!(projection.required_per_month, None);
+            assert_eq!(projection.required_per_week, None);
+            assert_eq!(projection.projected_completion_date, Some(today));
+        }
+
+        // Atingida mesmo depois do prazo continua concluída
+        let projection = goal.projection(Decimal::ZERO, None, date(2026, 2, 1));
+        assert_eq!(projection.status, "completed");
+        assert_eq!(projection.projected_balance_at_deadline, None);
+    }
+
+    #[test]
+    fn overdue_goal_has_no_requirement() {
+        let goal = goal(10_000, 4_000, date(2025, 1, 31));
+        let projection = goal.projection(Decimal::from(3_000), None, date(2025, 2, 10));
+
+        assert_eq!(projection.status, "overdue");
+        assert_eq!(projection.days_remaining, -10);
+        assert_eq!(projection.required_per_month, None);
+    }
+}
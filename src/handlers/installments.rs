@@ -0,0 +1,461 @@
+use actix_web::{web, HttpResponse, Responder};
+use chrono::{Months, NaiveDate, Utc};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, MySqlPool};
+use validator::Validate;
+
+use crate::handlers::accounts::account_exists;
+use crate::handlers::transactions::check_budget_alerts;
+use crate::middleware::workspace::Workspace;
+use crate::models::Transaction;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct InstallmentPlan {
+    pub id: String,
+    pub workspace_id: String,
+    pub user_id: String,
+    pub description: String,
+    pub total_amount: Decimal,
+    pub installment_count: u32,
+    #[sqlx(rename = "type")]
+    pub transaction_type: String,
+    pub category_id: Option<String>,
+    pub first_due_date: NaiveDate,
+    // active, prepaid ou cancelled
+    pub status: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateInstallmentPlan {
+    // Espaço reservado para o sufixo " 12/12"
+    #[validate(length(min = 1, max = 240))]
+    pub description: String,
+    pub total_amount: f64,
+    #[validate(range(min = 2, max = 120))]
+    pub installment_count: u32,
+    #[validate(custom = "validate_transaction_type")]
+    pub transaction_type: Option<String>,
+    pub category_id: Option<String>,
+    // Normalmente um cartão de crédito
+    pub account_id: Option<String>,
+    pub first_due_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PrepayInstallments {
+    pub date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InstallmentPlanView {
+    #[serde(flatten)]
+    pub plan: InstallmentPlan,
+    pub paid_count: usize,
+    pub paid_amount: Decimal,
+    pub remaining_count: usize,
+    pub remaining_amount: Decimal,
+    pub installments: Vec<Transaction>,
+}
+
+impl InstallmentPlanView {
+    // Parcelas com data até hoje contam como pagas
+    fn new(plan: InstallmentPlan, installments: Vec<Transaction>) -> Self {
+        let today = Utc::now().date_naive();
+        let (paid, remaining): (Vec<&Transaction>, Vec<&Transaction>) = installments
+            .iter()
+            .partition(|installment| installment.date.date_naive() <= today);
+
+        InstallmentPlanView {
+            plan,
+            paid_count: paid.len(),
+            paid_amount: paid.iter().map(|t| t.amount).sum(),
+            remaining_count: remaining.len(),
+            remaining_amount: remaining.iter().map(|t| t.amount).sum(),
+            installments,
+        }
+    }
+}
+
+fn validate_transaction_type(value: &str) -> Result<(), validator::ValidationError> {
+    if value == "income" || value == "expense" {
+        Ok(())
+    } else {
+        Err(validator::ValidationError::new("invalid_type"))
+    }
+}
+
+// Divide o total em parcelas iguais; os centavos que sobram vão para a primeira
+pub fn split_amount(total: Decimal, count: u32) -> Vec<Decimal> {
+    let installment = (total / Decimal::from(count))
+        .round_dp_with_strategy(2, RoundingStrategy::ToZero);
+    let first = total - installment * Decimal::from(count - 1);
+
+    std::iter::once(first)
+        .chain(std::iter::repeat_n(installment, count as usize - 1))
+        .collect()
+}
+
+fn database_error(e: sqlx::Error) -> HttpResponse {
+    eprintln!("Database error: {}", e);
+    HttpResponse::InternalServerError().json(serde_json::json!({
+        "error": "Database error"
+    }))
+}
+
+async fn load_plan(
+    pool: &MySqlPool,
+    plan_id: &str,
+    workspace_id: &str,
+) -> Result<Option<InstallmentPlanView>, sqlx::Error> {
+    let plan = sqlx::query_as::<_, InstallmentPlan>(
+        "SELECT * FROM installment_plans WHERE id = ? AND workspace_id = ?"
+    )
+    .bind(plan_id)
+    .bind(workspace_id)
+    .fetch_optional(pool)
+    .await?;
+
+    let plan = match plan {
+        Some(plan) => plan,
+        None => return Ok(None),
+    };
+
+    let installments = sqlx::query_as::<_, Transaction>(
+        "SELECT * FROM transactions WHERE installment_plan_id = ? ORDER BY installment_number"
+    )
+    .bind(&plan.id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(Some(InstallmentPlanView::new(plan, installments)))
+}
+
+// GET /api/transactions/installments - Listar parcelamentos
+pub async fn get_all(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+) -> impl Responder {
+    let result = async {
+        let plans = sqlx::query_as::<_, InstallmentPlan>(
+            "SELECT * FROM installment_plans WHERE workspace_id = ? ORDER BY created_at DESC"
+        )
+        .bind(&workspace.id)
+        .fetch_all(pool.get_ref())
+        .await?;
+
+        let mut installments = sqlx::query_as::<_, Transaction>(
+            "SELECT * FROM transactions
+             WHERE workspace_id = ? AND installment_plan_id IS NOT NULL
+             ORDER BY installment_number"
+        )
+        .bind(&workspace.id)
+        .fetch_all(pool.get_ref())
+        .await?;
+
+        let views: Vec<InstallmentPlanView> = plans
+            .into_iter()
+            .map(|plan| {
+                let (own, rest) = installments
+                    .drain(..)
+                    .partition(|t| t.installment_plan_id.as_deref() == Some(plan.id.as_str()));
+                installments = rest;
+                InstallmentPlanView::new(plan, own)
+            })
+            .collect();
+
+        Ok::<_, sqlx::Error>(views)
+    }
+    .await;
+
+    match result {
+        Ok(views) => HttpResponse::Ok().json(views),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to fetch installment plans"
+            }))
+        }
+    }
+}
+
+// GET /api/transactions/installments/{id} - Ver parcelamento
+pub async fn get_by_id(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    plan_id: web::Path<String>,
+) -> impl Responder {
+    match load_plan(pool.get_ref(), &plan_id, &workspace.id).await {
+        Ok(Some(view)) => HttpResponse::Ok().json(view),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Installment plan not found"
+        })),
+        Err(e) => database_error(e),
+    }
+}
+
+// POST /api/transactions/installments - Criar compra parcelada
+pub async fn create(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    plan_data: web::Json<CreateInstallmentPlan>,
+) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    if let Err(errors) = plan_data.validate() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Validation failed",
+            "details": errors.to_string()
+        }));
+    }
+
+    let count = plan_data.installment_count;
+    let total = match Decimal::from_f64_retain(plan_data.total_amount) {
+        Some(total) => total.round_dp(2),
+        None => Decimal::ZERO,
+    };
+
+    // Cada parcela precisa de pelo menos R$ 0,01
+    if total < Decimal::new(count as i64, 2) {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Invalid amount"
+        }));
+    }
+
+    if let Some(account_id) = &plan_data.account_id {
+        match account_exists(pool.get_ref(), &workspace.id, account_id).await {
+            Ok(true) => {}
+            Ok(false) => {
+                return HttpResponse::NotFound().json(serde_json::json!({
+                    "error": "Account not found"
+                }));
+            }
+            Err(e) => return database_error(e),
+        }
+    }
+
+    let plan_id = uuid::Uuid::new_v4().to_string();
+    let transaction_type = plan_data.transaction_type.as_deref().unwrap_or("expense");
+    let first_due_date = plan_data
+        .first_due_date
+        .unwrap_or_else(|| Utc::now().date_naive());
+
+    let result = async {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            "INSERT INTO installment_plans (id, workspace_id, user_id, description, total_amount, installment_count, type, category_id, first_due_date, status)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, 'active')"
+        )
+        .bind(&plan_id)
+        .bind(&workspace.id)
+        .bind(&workspace.user_id)
+        .bind(&plan_data.description)
+        .bind(total)
+        .bind(count)
+        .bind(transaction_type)
+        .bind(&plan_data.category_id)
+        .bind(first_due_date)
+        .execute(&mut *tx)
+        .await?;
+
+        for (index, amount) in split_amount(total, count).into_iter().enumerate() {
+            let number = index as u32 + 1;
+            // Vencimentos mensais a partir da primeira data (31/01 -> 28/02 -> 31/03)
+            let due_date = first_due_date + Months::new(index as u32);
+
+            sqlx::query(
+                "INSERT INTO transactions (id, workspace_id, user_id, description, amount, type, category_id, account_id, date, recurring, installment_plan_id, installment_number)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, FALSE, ?, ?)"
+            )
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(&workspace.id)
+            .bind(&workspace.user_id)
+            .bind(format!("{} {}/{}", plan_data.description, number, count))
+            .bind(amount)
+            .bind(transaction_type)
+            .bind(&plan_data.category_id)
+            .bind(&plan_data.account_id)
+            .bind(due_date.and_hms_opt(0, 0, 0).unwrap())
+            .bind(&plan_id)
+            .bind(number)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        load_plan(pool.get_ref(), &plan_id, &workspace.id).await
+    }
+    .await;
+
+    match result {
+        Ok(Some(view)) => {
+            // Cada parcela conta no orçamento do mês em que vence
+            for installment in &view.installments {
+                check_budget_alerts(pool.get_ref(), installment).await;
+            }
+            HttpResponse::Created().json(view)
+        }
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Installment plan not found"
+        })),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to create installment plan"
+            }))
+        }
+    }
+}
+
+// POST /api/transactions/installments/{id}/prepay - Antecipar as parcelas restantes
+pub async fn prepay(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    plan_id: web::Path<String>,
+    prepay_data: web::Json<PrepayInstallments>,
+) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    let date = prepay_data.date.unwrap_or_else(|| Utc::now().date_naive());
+    close_plan(pool.get_ref(), &plan_id, &workspace.id, ClosePlan::Prepay(date)).await
+}
+
+// DELETE /api/transactions/installments/{id} - Cancelar parcelamento
+pub async fn cancel(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    plan_id: web::Path<String>,
+) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    close_plan(pool.get_ref(), &plan_id, &workspace.id, ClosePlan::Cancel).await
+}
+
+enum ClosePlan {
+    // Parcelas futuras passam para a data da antecipação
+    Prepay(NaiveDate),
+    // Parcelas futuras são removidas; as já vencidas continuam registradas
+    Cancel,
+}
+
+async fn close_plan(pool: &MySqlPool, plan_id: &str, workspace_id: &str, action: ClosePlan) -> HttpResponse {
+    let plan = sqlx::query_as::<_, InstallmentPlan>(
+        "SELECT * FROM installment_plans WHERE id = ? AND workspace_id = ?"
+    )
+    .bind(plan_id)
+    .bind(workspace_id)
+    .fetch_optional(pool)
+    .await;
+
+    match plan {
+        Ok(Some(plan)) if plan.status == "active" => {}
+        Ok(Some(_)) => {
+            return HttpResponse::Conflict().json(serde_json::json!({
+                "error": "Installment plan is no longer active"
+            }));
+        }
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Installment plan not found"
+            }));
+        }
+        Err(e) => return database_error(e),
+    }
+
+    let (cutoff, status) = match action {
+        ClosePlan::Prepay(date) => (date, "prepaid"),
+        ClosePlan::Cancel => (Utc::now().date_naive(), "cancelled"),
+    };
+    // Parcelas com vencimento depois do dia de corte
+    let cutoff_time = (cutoff + chrono::Days::new(1)).and_hms_opt(0, 0, 0).unwrap();
+
+    let result = async {
+        let mut tx = pool.begin().await?;
+
+        let affected = match action {
+            ClosePlan::Prepay(date) => sqlx::query(
+                "UPDATE transactions SET date = ? WHERE installment_plan_id = ? AND date >= ?"
+            )
+            .bind(date.and_hms_opt(0, 0, 0).unwrap())
+            .bind(plan_id)
+            .bind(cutoff_time)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected(),
+            ClosePlan::Cancel => sqlx::query(
+                "DELETE FROM transactions WHERE installment_plan_id = ? AND date >= ?"
+            )
+            .bind(plan_id)
+            .bind(cutoff_time)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected(),
+        };
+
+        sqlx::query("UPDATE installment_plans SET status = ? WHERE id = ?")
+            .bind(status)
+            .bind(plan_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(affected)
+    }
+    .await;
+
+    match result {
+        Ok(affected) => match action {
+            ClosePlan::Prepay(_) => HttpResponse::Ok().json(serde_json::json!({
+                "message": "Installments prepaid successfully",
+                "prepaid": affected
+            })),
+            ClosePlan::Cancel => HttpResponse::Ok().json(serde_json::json!({
+                "message": "Installment plan cancelled successfully",
+                "removed": affected
+            })),
+        },
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to update installment plan"
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cents(value: i64) -> Decimal {
+        Decimal::new(value, 2)
+    }
+
+    #[test]
+    fn remainder_cents_go_to_the_first_installment() {
+        assert_eq!(split_amount(cents(10000), 3), vec![cents(3334), cents(3333), cents(3333)]);
+        assert_eq!(split_amount(cents(1000), 6), vec![cents(170), cents(166), cents(166), cents(166), cents(166), cents(166)]);
+    }
+
+    #[test]
+    fn even_totals_split_into_equal_installments() {
+        assert_eq!(split_amount(cents(120000), 12), vec![cents(10000); 12]);
+    }
+
+    #[test]
+    fn installments_always_add_up_to_the_total() {
+        for count in 2..=48 {
+            let total = cents(99999);
+            assert_eq!(split_amount(total, count).iter().sum::<Decimal>(), total);
+        }
+    }
+}
@@ -1,6 +1,19 @@
+pub mod accounts;
 pub mod auth;
+pub mod autosave;
+pub mod budgets;
+pub mod calendar;
 pub mod categories;
+pub mod dashboard;
+pub mod envelopes;
 pub mod goals;
+pub mod installments;
 pub mod notifications;
+pub mod rates;
 pub mod recurring;
+pub mod reports;
+pub mod search;
+pub mod splits;
+pub mod tags;
 pub mod transactions;
+pub mod workspaces;
@@ -2,6 +2,8 @@ use actix_web::{web, HttpResponse, Responder};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, MySqlPool};
 
+use crate::handlers::workspaces;
+
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct Notification {
     pub id: String,
@@ -142,3 +144,81 @@ pub async fn delete(
         }
     }
 }
+
+// Cria uma notificação gerada pelo próprio servidor
+pub async fn notify(
+    pool: &MySqlPool,
+    user_id: &str,
+    title: &str,
+    message: &str,
+    notification_type: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO notifications (id, user_id, title, message, type, `read`)
+         VALUES (?, ?, ?, ?, ?, FALSE)"
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(user_id)
+    .bind(title)
+    .bind(message)
+    .bind(notification_type)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+// Registra o evento `event_key` para o usuário sem notificar.
+// Retorna true quando é a primeira vez que o evento ocorre.
+pub async fn record_event(
+    pool: &MySqlPool,
+    user_id: &str,
+    event_key: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        "INSERT IGNORE INTO notification_events (user_id, event_key) VALUES (?, ?)"
+    )
+    .bind(user_id)
+    .bind(event_key)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+// Cria a notificação apenas na primeira vez que o evento `event_key` ocorre.
+// Retorna true quando a notificação foi criada.
+pub async fn notify_once(
+    pool: &MySqlPool,
+    user_id: &str,
+    event_key: &str,
+    title: &str,
+    message: &str,
+    notification_type: &str,
+) -> Result<bool, sqlx::Error> {
+    if !record_event(pool, user_id, event_key).await? {
+        return Ok(false);
+    }
+
+    notify(pool, user_id, title, message, notification_type).await?;
+    Ok(true)
+}
+
+// Avisa todos os membros do espaço de trabalho, uma vez por membro e evento.
+// Retorna quantas notificações foram criadas.
+pub async fn notify_workspace_once(
+    pool: &MySqlPool,
+    workspace_id: &str,
+    event_key: &str,
+    title: &str,
+    message: &str,
+    notification_type: &str,
+) -> Result<u32, sqlx::Error> {
+    let mut sent = 0;
+    for member_id in workspaces::member_ids(pool, workspace_id).await? {
+        if notify_once(pool, &member_id, event_key, title, message, notification_type).await? {
+            sent += 1;
+        }
+    }
+    Ok(sent)
+}
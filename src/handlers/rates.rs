@@ -0,0 +1,187 @@
+use actix_web::{web, HttpResponse, Responder};
+use chrono::{NaiveDate, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, MySqlPool};
+
+// Taxas de referência (% ao ano) mantidas localmente pelos administradores.
+// Não há consulta online: os valores valem até serem atualizados aqui.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ReferenceRate {
+    pub code: String,
+    pub annual_rate: Decimal,
+    pub reference_date: NaiveDate,
+    pub updated_by: Option<String>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateReferenceRate {
+    pub annual_rate: f64,
+    pub reference_date: Option<NaiveDate>,
+}
+
+const RATE_CODES: [&str; 3] = ["cdi", "selic", "tr"];
+
+// Acima desta Selic (% a.a.) a poupança rende 0,5% ao mês + TR;
+// abaixo, 70% da Selic + TR (Lei 12.703/2012)
+const POUPANCA_SELIC_THRESHOLD: i64 = 85;
+const POUPANCA_FIXED_MONTHLY: f64 = 0.005;
+
+pub const INVESTMENT_TYPES: [&str; 3] = ["cdi_percent", "fixed", "poupanca"];
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ReferenceRates {
+    pub cdi: Option<Decimal>,
+    pub selic: Option<Decimal>,
+    pub tr: Option<Decimal>,
+}
+
+// Taxa mensal equivalente a uma taxa anual em %
+fn monthly_from_annual(annual_percent: Decimal) -> Option<f64> {
+    let annual = annual_percent.to_f64()? / 100.0;
+    Some((1.0 + annual).powf(1.0 / 12.0) - 1.0)
+}
+
+impl ReferenceRates {
+    // Taxa mensal (fração) de um investimento. `rate` é o percentual do CDI
+    // (cdi_percent) ou a taxa anual (fixed); a poupança não usa `rate`.
+    pub fn monthly_rate(&self, investment_type: &str, rate: Option<Decimal>) -> Option<f64> {
+        match investment_type {
+            "cdi_percent" => monthly_from_annual(self.cdi? * rate? / Decimal::from(100)),
+            "fixed" => monthly_from_annual(rate?),
+            "poupanca" => {
+                let selic = self.selic?;
+                let tr = monthly_from_annual(self.tr.unwrap_or(Decimal::ZERO))?;
+                let base = if selic > Decimal::new(POUPANCA_SELIC_THRESHOLD, 1) {
+                    POUPANCA_FIXED_MONTHLY
+                } else {
+                    monthly_from_annual(selic * Decimal::new(7, 1))?
+                };
+                Some(base + tr)
+            }
+            _ => None,
+        }
+    }
+}
+
+pub async fn load_reference_rates(pool: &MySqlPool) -> Result<ReferenceRates, sqlx::Error> {
+    let rates = sqlx::query_as::<_, (String, Decimal)>("SELECT code, annual_rate FROM reference_rates")
+        .fetch_all(pool)
+        .await?;
+
+    let mut reference = ReferenceRates::default();
+    for (code, rate) in rates {
+        match code.as_str() {
+            "cdi" => reference.cdi = Some(rate),
+            "selic" => reference.selic = Some(rate),
+            "tr" => reference.tr = Some(rate),
+            _ => {}
+        }
+    }
+    Ok(reference)
+}
+
+pub async fn is_admin(pool: &MySqlPool, user_id: &str) -> Result<bool, sqlx::Error> {
+    let admin = sqlx::query_scalar::<_, bool>("SELECT is_admin FROM users WHERE id = ?")
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(admin.unwrap_or(false))
+}
+
+// GET /api/rates - Taxas de referência (CDI, Selic e TR)
+pub async fn get_all(pool: web::Data<MySqlPool>) -> impl Responder {
+    let rates = sqlx::query_as::<_, ReferenceRate>("SELECT * FROM reference_rates ORDER BY code")
+        .fetch_all(pool.get_ref())
+        .await;
+
+    match rates {
+        Ok(rates) => HttpResponse::Ok().json(rates),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to fetch reference rates"
+            }))
+        }
+    }
+}
+
+// PUT /api/rates/{code} - Atualizar taxa de referência (somente administradores)
+pub async fn update(
+    pool: web::Data<MySqlPool>,
+    user_id: web::ReqData<String>,
+    code: web::Path<String>,
+    rate_data: web::Json<UpdateReferenceRate>,
+) -> impl Responder {
+    let uid = user_id.into_inner();
+
+    match is_admin(pool.get_ref(), &uid).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return HttpResponse::Forbidden().json(serde_json::json!({
+                "error": "Only administrators can update reference rates"
+            }));
+        }
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            }));
+        }
+    }
+
+    let code = code.into_inner().to_lowercase();
+    if !RATE_CODES.contains(&code.as_str()) {
+        return HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Reference rate not found"
+        }));
+    }
+
+    let annual_rate = match Decimal::from_f64_retain(rate_data.annual_rate) {
+        Some(rate) if (0.0..=100.0).contains(&rate_data.annual_rate) => rate.round_dp(4),
+        _ => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "annual_rate must be between 0 and 100"
+            }));
+        }
+    };
+    let reference_date = rate_data.reference_date.unwrap_or_else(|| Utc::now().date_naive());
+
+    let result = sqlx::query(
+        "INSERT INTO reference_rates (code, annual_rate, reference_date, updated_by)
+         VALUES (?, ?, ?, ?)
+         ON DUPLICATE KEY UPDATE annual_rate = VALUES(annual_rate),
+             reference_date = VALUES(reference_date), updated_by = VALUES(updated_by)"
+    )
+    .bind(&code)
+    .bind(annual_rate)
+    .bind(reference_date)
+    .bind(&uid)
+    .execute(pool.get_ref())
+    .await;
+
+    if let Err(e) = result {
+        eprintln!("Database error: {}", e);
+        return HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to update reference rate"
+        }));
+    }
+
+    let rate = sqlx::query_as::<_, ReferenceRate>("SELECT * FROM reference_rates WHERE code = ?")
+        .bind(&code)
+        .fetch_one(pool.get_ref())
+        .await;
+
+    match rate {
+        Ok(rate) => HttpResponse::Ok().json(rate),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            }))
+        }
+    }
+}
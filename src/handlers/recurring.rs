@@ -1,12 +1,24 @@
 use actix_web::{web, HttpResponse, Responder};
+use chrono::{Duration, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, MySqlPool};
+use std::collections::BTreeMap;
 use validator::Validate;
 
+use crate::handlers::autosave;
+use crate::handlers::transactions::check_budget_alerts;
+use crate::middleware::workspace::Workspace;
+use crate::models::Transaction;
+use crate::utils::double_option;
+use crate::utils::holidays::BusinessDayShift;
+use crate::utils::recurrence::{Frequency, Schedule, MAX_REMAINING};
+use crate::utils::rrule::RRule;
+
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct RecurringTransaction {
     pub id: String,
+    pub workspace_id: String,
     pub user_id: String,
     pub description: String,
     pub amount: Decimal,
@@ -14,12 +26,56 @@ pub struct RecurringTransaction {
     pub transaction_type: String,
     pub category_id: Option<String>,
     pub frequency: String,
+    pub interval_count: u32,
+    // Regra iCalendar (RFC 5545); quando presente, prevalece sobre frequency/interval_count
+    pub rrule: Option<String>,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub max_occurrences: Option<u32>,
+    // none, forward ou backward: ocorrências em fim de semana/feriado mudam de dia
+    pub business_day_shift: String,
     pub active: bool,
-    pub last_generated: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_generated: Option<NaiveDate>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
+impl RecurringTransaction {
+    pub fn schedule(&self) -> Option<Schedule> {
+        let schedule = match &self.rrule {
+            Some(rrule) => Schedule::from_rrule(self.start_date, RRule::parse(rrule).ok()?),
+            None => Schedule::new(
+                self.start_date,
+                Frequency::parse(&self.frequency)?,
+                self.interval_count,
+            ),
+        };
+
+        Some(schedule.until(self.end_date).limit(self.max_occurrences))
+    }
+
+    // Data efetiva de uma ocorrência, já ajustada para dia útil
+    pub fn shift_date(&self, date: NaiveDate) -> NaiveDate {
+        BusinessDayShift::parse(&self.business_day_shift)
+            .map_or(date, |shift| shift.apply(date))
+    }
+
+    // Ocorrências (em ordem, posteriores a last_generated) sem as que caem no
+    // mesmo dia útil de uma anterior depois do ajuste
+    fn distinct_occurrences(&self, dates: Vec<NaiveDate>) -> Vec<NaiveDate> {
+        match BusinessDayShift::parse(&self.business_day_shift) {
+            Some(shift) => shift.distinct(&dates, self.last_generated),
+            None => dates,
+        }
+    }
+
+    // Próxima ocorrência ainda não gerada
+    pub fn next_occurrence(&self) -> Option<NaiveDate> {
+        let date = self.schedule()?.next_after(self.last_generated)?;
+        Some(self.shift_date(date))
+    }
+}
+
 #[derive(Debug, Deserialize, Validate)]
 pub struct CreateRecurring {
     #[validate(length(min = 1, max = 255))]
@@ -29,19 +85,66 @@ pub struct CreateRecurring {
     pub transaction_type: String,
     pub category_id: Option<String>,
     #[validate(custom = "validate_frequency")]
-    pub frequency: String,
+    pub frequency: Option<String>,
+    #[validate(range(min = 1, max = 999))]
+    pub interval_count: Option<u32>,
+    pub rrule: Option<String>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    #[validate(range(min = 1, max = 1200))]
+    pub max_occurrences: Option<u32>,
+    #[validate(custom = "validate_business_day_shift")]
+    pub business_day_shift: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate)]
 pub struct UpdateRecurring {
     pub description: Option<String>,
     pub amount: Option<f64>,
     pub transaction_type: Option<String>,
     pub category_id: Option<String>,
+    #[validate(custom = "validate_frequency")]
     pub frequency: Option<String>,
+    #[validate(range(min = 1, max = 999))]
+    pub interval_count: Option<u32>,
+    // null volta para a frequência simples
+    #[serde(default, deserialize_with = "double_option")]
+    pub rrule: Option<Option<String>>,
+    pub start_date: Option<NaiveDate>,
+    // Ausente mantém o valor atual; null remove o limite
+    #[serde(default, deserialize_with = "double_option")]
+    pub end_date: Option<Option<NaiveDate>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub max_occurrences: Option<Option<u32>>,
+    #[validate(custom = "validate_business_day_shift")]
+    pub business_day_shift: Option<String>,
     pub active: Option<bool>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct RecurringWithProjection {
+    #[serde(flatten)]
+    pub recurring: RecurringTransaction,
+    pub next_occurrence: Option<NaiveDate>,
+    // null quando a recorrência não tem data final nem limite de ocorrências
+    pub remaining_occurrences: Option<u32>,
+    pub projected_remaining_total: Option<Decimal>,
+}
+
+impl From<RecurringTransaction> for RecurringWithProjection {
+    fn from(recurring: RecurringTransaction) -> Self {
+        let schedule = recurring.schedule();
+        let remaining = schedule.and_then(|s| s.remaining_after(recurring.last_generated));
+
+        RecurringWithProjection {
+            next_occurrence: recurring.next_occurrence(),
+            remaining_occurrences: remaining,
+            projected_remaining_total: remaining.map(|n| recurring.amount * Decimal::from(n)),
+            recurring,
+        }
+    }
+}
+
 fn validate_transaction_type(value: &str) -> Result<(), validator::ValidationError> {
     if value == "income" || value == "expense" {
         Ok(())
@@ -50,8 +153,23 @@ fn validate_transaction_type(value: &str) -> Result<(), validator::ValidationErr
     }
 }
 
+fn validate_business_day_shift(value: &str) -> Result<(), validator::ValidationError> {
+    if BusinessDayShift::parse(value).is_some() {
+        Ok(())
+    } else {
+        Err(validator::ValidationError::new("invalid_business_day_shift"))
+    }
+}
+
+fn invalid_rrule(error: String) -> HttpResponse {
+    HttpResponse::BadRequest().json(serde_json::json!({
+        "error": "Invalid rrule",
+        "details": error
+    }))
+}
+
 fn validate_frequency(value: &str) -> Result<(), validator::ValidationError> {
-    if ["daily", "weekly", "monthly", "yearly"].contains(&value) {
+    if Frequency::parse(value).is_some() {
         Ok(())
     } else {
         Err(validator::ValidationError::new("invalid_frequency"))
@@ -61,17 +179,21 @@ fn validate_frequency(value: &str) -> Result<(), validator::ValidationError> {
 // GET /api/recurring - Listar todas
 pub async fn get_all(
     pool: web::Data<MySqlPool>,
-    user_id: web::ReqData<String>,
+    workspace: Workspace,
 ) -> impl Responder {
     let recurring = sqlx::query_as::<_, RecurringTransaction>(
-        "SELECT * FROM recurring_transactions WHERE user_id = ? ORDER BY created_at DESC"
+        "SELECT * FROM recurring_transactions WHERE workspace_id = ? ORDER BY created_at DESC"
     )
-    .bind(user_id.into_inner())
+    .bind(&workspace.id)
     .fetch_all(pool.get_ref())
     .await;
 
     match recurring {
-        Ok(recurring) => HttpResponse::Ok().json(recurring),
+        Ok(recurring) => {
+            let recurring: Vec<RecurringWithProjection> =
+                recurring.into_iter().map(Into::into).collect();
+            HttpResponse::Ok().json(recurring)
+        }
         Err(e) => {
             eprintln!("Database error: {}", e);
             HttpResponse::InternalServerError().json(serde_json::json!({
@@ -84,9 +206,13 @@ pub async fn get_all(
 // POST /api/recurring - Criar nova
 pub async fn create(
     pool: web::Data<MySqlPool>,
-    user_id: web::ReqData<String>,
+    workspace: Workspace,
     recurring_data: web::Json<CreateRecurring>,
 ) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
     if let Err(errors) = recurring_data.validate() {
         return HttpResponse::BadRequest().json(serde_json::json!({
             "error": "Validation failed",
@@ -102,18 +228,56 @@ pub async fn create(
     }
 
     let recurring_id = uuid::Uuid::new_v4().to_string();
+    let start_date = recurring_data
+        .start_date
+        .unwrap_or_else(|| Utc::now().date_naive());
+
+    if recurring_data.end_date.is_some_and(|end| end < start_date) {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "end_date must not be before start_date"
+        }));
+    }
+
+    // Com rrule, frequency e interval_count vêm da própria regra
+    let (frequency, interval_count, rrule) = match (&recurring_data.rrule, &recurring_data.frequency) {
+        (Some(rrule), _) => match RRule::parse(rrule) {
+            Ok(rule) => (
+                rule.frequency.as_str().to_string(),
+                rule.interval,
+                Some(rule.to_string()),
+            ),
+            Err(error) => return invalid_rrule(error),
+        },
+        (None, Some(frequency)) => (
+            frequency.clone(),
+            recurring_data.interval_count.unwrap_or(1),
+            None,
+        ),
+        (None, None) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "frequency or rrule is required"
+            }));
+        }
+    };
 
     let result = sqlx::query(
-        "INSERT INTO recurring_transactions (id, user_id, description, amount, type, category_id, frequency, active)
-         VALUES (?, ?, ?, ?, ?, ?, ?, TRUE)"
+        "INSERT INTO recurring_transactions (id, workspace_id, user_id, description, amount, type, category_id, frequency, interval_count, rrule, start_date, end_date, max_occurrences, business_day_shift, active)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, TRUE)"
     )
     .bind(&recurring_id)
-    .bind(user_id.into_inner())
+    .bind(&workspace.id)
+    .bind(&workspace.user_id)
     .bind(&recurring_data.description)
     .bind(amount.unwrap())
     .bind(&recurring_data.transaction_type)
     .bind(&recurring_data.category_id)
-    .bind(&recurring_data.frequency)
+    .bind(&frequency)
+    .bind(interval_count)
+    .bind(&rrule)
+    .bind(start_date)
+    .bind(recurring_data.end_date)
+    .bind(recurring_data.max_occurrences)
+    .bind(recurring_data.business_day_shift.as_deref().unwrap_or("none"))
     .execute(pool.get_ref())
     .await;
 
@@ -126,8 +290,8 @@ pub async fn create(
             .fetch_one(pool.get_ref())
             .await
             .unwrap();
-            
-            HttpResponse::Created().json(recurring)
+
+            HttpResponse::Created().json(RecurringWithProjection::from(recurring))
         }
         Err(e) => {
             eprintln!("Database error: {}", e);
@@ -141,10 +305,46 @@ pub async fn create(
 // PUT /api/recurring/{id} - Atualizar
 pub async fn update(
     pool: web::Data<MySqlPool>,
-    user_id: web::ReqData<String>,
+    workspace: Workspace,
     recurring_id: web::Path<String>,
     update_data: web::Json<UpdateRecurring>,
 ) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    if let Err(errors) = update_data.validate() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Validation failed",
+            "details": errors.to_string()
+        }));
+    }
+
+    let recurring_id = recurring_id.into_inner();
+    let current = match find_recurring(pool.get_ref(), &recurring_id, &workspace.id).await {
+        Ok(Some(recurring)) => recurring,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Recurring transaction not found"
+            }));
+        }
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to update recurring transaction"
+            }));
+        }
+    };
+
+    // A data final é comparada com o início salvo quando ele não muda
+    let start_date = update_data.start_date.unwrap_or(current.start_date);
+    let end_date = update_data.end_date.unwrap_or(current.end_date);
+    if end_date.is_some_and(|end| end < start_date) {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "end_date must not be before start_date"
+        }));
+    }
+
     let mut updates = Vec::new();
 
     if let Some(desc) = &update_data.description {
@@ -161,8 +361,52 @@ pub async fn update(
     if let Some(cat_id) = &update_data.category_id {
         updates.push(format!("category_id = '{}'", cat_id));
     }
-    if let Some(freq) = &update_data.frequency {
-        updates.push(format!("frequency = '{}'", freq));
+    match &update_data.rrule {
+        Some(Some(rrule)) => match RRule::parse(rrule) {
+            // A regra normalizada só contém letras, dígitos e ;=,+-
+            Ok(rule) => {
+                updates.push(format!("rrule = '{}'", rule));
+                updates.push(format!("frequency = '{}'", rule.frequency.as_str()));
+                updates.push(format!("interval_count = {}", rule.interval));
+            }
+            Err(error) => return invalid_rrule(error),
+        },
+        rrule => {
+            // Alterar a frequência simples descarta a rrule
+            let plain = update_data.frequency.is_some() || update_data.interval_count.is_some();
+            if rrule.is_some() || plain {
+                updates.push("rrule = NULL".to_string());
+            }
+            if let Some(freq) = &update_data.frequency {
+                updates.push(format!("frequency = '{}'", freq));
+            }
+            if let Some(interval) = update_data.interval_count {
+                updates.push(format!("interval_count = {}", interval));
+            }
+        }
+    }
+    if let Some(start_date) = update_data.start_date {
+        updates.push(format!("start_date = '{}'", start_date));
+    }
+    if let Some(end_date) = update_data.end_date {
+        match end_date {
+            Some(end_date) => updates.push(format!("end_date = '{}'", end_date)),
+            None => updates.push("end_date = NULL".to_string()),
+        }
+    }
+    if let Some(max_occurrences) = update_data.max_occurrences {
+        match max_occurrences {
+            Some(max) if max == 0 || max > MAX_REMAINING => {
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": "max_occurrences must be between 1 and 1200"
+                }));
+            }
+            Some(max) => updates.push(format!("max_occurrences = {}", max)),
+            None => updates.push("max_occurrences = NULL".to_string()),
+        }
+    }
+    if let Some(shift) = &update_data.business_day_shift {
+        updates.push(format!("business_day_shift = '{}'", shift));
     }
     if let Some(active) = update_data.active {
         updates.push(format!("active = {}", active));
@@ -175,22 +419,44 @@ pub async fn update(
     }
 
     let query = format!(
-        "UPDATE recurring_transactions SET {} WHERE id = '{}' AND user_id = '{}'",
+        "UPDATE recurring_transactions SET {} WHERE id = '{}' AND workspace_id = '{}'",
         updates.join(", "),
-        recurring_id.into_inner(),
-        user_id.into_inner()
+        recurring_id,
+        workspace.id
     );
 
-    let result = sqlx::query(&query).execute(pool.get_ref()).await;
+    // Recorrências desativadas por terem se esgotado voltam a ficar ativas
+    // quando a nova data final, limite ou regra permite mais ocorrências
+    let exhausted = !current.active && current.next_occurrence().is_none();
 
-    match result {
-        Ok(result) if result.rows_affected() > 0 => {
-            HttpResponse::Ok().json(serde_json::json!({
-                "message": "Recurring transaction updated successfully"
-            }))
+    let result = async {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(&query).execute(&mut *tx).await?;
+
+        if exhausted && update_data.active.is_none() {
+            let updated = sqlx::query_as::<_, RecurringTransaction>(
+                "SELECT * FROM recurring_transactions WHERE id = ? FOR UPDATE"
+            )
+            .bind(&recurring_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            if updated.next_occurrence().is_some() {
+                sqlx::query("UPDATE recurring_transactions SET active = TRUE WHERE id = ?")
+                    .bind(&recurring_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
         }
-        Ok(_) => HttpResponse::NotFound().json(serde_json::json!({
-            "error": "Recurring transaction not found"
+
+        tx.commit().await
+    }
+    .await;
+
+    match result {
+        Ok(_) => HttpResponse::Ok().json(serde_json::json!({
+            "message": "Recurring transaction updated successfully"
         })),
         Err(e) => {
             eprintln!("Database error: {}", e);
@@ -204,14 +470,18 @@ pub async fn update(
 // DELETE /api/recurring/{id} - Deletar
 pub async fn delete(
     pool: web::Data<MySqlPool>,
-    user_id: web::ReqData<String>,
+    workspace: Workspace,
     recurring_id: web::Path<String>,
 ) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
     let result = sqlx::query(
-        "DELETE FROM recurring_transactions WHERE id = ? AND user_id = ?"
+        "DELETE FROM recurring_transactions WHERE id = ? AND workspace_id = ?"
     )
     .bind(recurring_id.into_inner())
-    .bind(user_id.into_inner())
+    .bind(&workspace.id)
     .execute(pool.get_ref())
     .await;
 
@@ -233,16 +503,352 @@ pub async fn delete(
     }
 }
 
+#[derive(Debug, Deserialize)]
+pub struct GenerateQuery {
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct GenerationSummary {
+    pub generated: u32,
+    // Recorrências que atingiram o limite e ainda têm ocorrências atrasadas
+    pub capped: Vec<String>,
+}
+
+const DEFAULT_CATCHUP_LIMIT: u32 = 60;
+
+// Máximo de ocorrências atrasadas geradas por recorrência em cada execução
+pub fn catchup_limit() -> u32 {
+    std::env::var("RECURRING_CATCHUP_LIMIT")
+        .ok()
+        .and_then(|value| value.parse::<u32>().ok())
+        .filter(|limit| *limit > 0)
+        .unwrap_or(DEFAULT_CATCHUP_LIMIT)
+}
+
+// Gera todas as ocorrências vencidas de uma recorrência desde last_generated,
+// cada uma com a sua data. A linha da recorrência fica bloqueada (FOR UPDATE)
+// durante a transação, e a chave única (recurring_id, occurrence_date) impede
+// duplicatas mesmo com chamadas concorrentes.
+// Retorna quantas transações foram criadas e se o limite foi atingido.
+pub async fn generate_for_rule(
+    pool: &MySqlPool,
+    recurring_id: &str,
+    today: NaiveDate,
+    limit: u32,
+) -> Result<(u32, bool), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let recurring = sqlx::query_as::<_, RecurringTransaction>(
+        "SELECT * FROM recurring_transactions WHERE id = ? AND active = TRUE FOR UPDATE"
+    )
+    .bind(recurring_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let (recurring, schedule) = match recurring {
+        Some(recurring) => match recurring.schedule() {
+            Some(schedule) => (recurring, schedule),
+            None => return Ok((0, false)),
+        },
+        None => return Ok((0, false)),
+    };
+
+    // O ajuste para dia útil preserva a ordem das ocorrências, então basta
+    // parar na primeira cuja data efetiva ainda não chegou
+    let mut due = schedule
+        .occurrences_after(recurring.last_generated)
+        .take_while(|date| recurring.shift_date(*date) <= today);
+    let occurrences: Vec<NaiveDate> = due.by_ref().take(limit as usize).collect();
+    let capped = due.next().is_some();
+
+    let exceptions: BTreeMap<NaiveDate, RecurringException> = sqlx::query_as::<_, RecurringException>(
+        "SELECT * FROM recurring_exceptions WHERE recurring_id = ?"
+    )
+    .bind(&recurring.id)
+    .fetch_all(&mut *tx)
+    .await?
+    .into_iter()
+    .map(|exception| (exception.occurrence_date, exception))
+    .collect();
+
+    let mut generated = Vec::new();
+    for date in &recurring.distinct_occurrences(occurrences.clone()) {
+        let occurrence = match exceptions.get(date) {
+            // Ocorrências remarcadas são geradas abaixo, na nova data
+            Some(exception) if exception.new_date.is_some() => continue,
+            Some(exception) => exception.apply(recurring.occurrence(*date)),
+            None => Some(recurring.occurrence(*date)),
+        };
+
+        if let Some(occurrence) = occurrence {
+            if let Some(transaction_id) = insert_occurrence(&mut tx, &recurring, &occurrence).await? {
+                generated.push(transaction_id);
+            }
+        }
+    }
+
+    // Remarcadas (para antes ou depois da data original) vencem na nova data
+    for exception in exceptions.values() {
+        let due = exception.new_date.is_some_and(|date| date <= today);
+        if !due || !schedule.contains(exception.occurrence_date) {
+            continue;
+        }
+
+        if let Some(occurrence) = exception.apply(recurring.occurrence(exception.occurrence_date)) {
+            if let Some(transaction_id) = insert_occurrence(&mut tx, &recurring, &occurrence).await? {
+                generated.push(transaction_id);
+            }
+        }
+    }
+
+    let last_generated = occurrences.last().copied().or(recurring.last_generated);
+
+    // Recorrência esgotada (data final ou limite de ocorrências): desativar,
+    // a menos que ainda falte gerar alguma ocorrência remarcada para o futuro
+    let rescheduled_pending = exceptions
+        .values()
+        .any(|exception| !exception.skipped && exception.new_date.is_some_and(|date| date > today));
+    let exhausted = schedule.next_after(last_generated).is_none() && !rescheduled_pending;
+
+    sqlx::query(
+        "UPDATE recurring_transactions SET last_generated = ?, active = ? WHERE id = ?"
+    )
+    .bind(last_generated)
+    .bind(!exhausted)
+    .bind(&recurring.id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    for transaction_id in &generated {
+        let transaction = sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE id = ?")
+            .bind(transaction_id)
+            .fetch_one(pool)
+            .await?;
+        check_budget_alerts(pool, &transaction).await;
+        if let Err(e) = autosave::apply_rules(pool, &transaction).await {
+            eprintln!("Failed to apply auto-save rules: {}", e);
+        }
+    }
+
+    Ok((generated.len() as u32, capped))
+}
+
+// Insere a transação de uma ocorrência, a menos que ela já tenha sido gerada.
+// Retorna o id da transação criada.
+async fn insert_occurrence(
+    tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+    recurring: &RecurringTransaction,
+    occurrence: &Occurrence,
+) -> Result<Option<String>, sqlx::Error> {
+    let exists = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM transactions WHERE recurring_id = ? AND occurrence_date = ?"
+    )
+    .bind(&recurring.id)
+    .bind(occurrence.original_date)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    if exists > 0 {
+        return Ok(None);
+    }
+
+    let transaction_id = uuid::Uuid::new_v4().to_string();
+    sqlx::query(
+        "INSERT INTO transactions (id, workspace_id, user_id, description, amount, type, category_id, date, recurring, recurring_id, occurrence_date)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, TRUE, ?, ?)"
+    )
+    .bind(&transaction_id)
+    .bind(&recurring.workspace_id)
+    .bind(&recurring.user_id)
+    .bind(&occurrence.description)
+    .bind(occurrence.amount)
+    .bind(&recurring.transaction_type)
+    .bind(&recurring.category_id)
+    .bind(occurrence.date.and_hms_opt(0, 0, 0).unwrap())
+    .bind(&recurring.id)
+    .bind(occurrence.original_date)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(Some(transaction_id))
+}
+
+// Gera as ocorrências pendentes de todas as recorrências ativas do espaço de trabalho
+pub async fn generate_for_workspace(
+    pool: &MySqlPool,
+    workspace_id: &str,
+    today: NaiveDate,
+    limit: u32,
+) -> Result<GenerationSummary, sqlx::Error> {
+    let recurring_ids = sqlx::query_scalar::<_, String>(
+        "SELECT id FROM recurring_transactions WHERE workspace_id = ? AND active = TRUE"
+    )
+    .bind(workspace_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut summary = GenerationSummary::default();
+    for recurring_id in recurring_ids {
+        let (generated, capped) = generate_for_rule(pool, &recurring_id, today, limit).await?;
+        summary.generated += generated;
+        if capped {
+            summary.capped.push(recurring_id);
+        }
+    }
+
+    Ok(summary)
+}
+
 // POST /api/recurring/generate - Gerar transações pendentes
 pub async fn generate_pending(
     pool: web::Data<MySqlPool>,
-    user_id: web::ReqData<String>,
+    workspace: Workspace,
+    query: web::Query<GenerateQuery>,
 ) -> impl Responder {
-    // Buscar recorrências ativas
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    let max_limit = catchup_limit();
+    let limit = query.limit.unwrap_or(max_limit).clamp(1, max_limit);
+    let today = Utc::now().date_naive();
+
+    match generate_for_workspace(pool.get_ref(), &workspace.id, today, limit).await {
+        Ok(summary) => HttpResponse::Ok().json(serde_json::json!({
+            "message": format!("{} transactions generated", summary.generated),
+            "count": summary.generated,
+            "capped": summary.capped
+        })),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to generate recurring transactions"
+            }))
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpcomingQuery {
+    pub days: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OccurrencesQuery {
+    pub count: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Occurrence {
+    pub recurring_id: String,
+    pub date: NaiveDate,
+    // Data agendada pela regra; difere de `date` quando a ocorrência foi remarcada
+    pub original_date: NaiveDate,
+    pub description: String,
+    pub amount: Decimal,
+    pub transaction_type: String,
+    pub category_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DailyTotal {
+    pub date: NaiveDate,
+    pub income: Decimal,
+    pub expense: Decimal,
+    pub net: Decimal,
+    pub projected_balance: Decimal,
+}
+
+const DEFAULT_UPCOMING_DAYS: u32 = 30;
+const MAX_UPCOMING_DAYS: u32 = 366;
+const DEFAULT_OCCURRENCES: u32 = 12;
+const MAX_OCCURRENCES: u32 = 366;
+
+impl RecurringTransaction {
+    fn occurrence(&self, date: NaiveDate) -> Occurrence {
+        Occurrence {
+            recurring_id: self.id.clone(),
+            date: self.shift_date(date),
+            original_date: date,
+            description: self.description.clone(),
+            amount: self.amount,
+            transaction_type: self.transaction_type.clone(),
+            category_id: self.category_id.clone(),
+        }
+    }
+
+    // Prévia das ocorrências ainda não geradas, sem persistir nada. `exceptions`
+    // são as exceções pendentes (sem transação gerada); `until` e `limit` valem
+    // para as datas já remarcadas.
+    fn preview(
+        &self,
+        exceptions: &[RecurringException],
+        until: Option<NaiveDate>,
+        limit: usize,
+    ) -> Vec<Occurrence> {
+        let schedule = match self.schedule() {
+            Some(schedule) => schedule,
+            None => return Vec::new(),
+        };
+
+        let exceptions: BTreeMap<NaiveDate, &RecurringException> = exceptions
+            .iter()
+            .filter(|exception| exception.recurring_id == self.id)
+            .map(|exception| (exception.occurrence_date, exception))
+            .collect();
+        let skipped = exceptions.values().filter(|e| e.skipped).count();
+
+        let dates: Vec<NaiveDate> = schedule
+            .occurrences_after(self.last_generated)
+            .take_while(|date| until.is_none_or(|until| self.shift_date(*date) <= until))
+            .take(limit.saturating_add(skipped))
+            .collect();
+        let mut dates = self.distinct_occurrences(dates);
+
+        // Ocorrências remarcadas podem vir de fora da janela (ou de antes de last_generated)
+        for exception in exceptions.values() {
+            if exception.new_date.is_some()
+                && !dates.contains(&exception.occurrence_date)
+                && schedule.contains(exception.occurrence_date)
+            {
+                dates.push(exception.occurrence_date);
+            }
+        }
+
+        let mut occurrences: Vec<Occurrence> = dates
+            .into_iter()
+            .filter_map(|date| match exceptions.get(&date) {
+                Some(exception) => exception.apply(self.occurrence(date)),
+                None => Some(self.occurrence(date)),
+            })
+            .filter(|occurrence| until.is_none_or(|until| occurrence.date <= until))
+            .collect();
+        occurrences.sort_by_key(|occurrence| occurrence.date);
+        occurrences.truncate(limit);
+        occurrences
+    }
+}
+
+// GET /api/recurring/upcoming?days=N - Prévia das ocorrências dos próximos dias
+pub async fn upcoming(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    query: web::Query<UpcomingQuery>,
+) -> impl Responder {
+    let days = query
+        .days
+        .unwrap_or(DEFAULT_UPCOMING_DAYS)
+        .clamp(1, MAX_UPCOMING_DAYS);
+    let today = Utc::now().date_naive();
+    let until = today + Duration::days(days as i64);
+
     let recurring_list = sqlx::query_as::<_, RecurringTransaction>(
-        "SELECT * FROM recurring_transactions WHERE user_id = ? AND active = TRUE"
+        "SELECT * FROM recurring_transactions WHERE workspace_id = ? AND active = TRUE"
     )
-    .bind(user_id.as_ref() as &str)
+    .bind(&workspace.id)
     .fetch_all(pool.get_ref())
     .await;
 
@@ -256,60 +862,432 @@ pub async fn generate_pending(
         }
     };
 
-    let mut generated_count = 0;
-    let now = chrono::Utc::now();
-
-    for recurring in recurring_list {
-        // Verificar se precisa gerar
-        let should_generate = match recurring.last_generated {
-            None => true,
-            Some(last) => {
-                let days_since = (now - last).num_days();
-                match recurring.frequency.as_str() {
-                    "daily" => days_since >= 1,
-                    "weekly" => days_since >= 7,
-                    "monthly" => days_since >= 30,
-                    "yearly" => days_since >= 365,
-                    _ => false,
-                }
+    let exceptions = match pending_exceptions(pool.get_ref(), &workspace.id, None).await {
+        Ok(exceptions) => exceptions,
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to fetch recurring exceptions"
+            }));
+        }
+    };
+
+    let current_balance = sqlx::query_scalar::<_, Decimal>(
+        "SELECT COALESCE(SUM(CASE WHEN type = 'income' THEN amount ELSE -amount END), 0)
+         FROM transactions WHERE workspace_id = ?"
+    )
+    .bind(&workspace.id)
+    .fetch_one(pool.get_ref())
+    .await;
+
+    let current_balance = match current_balance {
+        Ok(balance) => balance,
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            }));
+        }
+    };
+
+    // Ocorrências atrasadas (ainda não geradas) também entram na prévia
+    let mut occurrences: Vec<Occurrence> = recurring_list
+        .iter()
+        .flat_map(|recurring| recurring.preview(&exceptions, Some(until), usize::MAX))
+        .collect();
+    occurrences.sort_by(|a, b| a.date.cmp(&b.date).then(a.description.cmp(&b.description)));
+
+    let mut per_day: BTreeMap<NaiveDate, (Decimal, Decimal)> = BTreeMap::new();
+    for occurrence in &occurrences {
+        let entry = per_day.entry(occurrence.date).or_default();
+        if occurrence.transaction_type == "income" {
+            entry.0 += occurrence.amount;
+        } else {
+            entry.1 += occurrence.amount;
+        }
+    }
+
+    let mut projected_balance = current_balance;
+    let daily_totals: Vec<DailyTotal> = per_day
+        .into_iter()
+        .map(|(date, (income, expense))| {
+            projected_balance += income - expense;
+            DailyTotal {
+                date,
+                income,
+                expense,
+                net: income - expense,
+                projected_balance,
             }
-        };
+        })
+        .collect();
+
+    let total_income: Decimal = daily_totals.iter().map(|d| d.income).sum();
+    let total_expense: Decimal = daily_totals.iter().map(|d| d.expense).sum();
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "from": today,
+        "to": until,
+        "days": days,
+        "occurrences": occurrences,
+        "daily_totals": daily_totals,
+        "total_income": total_income,
+        "total_expense": total_expense,
+        "net": total_income - total_expense,
+        "current_balance": current_balance,
+        "projected_balance": projected_balance
+    }))
+}
+
+// GET /api/recurring/{id}/occurrences?count=N - Próximas ocorrências de uma recorrência
+pub async fn occurrences(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    recurring_id: web::Path<String>,
+    query: web::Query<OccurrencesQuery>,
+) -> impl Responder {
+    let count = query
+        .count
+        .unwrap_or(DEFAULT_OCCURRENCES)
+        .clamp(1, MAX_OCCURRENCES);
+
+    let recurring = find_recurring(pool.get_ref(), &recurring_id, &workspace.id).await;
+
+    let recurring = match recurring {
+        Ok(Some(recurring)) => pending_exceptions(pool.get_ref(), &workspace.id, Some(&recurring.id))
+            .await
+            .map(|exceptions| Some((recurring, exceptions))),
+        Ok(None) => Ok(None),
+        Err(e) => Err(e),
+    };
+
+    match recurring {
+        Ok(Some((recurring, exceptions))) => {
+            let occurrences = recurring.preview(&exceptions, None, count as usize);
+            let total: Decimal = occurrences.iter().map(|o| o.amount).sum();
+
+            HttpResponse::Ok().json(serde_json::json!({
+                "recurring": RecurringWithProjection::from(recurring),
+                "occurrences": occurrences,
+                "total": total
+            }))
+        }
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Recurring transaction not found"
+        })),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            }))
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct RecurringException {
+    pub id: String,
+    pub recurring_id: String,
+    pub user_id: String,
+    // Data original da ocorrência na agenda da recorrência
+    pub occurrence_date: NaiveDate,
+    pub skipped: bool,
+    pub new_date: Option<NaiveDate>,
+    pub amount: Option<Decimal>,
+    pub description: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl RecurringException {
+    // Aplica a exceção à ocorrência; None quando ela foi pulada
+    fn apply(&self, mut occurrence: Occurrence) -> Option<Occurrence> {
+        if self.skipped {
+            return None;
+        }
+        if let Some(new_date) = self.new_date {
+            occurrence.date = new_date;
+        }
+        if let Some(amount) = self.amount {
+            occurrence.amount = amount;
+        }
+        if let Some(description) = &self.description {
+            occurrence.description = description.clone();
+        }
+        Some(occurrence)
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpsertException {
+    pub skipped: Option<bool>,
+    pub new_date: Option<NaiveDate>,
+    pub amount: Option<f64>,
+    #[validate(length(min = 1, max = 255))]
+    pub description: Option<String>,
+}
+
+async fn find_recurring(
+    pool: &MySqlPool,
+    recurring_id: &str,
+    workspace_id: &str,
+) -> Result<Option<RecurringTransaction>, sqlx::Error> {
+    sqlx::query_as::<_, RecurringTransaction>(
+        "SELECT * FROM recurring_transactions WHERE id = ? AND workspace_id = ?"
+    )
+    .bind(recurring_id)
+    .bind(workspace_id)
+    .fetch_optional(pool)
+    .await
+}
+
+// Exceções cujas ocorrências ainda não viraram transação
+async fn pending_exceptions(
+    pool: &MySqlPool,
+    workspace_id: &str,
+    recurring_id: Option<&str>,
+) -> Result<Vec<RecurringException>, sqlx::Error> {
+    sqlx::query_as::<_, RecurringException>(
+        "SELECT e.* FROM recurring_exceptions e
+         JOIN recurring_transactions r ON r.id = e.recurring_id
+         WHERE r.workspace_id = ? AND (? IS NULL OR e.recurring_id = ?)
+           AND NOT EXISTS (
+               SELECT 1 FROM transactions t
+               WHERE t.recurring_id = e.recurring_id AND t.occurrence_date = e.occurrence_date
+           )"
+    )
+    .bind(workspace_id)
+    .bind(recurring_id)
+    .bind(recurring_id)
+    .fetch_all(pool)
+    .await
+}
+
+fn parse_occurrence_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()
+}
+
+// GET /api/recurring/{id}/exceptions - Listar exceções da recorrência
+pub async fn get_exceptions(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    recurring_id: web::Path<String>,
+) -> impl Responder {
+    match find_recurring(pool.get_ref(), &recurring_id, &workspace.id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Recurring transaction not found"
+            }));
+        }
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            }));
+        }
+    }
+
+    let exceptions = sqlx::query_as::<_, RecurringException>(
+        "SELECT * FROM recurring_exceptions WHERE recurring_id = ? ORDER BY occurrence_date"
+    )
+    .bind(recurring_id.into_inner())
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match exceptions {
+        Ok(exceptions) => HttpResponse::Ok().json(exceptions),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to fetch recurring exceptions"
+            }))
+        }
+    }
+}
+
+// PUT /api/recurring/{id}/occurrences/{date} - Pular, remarcar ou alterar uma ocorrência
+pub async fn upsert_exception(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    path: web::Path<(String, String)>,
+    exception_data: web::Json<UpsertException>,
+) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    if let Err(errors) = exception_data.validate() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Validation failed",
+            "details": errors.to_string()
+        }));
+    }
+
+    let (recurring_id, date) = path.into_inner();
+    let occurrence_date = match parse_occurrence_date(&date) {
+        Some(date) => date,
+        None => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Invalid date, expected YYYY-MM-DD"
+            }));
+        }
+    };
+
+    let skipped = exception_data.skipped.unwrap_or(false);
+    if !skipped
+        && exception_data.new_date.is_none()
+        && exception_data.amount.is_none()
+        && exception_data.description.is_none()
+    {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Exception must skip the occurrence or change its date, amount or description"
+        }));
+    }
+
+    let amount = match exception_data.amount {
+        Some(amount) => match Decimal::from_f64_retain(amount) {
+            Some(decimal) if amount > 0.0 => Some(decimal),
+            _ => {
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": "Invalid amount"
+                }));
+            }
+        },
+        None => None,
+    };
+
+    let recurring = match find_recurring(pool.get_ref(), &recurring_id, &workspace.id).await {
+        Ok(Some(recurring)) => recurring,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Recurring transaction not found"
+            }));
+        }
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            }));
+        }
+    };
+
+    if !recurring
+        .schedule()
+        .is_some_and(|schedule| schedule.contains(occurrence_date))
+    {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Date is not a scheduled occurrence of this recurring transaction"
+        }));
+    }
+
+    let generated = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM transactions WHERE recurring_id = ? AND occurrence_date = ?"
+    )
+    .bind(&recurring.id)
+    .bind(occurrence_date)
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match generated {
+        Ok(0) => {}
+        Ok(_) => {
+            return HttpResponse::Conflict().json(serde_json::json!({
+                "error": "Occurrence already generated, edit its transaction instead"
+            }));
+        }
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            }));
+        }
+    }
 
-        if should_generate {
-            // Criar transação
-            let transaction_id = uuid::Uuid::new_v4().to_string();
-            let result = sqlx::query(
-                "INSERT INTO transactions (id, user_id, description, amount, type, category_id, date, recurring, recurring_id)
-                 VALUES (?, ?, ?, ?, ?, ?, ?, TRUE, ?)"
+    let result = sqlx::query(
+        "INSERT INTO recurring_exceptions (id, recurring_id, user_id, occurrence_date, skipped, new_date, amount, description)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+         ON DUPLICATE KEY UPDATE skipped = VALUES(skipped), new_date = VALUES(new_date),
+                                 amount = VALUES(amount), description = VALUES(description)"
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(&recurring.id)
+    .bind(&workspace.user_id)
+    .bind(occurrence_date)
+    .bind(skipped)
+    .bind(exception_data.new_date)
+    .bind(amount)
+    .bind(&exception_data.description)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(_) => {
+            let exception = sqlx::query_as::<_, RecurringException>(
+                "SELECT * FROM recurring_exceptions WHERE recurring_id = ? AND occurrence_date = ?"
             )
-            .bind(&transaction_id)
-            .bind(&recurring.user_id)
-            .bind(&recurring.description)
-            .bind(&recurring.amount)
-            .bind(&recurring.transaction_type)
-            .bind(&recurring.category_id)
-            .bind(now)
             .bind(&recurring.id)
-            .execute(pool.get_ref())
-            .await;
-
-            if result.is_ok() {
-                // Atualizar last_generated
-                let _ = sqlx::query(
-                    "UPDATE recurring_transactions SET last_generated = ? WHERE id = ?"
-                )
-                .bind(now)
-                .bind(&recurring.id)
-                .execute(pool.get_ref())
-                .await;
-
-                generated_count += 1;
-            }
+            .bind(occurrence_date)
+            .fetch_one(pool.get_ref())
+            .await
+            .unwrap();
+
+            HttpResponse::Ok().json(exception)
+        }
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to save recurring exception"
+            }))
         }
     }
+}
 
-    HttpResponse::Ok().json(serde_json::json!({
-        "message": format!("{} transactions generated", generated_count),
-        "count": generated_count
-    }))
+// DELETE /api/recurring/{id}/occurrences/{date} - Remover exceção de uma ocorrência
+pub async fn delete_exception(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    let (recurring_id, date) = path.into_inner();
+    let occurrence_date = match parse_occurrence_date(&date) {
+        Some(date) => date,
+        None => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Invalid date, expected YYYY-MM-DD"
+            }));
+        }
+    };
+
+    let result = sqlx::query(
+        "DELETE e FROM recurring_exceptions e
+         JOIN recurring_transactions r ON r.id = e.recurring_id
+         WHERE e.recurring_id = ? AND r.workspace_id = ? AND e.occurrence_date = ?"
+    )
+    .bind(recurring_id)
+    .bind(&workspace.id)
+    .bind(occurrence_date)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(result) if result.rows_affected() > 0 => {
+            HttpResponse::Ok().json(serde_json::json!({
+                "message": "Recurring exception deleted successfully"
+            }))
+        }
+        Ok(_) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Recurring exception not found"
+        })),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to delete recurring exception"
+            }))
+        }
+    }
 }
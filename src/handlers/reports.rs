@@ -0,0 +1,418 @@
+use actix_web::{web, HttpResponse, Responder};
+use chrono::{Datelike, Duration, Months, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, MySqlPool};
+use std::collections::BTreeMap;
+
+use crate::middleware::workspace::Workspace;
+use crate::utils::parse_utc_offset;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Granularity {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupBy {
+    Category,
+    Type,
+    Tag,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CashflowQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub granularity: Option<Granularity>,
+    pub group_by: Option<GroupBy>,
+    pub tz: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct BucketRow {
+    period: NaiveDate,
+    group_key: Option<String>,
+    group_name: Option<String>,
+    income: Decimal,
+    expense: Decimal,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PeriodChange {
+    pub income: Decimal,
+    pub expense: Decimal,
+    pub net: Decimal,
+    pub net_percentage: Option<Decimal>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CashflowBucket {
+    pub period: NaiveDate,
+    pub income: Decimal,
+    pub expense: Decimal,
+    pub net: Decimal,
+    pub running_balance: Decimal,
+    pub change: Option<PeriodChange>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GroupPoint {
+    pub period: NaiveDate,
+    pub income: Decimal,
+    pub expense: Decimal,
+    pub net: Decimal,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CashflowGroup {
+    pub key: Option<String>,
+    pub name: String,
+    pub income: Decimal,
+    pub expense: Decimal,
+    pub series: Vec<GroupPoint>,
+}
+
+// (receitas, despesas) por início de período
+type PeriodTotals = BTreeMap<NaiveDate, (Decimal, Decimal)>;
+
+const MAX_BUCKETS: usize = 1000;
+
+impl Granularity {
+    // Expressão SQL que leva a data local ao início do seu período
+    fn bucket_sql(self) -> &'static str {
+        match self {
+            Granularity::Day => "DATE(local_date)",
+            Granularity::Week => "DATE_SUB(DATE(local_date), INTERVAL WEEKDAY(local_date) DAY)",
+            Granularity::Month => "CAST(DATE_FORMAT(local_date, '%Y-%m-01') AS DATE)",
+            Granularity::Year => "CAST(DATE_FORMAT(local_date, '%Y-01-01') AS DATE)",
+        }
+    }
+
+    // Mesmo cálculo em Rust, usado para preencher os períodos sem movimento
+    fn bucket_start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Granularity::Day => date,
+            Granularity::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+            Granularity::Month => date.with_day(1).unwrap(),
+            Granularity::Year => NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap(),
+        }
+    }
+
+    fn next(self, bucket: NaiveDate) -> NaiveDate {
+        match self {
+            Granularity::Day => bucket + Duration::days(1),
+            Granularity::Week => bucket + Duration::days(7),
+            Granularity::Month => bucket + Months::new(1),
+            Granularity::Year => bucket + Months::new(12),
+        }
+    }
+
+    fn buckets(self, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        let mut buckets = Vec::new();
+        let mut current = self.bucket_start(from);
+        while current <= to && buckets.len() <= MAX_BUCKETS {
+            buckets.push(current);
+            current = self.next(current);
+        }
+        buckets
+    }
+}
+
+impl GroupBy {
+    fn key_sql(self) -> &'static str {
+        match self {
+            GroupBy::Category => "t.category_id",
+            GroupBy::Type => "t.type",
+            GroupBy::Tag => "g.id",
+        }
+    }
+
+    fn name_sql(self) -> &'static str {
+        match self {
+            GroupBy::Category => "COALESCE(c.name, 'Sem categoria')",
+            GroupBy::Type => "t.type",
+            GroupBy::Tag => "COALESCE(g.name, 'Sem tag')",
+        }
+    }
+
+    // Uma transação com várias tags conta em cada uma delas
+    fn join_sql(self) -> &'static str {
+        match self {
+            GroupBy::Category => "LEFT JOIN categories c ON c.id = t.category_id",
+            GroupBy::Type => "",
+            GroupBy::Tag => {
+                "LEFT JOIN transaction_tags tt ON tt.transaction_id = t.transaction_id
+                 LEFT JOIN tags g ON g.id = tt.tag_id"
+            }
+        }
+    }
+}
+
+fn percentage_change(previous: Decimal, current: Decimal) -> Option<Decimal> {
+    if previous.is_zero() {
+        None
+    } else {
+        Some(((current - previous) * Decimal::from(100) / previous.abs()).round_dp(2))
+    }
+}
+
+// GET /api/reports/cashflow - Fluxo de caixa por período
+pub async fn cashflow(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    query: web::Query<CashflowQuery>,
+) -> impl Responder {
+    let timezone = match &query.tz {
+        Some(tz) => tz.clone(),
+        None => {
+            let stored = sqlx::query_scalar::<_, String>("SELECT timezone FROM users WHERE id = ?")
+                .bind(&workspace.user_id)
+                .fetch_one(pool.get_ref())
+                .await;
+
+            match stored {
+                Ok(tz) => tz,
+                Err(e) => {
+                    eprintln!("Database error: {}", e);
+                    return HttpResponse::InternalServerError().json(serde_json::json!({
+                        "error": "Database error"
+                    }));
+                }
+            }
+        }
+    };
+
+    let offset = match parse_utc_offset(&timezone) {
+        Some(offset) => offset,
+        None => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Invalid timezone, expected an offset like -03:00"
+            }));
+        }
+    };
+
+    let today = Utc::now().with_timezone(&offset).date_naive();
+    let to = query.to.unwrap_or(today);
+    let from = query
+        .from
+        .unwrap_or_else(|| to.with_day(1).unwrap() - Months::new(11));
+    let granularity = query.granularity.unwrap_or(Granularity::Month);
+
+    if from > to {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "'from' must not be after 'to'"
+        }));
+    }
+
+    let periods = granularity.buckets(from, to);
+    if periods.len() > MAX_BUCKETS {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Too many buckets, the limit is {}", MAX_BUCKETS)
+        }));
+    }
+
+    // Limites do intervalo em UTC, a partir da meia-noite no fuso do usuário
+    let utc_shift = Duration::seconds(offset.local_minus_utc() as i64);
+    let range_start = from.and_hms_opt(0, 0, 0).unwrap() - utc_shift;
+    let range_end = (to + Duration::days(1)).and_hms_opt(0, 0, 0).unwrap() - utc_shift;
+
+    let opening_balance = sqlx::query_scalar::<_, Decimal>(
+        "SELECT COALESCE(SUM(CASE WHEN type = 'income' THEN amount ELSE -amount END), 0)
+         FROM transactions WHERE workspace_id = ? AND date < ?"
+    )
+    .bind(&workspace.id)
+    .bind(range_start)
+    .fetch_one(pool.get_ref())
+    .await;
+
+    let opening_balance = match opening_balance {
+        Ok(balance) => balance,
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to build cashflow report"
+            }));
+        }
+    };
+
+    let rows = fetch_buckets(
+        pool.get_ref(),
+        &workspace.id,
+        &timezone,
+        range_start,
+        range_end,
+        granularity,
+        None,
+    )
+    .await;
+
+    let rows = match rows {
+        Ok(rows) => rows,
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to build cashflow report"
+            }));
+        }
+    };
+
+    let totals: PeriodTotals = rows
+        .into_iter()
+        .map(|row| (row.period, (row.income, row.expense)))
+        .collect();
+
+    let mut running_balance = opening_balance;
+    let mut previous: Option<(Decimal, Decimal, Decimal)> = None;
+    let mut buckets = Vec::with_capacity(periods.len());
+
+    for period in &periods {
+        let (income, expense) = totals.get(period).copied().unwrap_or_default();
+        let net = income - expense;
+        running_balance += net;
+
+        let change = previous.map(|(prev_income, prev_expense, prev_net)| PeriodChange {
+            income: income - prev_income,
+            expense: expense - prev_expense,
+            net: net - prev_net,
+            net_percentage: percentage_change(prev_net, net),
+        });
+        previous = Some((income, expense, net));
+
+        buckets.push(CashflowBucket {
+            period: *period,
+            income,
+            expense,
+            net,
+            running_balance,
+            change,
+        });
+    }
+
+    let groups = match query.group_by {
+        Some(group_by) => {
+            let rows = fetch_buckets(
+                pool.get_ref(),
+                &workspace.id,
+                &timezone,
+                range_start,
+                range_end,
+                granularity,
+                Some(group_by),
+            )
+            .await;
+
+            match rows {
+                Ok(rows) => Some(build_groups(rows, &periods)),
+                Err(e) => {
+                    eprintln!("Database error: {}", e);
+                    return HttpResponse::InternalServerError().json(serde_json::json!({
+                        "error": "Failed to build cashflow report"
+                    }));
+                }
+            }
+        }
+        None => None,
+    };
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "from": from,
+        "to": to,
+        "granularity": granularity,
+        "timezone": timezone,
+        "opening_balance": opening_balance,
+        "closing_balance": running_balance,
+        "buckets": buckets,
+        "groups": groups
+    }))
+}
+
+async fn fetch_buckets(
+    pool: &MySqlPool,
+    workspace_id: &str,
+    timezone: &str,
+    range_start: chrono::NaiveDateTime,
+    range_end: chrono::NaiveDateTime,
+    granularity: Granularity,
+    group_by: Option<GroupBy>,
+) -> Result<Vec<BucketRow>, sqlx::Error> {
+    let (key, name, join) = match group_by {
+        Some(group_by) => (group_by.key_sql(), group_by.name_sql(), group_by.join_sql()),
+        None => ("NULL", "NULL", ""),
+    };
+
+    // O MySQL grava TIMESTAMP em UTC; CONVERT_TZ com deslocamento fixo não
+    // depende das tabelas de fuso horário do servidor.
+    let query = format!(
+        "SELECT {bucket} AS period,
+                {key} AS group_key,
+                {name} AS group_name,
+                COALESCE(SUM(CASE WHEN t.type = 'income' THEN t.amount ELSE 0 END), 0) AS income,
+                COALESCE(SUM(CASE WHEN t.type = 'expense' THEN t.amount ELSE 0 END), 0) AS expense
+         FROM (
+             SELECT CONVERT_TZ(date, '+00:00', ?) AS local_date, type, amount, category_id, transaction_id
+             FROM transaction_lines
+             WHERE workspace_id = ? AND date >= ? AND date < ?
+         ) t
+         {join}
+         GROUP BY period, group_key, group_name
+         ORDER BY period ASC",
+        bucket = granularity.bucket_sql(),
+        key = key,
+        name = name,
+        join = join,
+    );
+
+    sqlx::query_as::<_, BucketRow>(&query)
+        .bind(timezone)
+        .bind(workspace_id)
+        .bind(range_start)
+        .bind(range_end)
+        .fetch_all(pool)
+        .await
+}
+
+fn build_groups(rows: Vec<BucketRow>, periods: &[NaiveDate]) -> Vec<CashflowGroup> {
+    let mut grouped: BTreeMap<Option<String>, (String, PeriodTotals)> = BTreeMap::new();
+
+    for row in rows {
+        let entry = grouped
+            .entry(row.group_key)
+            .or_insert_with(|| (row.group_name.unwrap_or_default(), BTreeMap::new()));
+        entry.1.insert(row.period, (row.income, row.expense));
+    }
+
+    let mut groups: Vec<CashflowGroup> = grouped
+        .into_iter()
+        .map(|(key, (name, values))| {
+            let series: Vec<GroupPoint> = periods
+                .iter()
+                .map(|period| {
+                    let (income, expense) = values.get(period).copied().unwrap_or_default();
+                    GroupPoint {
+                        period: *period,
+                        income,
+                        expense,
+                        net: income - expense,
+                    }
+                })
+                .collect();
+
+            CashflowGroup {
+                key,
+                name,
+                income: series.iter().map(|p| p.income).sum(),
+                expense: series.iter().map(|p| p.expense).sum(),
+                series,
+            }
+        })
+        .collect();
+
+    groups.sort_by_key(|group| std::cmp::Reverse(group.income + group.expense));
+    groups
+}
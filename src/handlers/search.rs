@@ -0,0 +1,174 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, MySqlPool};
+
+use crate::handlers::goals::Goal;
+use crate::middleware::workspace::Workspace;
+use crate::models::{Category, Transaction};
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    pub q: String,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct TransactionHit {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub transaction: Transaction,
+    pub score: f64,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct CategoryHit {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub category: Category,
+    pub score: i64,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct GoalHit {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub goal: Goal,
+    pub score: i64,
+}
+
+const DEFAULT_LIMIT: u32 = 20;
+const MAX_LIMIT: u32 = 100;
+
+// Monta a expressão do MATCH ... AGAINST em BOOLEAN MODE: todos os termos
+// são obrigatórios e aceitam prefixo ("ube" encontra "Uber").
+fn fulltext_query(q: &str) -> String {
+    q.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("+{}*", word))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Escapa os curingas do LIKE para que o termo seja buscado literalmente
+fn escape_like(q: &str) -> String {
+    q.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+// GET /api/search?q= - Buscar transações, categorias e metas
+//
+// A comparação usa o collation utf8mb4_unicode_ci das colunas, que ignora
+// acentos e maiúsculas: "alimentacao" encontra "Alimentação".
+pub async fn search(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    query: web::Query<SearchQuery>,
+) -> impl Responder {
+    let q = query.q.trim();
+    if q.is_empty() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Query must not be empty"
+        }));
+    }
+
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let fulltext = fulltext_query(q);
+    let escaped = escape_like(q);
+    let pattern = format!("%{}%", escaped);
+    let prefix = format!("{}%", escaped);
+
+    // O índice FULLTEXT cobre palavras completas e prefixos; o LIKE garante
+    // resultados para termos menores que innodb_ft_min_token_size.
+    let transactions = sqlx::query_as::<_, TransactionHit>(
+        "SELECT *, MATCH(description) AGAINST (? IN BOOLEAN MODE) AS score
+         FROM transactions
+         WHERE workspace_id = ?
+           AND (MATCH(description) AGAINST (? IN BOOLEAN MODE) OR description LIKE ?)
+         ORDER BY score DESC, date DESC
+         LIMIT ?"
+    )
+    .bind(&fulltext)
+    .bind(&workspace.id)
+    .bind(&fulltext)
+    .bind(&pattern)
+    .bind(limit)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    let transactions = match transactions {
+        Ok(transactions) => transactions,
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to search transactions"
+            }));
+        }
+    };
+
+    let categories = sqlx::query_as::<_, CategoryHit>(
+        "SELECT id, workspace_id, user_id, name, icon, color, type as category_type, is_default, created_at,
+                CASE WHEN name = ? THEN 3 WHEN name LIKE ? THEN 2 ELSE 1 END AS score
+         FROM categories
+         WHERE (workspace_id = ? OR is_default = TRUE) AND name LIKE ?
+         ORDER BY score DESC, name ASC
+         LIMIT ?"
+    )
+    .bind(q)
+    .bind(&prefix)
+    .bind(&workspace.id)
+    .bind(&pattern)
+    .bind(limit)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    let categories = match categories {
+        Ok(categories) => categories,
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to search categories"
+            }));
+        }
+    };
+
+    let goals = sqlx::query_as::<_, GoalHit>(
+        "SELECT *, CASE WHEN name = ? THEN 3 WHEN name LIKE ? THEN 2 ELSE 1 END AS score
+         FROM goals
+         WHERE workspace_id = ? AND name LIKE ?
+         ORDER BY score DESC, deadline ASC
+         LIMIT ?"
+    )
+    .bind(q)
+    .bind(&prefix)
+    .bind(&workspace.id)
+    .bind(&pattern)
+    .bind(limit)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    let goals = match goals {
+        Ok(goals) => goals,
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to search goals"
+            }));
+        }
+    };
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "query": q,
+        "total": transactions.len() + categories.len() + goals.len(),
+        "results": {
+            "transactions": transactions,
+            "categories": categories,
+            "goals": goals
+        }
+    }))
+}
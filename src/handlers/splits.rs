@@ -0,0 +1,1368 @@
+use actix_web::{web, HttpResponse, Responder};
+use chrono::{NaiveDate, Utc};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, MySqlPool};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use validator::Validate;
+
+use crate::handlers::accounts::account_exists;
+use crate::handlers::installments::split_amount;
+use crate::handlers::notifications::notify;
+use crate::middleware::workspace::{member_role, Workspace};
+use crate::models::Transaction;
+
+// Divisão de uma despesa: `paid_by` pagou a transação inteira e cada
+// participante deve a ele a própria parte (a parte de quem pagou não gera dívida)
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ExpenseSplit {
+    pub id: String,
+    pub transaction_id: String,
+    pub paid_by: String,
+    // equal, exact ou percent
+    pub method: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct SplitShare {
+    pub id: String,
+    pub user_id: Option<String>,
+    pub contact_id: Option<String>,
+    // Nome do usuário ou do contato
+    pub name: String,
+    pub amount: Decimal,
+    pub percent: Option<Decimal>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SplitView {
+    #[serde(flatten)]
+    pub split: ExpenseSplit,
+    pub description: String,
+    pub total_amount: Decimal,
+    pub shares: Vec<SplitShare>,
+}
+
+// Divisões das quais o usuário participa, pagas por ele ou por outra pessoa
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct SplitSummary {
+    pub id: String,
+    pub transaction_id: String,
+    pub description: String,
+    pub date: chrono::DateTime<chrono::Utc>,
+    pub total_amount: Decimal,
+    pub paid_by: String,
+    pub paid_by_name: String,
+    pub method: String,
+    pub your_share: Decimal,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Settlement {
+    pub id: String,
+    pub from_user_id: Option<String>,
+    pub from_contact_id: Option<String>,
+    pub to_user_id: Option<String>,
+    pub to_contact_id: Option<String>,
+    pub amount: Decimal,
+    pub transaction_id: Option<String>,
+    pub created_by: String,
+    // pending, confirmed ou declined; só os confirmados abatem o saldo
+    pub status: String,
+    pub settled_at: NaiveDate,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub responded_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SplitParticipant {
+    // Membro de algum espaço do usuário; o próprio e-mail inclui quem pagou na divisão
+    pub email: Option<String>,
+    // Contato sem cadastro, criado pelo nome na primeira vez
+    pub name: Option<String>,
+    // Obrigatório no método exact
+    pub amount: Option<f64>,
+    // Obrigatório no método percent
+    pub percent: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct SaveSplit {
+    #[validate(custom = "validate_method")]
+    pub method: String,
+    // Quem pagou, entre os membros do espaço; padrão: quem lançou a despesa
+    pub paid_by: Option<String>,
+    #[validate(length(min = 1, max = 50))]
+    pub participants: Vec<SplitParticipant>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SettleUp {
+    pub user_id: Option<String>,
+    pub contact_id: Option<String>,
+    // Padrão: o saldo inteiro com a pessoa
+    pub amount: Option<f64>,
+    pub account_id: Option<String>,
+    pub date: Option<NaiveDate>,
+    // Registra a receita ou despesa do acerto no espaço ativo (padrão true)
+    pub record_transaction: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Person {
+    pub user_id: Option<String>,
+    pub contact_id: Option<String>,
+    pub name: String,
+    pub email: Option<String>,
+}
+
+// Saldo positivo: a pessoa deve ao usuário; negativo: o usuário deve a ela
+#[derive(Debug, Serialize)]
+pub struct Balance {
+    #[serde(flatten)]
+    pub person: Person,
+    pub balance: Decimal,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SuggestedPayment {
+    pub from: Person,
+    pub to: Person,
+    pub amount: Decimal,
+}
+
+fn validate_method(value: &str) -> Result<(), validator::ValidationError> {
+    match value {
+        "equal" | "exact" | "percent" => Ok(()),
+        _ => Err(validator::ValidationError::new("invalid_method")),
+    }
+}
+
+// Participante de uma dívida: usuário cadastrado ou contato de quem dividiu
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Party {
+    User(String),
+    Contact(String),
+}
+
+impl Party {
+    fn from_columns(user_id: Option<String>, contact_id: Option<String>) -> Option<Self> {
+        user_id.map(Party::User).or(contact_id.map(Party::Contact))
+    }
+
+    fn user_id(&self) -> Option<&str> {
+        match self {
+            Party::User(id) => Some(id),
+            Party::Contact(_) => None,
+        }
+    }
+
+    fn contact_id(&self) -> Option<&str> {
+        match self {
+            Party::User(_) => None,
+            Party::Contact(id) => Some(id),
+        }
+    }
+}
+
+#[derive(FromRow)]
+struct DebtRow {
+    creditor_user: Option<String>,
+    creditor_contact: Option<String>,
+    debtor_user: Option<String>,
+    debtor_contact: Option<String>,
+    amount: Decimal,
+}
+
+struct Debt {
+    creditor: Party,
+    debtor: Party,
+    amount: Decimal,
+}
+
+fn placeholders(count: usize) -> String {
+    vec!["?"; count].join(", ")
+}
+
+fn database_error(e: sqlx::Error) -> HttpResponse {
+    eprintln!("Database error: {}", e);
+    HttpResponse::InternalServerError().json(serde_json::json!({
+        "error": "Database error"
+    }))
+}
+
+fn bad_request(error: &str) -> HttpResponse {
+    HttpResponse::BadRequest().json(serde_json::json!({
+        "error": error
+    }))
+}
+
+// Partes das divisões e acertos confirmados que envolvem algum dos usuários.
+// Um acerto de `from` para `to` entra como dívida de `to` com `from`, abatendo o saldo.
+async fn load_debts(pool: &MySqlPool, user_ids: &[String]) -> Result<Vec<Debt>, sqlx::Error> {
+    let ids = placeholders(user_ids.len());
+    let sql = format!(
+        "SELECT s.paid_by AS creditor_user, CAST(NULL AS CHAR(36)) AS creditor_contact,
+                sh.user_id AS debtor_user, sh.contact_id AS debtor_contact, sh.amount
+         FROM expense_split_shares sh
+         JOIN expense_splits s ON s.id = sh.split_id
+         WHERE (sh.user_id IS NULL OR sh.user_id <> s.paid_by)
+         AND (s.paid_by IN ({ids}) OR sh.user_id IN ({ids}))
+         UNION ALL
+         SELECT from_user_id, from_contact_id, to_user_id, to_contact_id, amount
+         FROM split_settlements
+         WHERE status = 'confirmed' AND (from_user_id IN ({ids}) OR to_user_id IN ({ids}))"
+    );
+
+    let mut query = sqlx::query_as::<_, DebtRow>(&sql);
+    for _ in 0..4 {
+        for user_id in user_ids {
+            query = query.bind(user_id);
+        }
+    }
+
+    Ok(query
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .filter_map(|row| {
+            Some(Debt {
+                creditor: Party::from_columns(row.creditor_user, row.creditor_contact)?,
+                debtor: Party::from_columns(row.debtor_user, row.debtor_contact)?,
+                amount: row.amount,
+            })
+        })
+        .collect())
+}
+
+// Saldo líquido do usuário com cada pessoa com quem tem dívidas
+fn balances_with(user: &Party, debts: &[Debt]) -> BTreeMap<Party, Decimal> {
+    let mut balances = BTreeMap::new();
+    for debt in debts {
+        if &debt.creditor == user {
+            *balances.entry(debt.debtor.clone()).or_insert(Decimal::ZERO) += debt.amount;
+        } else if &debt.debtor == user {
+            *balances.entry(debt.creditor.clone()).or_insert(Decimal::ZERO) -= debt.amount;
+        }
+    }
+    balances
+}
+
+// Reduz as dívidas do grupo a poucos pagamentos: cada pessoa fica só com o
+// saldo líquido e o maior devedor paga ao maior credor até um dos dois zerar
+fn simplify(debts: &[Debt]) -> Vec<(Party, Party, Decimal)> {
+    let mut net: BTreeMap<Party, Decimal> = BTreeMap::new();
+    for debt in debts {
+        *net.entry(debt.creditor.clone()).or_insert(Decimal::ZERO) += debt.amount;
+        *net.entry(debt.debtor.clone()).or_insert(Decimal::ZERO) -= debt.amount;
+    }
+
+    let mut creditors: Vec<(Party, Decimal)> = net
+        .iter()
+        .filter(|(_, amount)| amount.is_sign_positive() && !amount.is_zero())
+        .map(|(party, amount)| (party.clone(), *amount))
+        .collect();
+    let mut debtors: Vec<(Party, Decimal)> = net
+        .into_iter()
+        .filter(|(_, amount)| amount.is_sign_negative() && !amount.is_zero())
+        .map(|(party, amount)| (party, -amount))
+        .collect();
+    creditors.sort_by_key(|(_, amount)| Reverse(*amount));
+    debtors.sort_by_key(|(_, amount)| Reverse(*amount));
+
+    let mut payments = Vec::new();
+    let (mut d, mut c) = (0, 0);
+    while d < debtors.len() && c < creditors.len() {
+        let amount = debtors[d].1.min(creditors[c].1);
+        payments.push((debtors[d].0.clone(), creditors[c].0.clone(), amount));
+        debtors[d].1 -= amount;
+        creditors[c].1 -= amount;
+        if debtors[d].1.is_zero() {
+            d += 1;
+        }
+        if creditors[c].1.is_zero() {
+            c += 1;
+        }
+    }
+    payments
+}
+
+// Pessoas que o usuário pode ver: ele mesmo, quem divide um espaço com ele,
+// quem participa das mesmas divisões e os contatos dele
+async fn visible_parties(pool: &MySqlPool, user_id: &str) -> Result<HashSet<Party>, sqlx::Error> {
+    let users = sqlx::query_scalar::<_, String>(
+        "SELECT m.user_id FROM workspace_members m
+         JOIN workspace_members me ON me.workspace_id = m.workspace_id
+         WHERE me.user_id = ?
+         UNION
+         SELECT s.paid_by FROM expense_splits s
+         JOIN expense_split_shares sh ON sh.split_id = s.id
+         WHERE sh.user_id = ?
+         UNION
+         SELECT sh.user_id FROM expense_split_shares sh
+         JOIN expense_splits s ON s.id = sh.split_id
+         LEFT JOIN expense_split_shares mine ON mine.split_id = s.id AND mine.user_id = ?
+         WHERE sh.user_id IS NOT NULL AND (s.paid_by = ? OR mine.id IS NOT NULL)"
+    )
+    .bind(user_id)
+    .bind(user_id)
+    .bind(user_id)
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    let contacts = sqlx::query_scalar::<_, String>(
+        "SELECT id FROM split_contacts WHERE user_id = ?"
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut visible: HashSet<Party> = users.into_iter().map(Party::User).collect();
+    visible.extend(contacts.into_iter().map(Party::Contact));
+    visible.insert(Party::User(user_id.to_string()));
+    Ok(visible)
+}
+
+// Nomes dos usuários envolvidos e dos contatos do usuário
+async fn load_people<'a>(
+    pool: &MySqlPool,
+    user_id: &str,
+    parties: impl Iterator<Item = &'a Party>,
+) -> Result<HashMap<Party, Person>, sqlx::Error> {
+    let user_ids: Vec<&str> = parties.filter_map(Party::user_id).collect();
+    let mut people = HashMap::new();
+
+    if !user_ids.is_empty() {
+        let sql = format!(
+            "SELECT id, full_name, email FROM users WHERE id IN ({})",
+            placeholders(user_ids.len())
+        );
+        let mut query = sqlx::query_as::<_, (String, String, String)>(&sql);
+        for id in &user_ids {
+            query = query.bind(*id);
+        }
+        for (id, full_name, email) in query.fetch_all(pool).await? {
+            people.insert(Party::User(id.clone()), Person {
+                user_id: Some(id),
+                contact_id: None,
+                name: full_name,
+                email: Some(email),
+            });
+        }
+    }
+
+    let contacts = sqlx::query_as::<_, (String, String)>(
+        "SELECT id, name FROM split_contacts WHERE user_id = ?"
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    for (id, name) in contacts {
+        people.insert(Party::Contact(id.clone()), Person {
+            user_id: None,
+            contact_id: Some(id),
+            name,
+            email: None,
+        });
+    }
+
+    Ok(people)
+}
+
+fn person(people: &HashMap<Party, Person>, party: &Party) -> Person {
+    people.get(party).cloned().unwrap_or_else(|| Person {
+        user_id: party.user_id().map(str::to_string),
+        contact_id: party.contact_id().map(str::to_string),
+        name: String::new(),
+        email: None,
+    })
+}
+
+// Valor (e percentual) da parte de cada participante; os centavos que sobram
+// do arredondamento vão para o primeiro
+fn share_amounts(
+    method: &str,
+    total: Decimal,
+    participants: &[SplitParticipant],
+) -> Result<Vec<(Decimal, Option<Decimal>)>, &'static str> {
+    match method {
+        "equal" => Ok(split_amount(total, participants.len() as u32)
+            .into_iter()
+            .map(|amount| (amount, None))
+            .collect()),
+        "exact" => {
+            let amounts = participants
+                .iter()
+                .map(|p| p.amount.and_then(Decimal::from_f64_retain).map(|a| a.round_dp(2)))
+                .collect::<Option<Vec<Decimal>>>()
+                .filter(|amounts| amounts.iter().all(|a| !a.is_sign_negative()))
+                .ok_or("Each participant needs a non-negative amount")?;
+
+            if amounts.iter().sum::<Decimal>() != total {
+                return Err("Share amounts must add up to the transaction amount");
+            }
+            Ok(amounts.into_iter().map(|amount| (amount, None)).collect())
+        }
+        _ => {
+            let percents = participants
+                .iter()
+                .map(|p| p.percent.and_then(Decimal::from_f64_retain).map(|p| p.round_dp(2)))
+                .collect::<Option<Vec<Decimal>>>()
+                .filter(|percents| percents.iter().all(|p| !p.is_sign_negative()))
+                .ok_or("Each participant needs a non-negative percent")?;
+
+            if percents.iter().sum::<Decimal>() != Decimal::ONE_HUNDRED {
+                return Err("Percents must add up to 100");
+            }
+
+            let mut amounts: Vec<Decimal> = percents
+                .iter()
+                .map(|percent| {
+                    (total * percent / Decimal::ONE_HUNDRED)
+                        .round_dp_with_strategy(2, RoundingStrategy::ToZero)
+                })
+                .collect();
+            let remainder = total - amounts.iter().sum::<Decimal>();
+            amounts[0] += remainder;
+
+            Ok(amounts.into_iter().zip(percents.into_iter().map(Some)).collect())
+        }
+    }
+}
+
+// Participantes identificados pelo e-mail (usuários de algum espaço do
+// usuário) ou pelo nome (contatos do usuário, criados na primeira divisão em
+// que aparecem)
+async fn resolve_participants(
+    pool: &MySqlPool,
+    user_id: &str,
+    participants: &[SplitParticipant],
+) -> Result<Vec<Party>, HttpResponse> {
+    let mut parties = Vec::with_capacity(participants.len());
+
+    for participant in participants {
+        let party = match (&participant.email, &participant.name) {
+            (Some(email), None) => {
+                // Só e-mails de quem já divide um espaço com o usuário: a mesma
+                // resposta para e-mails não cadastrados e de desconhecidos
+                let found = sqlx::query_scalar::<_, String>(
+                    "SELECT u.id FROM users u
+                     WHERE u.email = ? AND (u.id = ? OR EXISTS (
+                         SELECT 1 FROM workspace_members a
+                         JOIN workspace_members b ON b.workspace_id = a.workspace_id
+                         WHERE a.user_id = u.id AND b.user_id = ?
+                     ))"
+                )
+                .bind(email.trim())
+                .bind(user_id)
+                .bind(user_id)
+                .fetch_optional(pool)
+                .await
+                .map_err(database_error)?;
+
+                match found {
+                    Some(id) => Party::User(id),
+                    None => {
+                        return Err(bad_request(
+                            "Participants added by email must share a workspace with you; add other people by name"
+                        ));
+                    }
+                }
+            }
+            (None, Some(name)) => {
+                let name = name.trim();
+                if name.is_empty() || name.chars().count() > 100 {
+                    return Err(bad_request("Contact names must have 1 to 100 characters"));
+                }
+
+                sqlx::query("INSERT IGNORE INTO split_contacts (id, user_id, name) VALUES (?, ?, ?)")
+                    .bind(uuid::Uuid::new_v4().to_string())
+                    .bind(user_id)
+                    .bind(name)
+                    .execute(pool)
+                    .await
+                    .map_err(database_error)?;
+
+                let id = sqlx::query_scalar::<_, String>(
+                    "SELECT id FROM split_contacts WHERE user_id = ? AND name = ?"
+                )
+                .bind(user_id)
+                .bind(name)
+                .fetch_one(pool)
+                .await
+                .map_err(database_error)?;
+
+                Party::Contact(id)
+            }
+            _ => return Err(bad_request("Each participant needs either an email or a name")),
+        };
+
+        if parties.contains(&party) {
+            return Err(bad_request("Duplicate participant"));
+        }
+        parties.push(party);
+    }
+
+    Ok(parties)
+}
+
+async fn find_expense(
+    pool: &MySqlPool,
+    transaction_id: &str,
+    workspace_id: &str,
+) -> Result<Option<Transaction>, sqlx::Error> {
+    sqlx::query_as::<_, Transaction>(
+        "SELECT * FROM transactions WHERE id = ? AND workspace_id = ?"
+    )
+    .bind(transaction_id)
+    .bind(workspace_id)
+    .fetch_optional(pool)
+    .await
+}
+
+async fn load_split(
+    pool: &MySqlPool,
+    transaction: &Transaction,
+) -> Result<Option<SplitView>, sqlx::Error> {
+    let split = sqlx::query_as::<_, ExpenseSplit>(
+        "SELECT * FROM expense_splits WHERE transaction_id = ?"
+    )
+    .bind(&transaction.id)
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(split) = split else {
+        return Ok(None);
+    };
+
+    let shares = sqlx::query_as::<_, SplitShare>(
+        "SELECT sh.id, sh.user_id, sh.contact_id, COALESCE(u.full_name, c.name) AS name,
+                sh.amount, sh.percent
+         FROM expense_split_shares sh
+         LEFT JOIN users u ON u.id = sh.user_id
+         LEFT JOIN split_contacts c ON c.id = sh.contact_id
+         WHERE sh.split_id = ?
+         ORDER BY sh.amount DESC, name ASC"
+    )
+    .bind(&split.id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(Some(SplitView {
+        split,
+        description: transaction.description.clone(),
+        total_amount: transaction.amount,
+        shares,
+    }))
+}
+
+fn transaction_not_found() -> HttpResponse {
+    HttpResponse::NotFound().json(serde_json::json!({
+        "error": "Transaction not found"
+    }))
+}
+
+// GET /api/transactions/{id}/split - Ver a divisão da despesa
+pub async fn get_split(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    transaction_id: web::Path<String>,
+) -> impl Responder {
+    let transaction = match find_expense(pool.get_ref(), &transaction_id, &workspace.id).await {
+        Ok(Some(transaction)) => transaction,
+        Ok(None) => return transaction_not_found(),
+        Err(e) => return database_error(e),
+    };
+
+    match load_split(pool.get_ref(), &transaction).await {
+        Ok(Some(split)) => HttpResponse::Ok().json(split),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "This transaction is not split"
+        })),
+        Err(e) => database_error(e),
+    }
+}
+
+// PUT /api/transactions/{id}/split - Dividir a despesa (substitui a divisão anterior)
+pub async fn save_split(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    transaction_id: web::Path<String>,
+    split_data: web::Json<SaveSplit>,
+) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    if let Err(errors) = split_data.validate() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Validation failed",
+            "details": errors.to_string()
+        }));
+    }
+
+    let transaction = match find_expense(pool.get_ref(), &transaction_id, &workspace.id).await {
+        Ok(Some(transaction)) => transaction,
+        Ok(None) => return transaction_not_found(),
+        Err(e) => return database_error(e),
+    };
+
+    if transaction.transaction_type != "expense" {
+        return bad_request("Only expenses can be split");
+    }
+
+    let paid_by = match &split_data.paid_by {
+        Some(paid_by) if paid_by != &transaction.user_id => {
+            match member_role(pool.get_ref(), &workspace.id, paid_by).await {
+                Ok(Some(_)) => paid_by.clone(),
+                Ok(None) => return bad_request("paid_by must be a member of this workspace"),
+                Err(e) => return database_error(e),
+            }
+        }
+        _ => transaction.user_id.clone(),
+    };
+
+    let amounts = match share_amounts(&split_data.method, transaction.amount, &split_data.participants) {
+        Ok(amounts) => amounts,
+        Err(error) => return bad_request(error),
+    };
+
+    let parties = match resolve_participants(pool.get_ref(), &workspace.user_id, &split_data.participants).await {
+        Ok(parties) => parties,
+        Err(response) => return response,
+    };
+
+    // As partes foram calculadas sobre o valor lido acima: confere de novo com
+    // a transação bloqueada, caso ela tenha mudado nesse meio tempo
+    let result = async {
+        let mut tx = pool.begin().await?;
+
+        let locked = sqlx::query_as::<_, (Decimal, String)>(
+            "SELECT amount, type FROM transactions WHERE id = ? FOR UPDATE"
+        )
+        .bind(&transaction.id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if locked != (transaction.amount, transaction.transaction_type.clone()) {
+            return Ok(false);
+        }
+
+        sqlx::query("DELETE FROM expense_splits WHERE transaction_id = ?")
+            .bind(&transaction.id)
+            .execute(&mut *tx)
+            .await?;
+
+        let split_id = uuid::Uuid::new_v4().to_string();
+        sqlx::query(
+            "INSERT INTO expense_splits (id, transaction_id, paid_by, method) VALUES (?, ?, ?, ?)"
+        )
+        .bind(&split_id)
+        .bind(&transaction.id)
+        .bind(&paid_by)
+        .bind(&split_data.method)
+        .execute(&mut *tx)
+        .await?;
+
+        for (party, (amount, percent)) in parties.iter().zip(&amounts) {
+            sqlx::query(
+                "INSERT INTO expense_split_shares (id, split_id, user_id, contact_id, amount, percent)
+                 VALUES (?, ?, ?, ?, ?, ?)"
+            )
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(&split_id)
+            .bind(party.user_id())
+            .bind(party.contact_id())
+            .bind(amount)
+            .bind(percent)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(true)
+    }
+    .await;
+
+    match result {
+        Ok(true) => {}
+        Ok(false) => {
+            return HttpResponse::Conflict().json(serde_json::json!({
+                "error": "The transaction changed; send the split again"
+            }));
+        }
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to split transaction"
+            }));
+        }
+    }
+
+    for (party, (amount, _)) in parties.iter().zip(&amounts) {
+        match party.user_id() {
+            Some(user_id) if user_id != paid_by && user_id != workspace.user_id => {
+                let message = format!(
+                    "Uma despesa de R$ {} (\"{}\") foi dividida com você. Sua parte é R$ {}.",
+                    transaction.amount, transaction.description, amount
+                );
+                if let Err(e) = notify(pool.get_ref(), user_id, "Despesa dividida", &message, "info").await {
+                    eprintln!("Failed to notify split participant: {}", e);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    match load_split(pool.get_ref(), &transaction).await {
+        Ok(Some(split)) => HttpResponse::Ok().json(split),
+        Ok(None) => transaction_not_found(),
+        Err(e) => database_error(e),
+    }
+}
+
+// DELETE /api/transactions/{id}/split - Desfazer a divisão
+pub async fn delete_split(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    transaction_id: web::Path<String>,
+) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    let result = sqlx::query(
+        "DELETE s FROM expense_splits s
+         JOIN transactions t ON t.id = s.transaction_id
+         WHERE s.transaction_id = ? AND t.workspace_id = ?"
+    )
+    .bind(transaction_id.into_inner())
+    .bind(&workspace.id)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(result) if result.rows_affected() > 0 => HttpResponse::Ok().json(serde_json::json!({
+            "message": "Split deleted successfully"
+        })),
+        Ok(_) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "This transaction is not split"
+        })),
+        Err(e) => database_error(e),
+    }
+}
+
+// GET /api/splits - Listar despesas divididas em que o usuário pagou ou participa
+pub async fn get_all(
+    pool: web::Data<MySqlPool>,
+    user_id: web::ReqData<String>,
+) -> impl Responder {
+    let uid = user_id.into_inner();
+
+    let splits = sqlx::query_as::<_, SplitSummary>(
+        "SELECT s.id, s.transaction_id, t.description, t.date, t.amount AS total_amount,
+                s.paid_by, u.full_name AS paid_by_name, s.method,
+                COALESCE((SELECT sh.amount FROM expense_split_shares sh
+                          WHERE sh.split_id = s.id AND sh.user_id = ?), 0) AS your_share
+         FROM expense_splits s
+         JOIN transactions t ON t.id = s.transaction_id
+         JOIN users u ON u.id = s.paid_by
+         WHERE s.paid_by = ? OR EXISTS (
+             SELECT 1 FROM expense_split_shares sh WHERE sh.split_id = s.id AND sh.user_id = ?
+         )
+         ORDER BY t.date DESC"
+    )
+    .bind(&uid)
+    .bind(&uid)
+    .bind(&uid)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match splits {
+        Ok(splits) => HttpResponse::Ok().json(splits),
+        Err(e) => database_error(e),
+    }
+}
+
+// GET /api/splits/balances - Saldo com cada pessoa (positivo: ela deve ao usuário)
+pub async fn balances(
+    pool: web::Data<MySqlPool>,
+    user_id: web::ReqData<String>,
+) -> impl Responder {
+    let uid = user_id.into_inner();
+
+    let debts = match load_debts(pool.get_ref(), std::slice::from_ref(&uid)).await {
+        Ok(debts) => debts,
+        Err(e) => return database_error(e),
+    };
+    let balances: Vec<(Party, Decimal)> = balances_with(&Party::User(uid.clone()), &debts)
+        .into_iter()
+        .filter(|(_, balance)| !balance.is_zero())
+        .collect();
+
+    let people = match load_people(pool.get_ref(), &uid, balances.iter().map(|(party, _)| party)).await {
+        Ok(people) => people,
+        Err(e) => return database_error(e),
+    };
+
+    let owed_to_you: Decimal = balances.iter().map(|(_, b)| *b).filter(|b| b.is_sign_positive()).sum();
+    let you_owe: Decimal = -balances.iter().map(|(_, b)| *b).filter(|b| b.is_sign_negative()).sum::<Decimal>();
+
+    let mut balances: Vec<Balance> = balances
+        .iter()
+        .map(|(party, balance)| Balance {
+            person: person(&people, party),
+            balance: *balance,
+        })
+        .collect();
+    balances.sort_by_key(|b| Reverse(b.balance));
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "owed_to_you": owed_to_you,
+        "you_owe": you_owe,
+        "net": owed_to_you - you_owe,
+        "balances": balances
+    }))
+}
+
+// GET /api/splits/simplify - Sugerir o menor conjunto de pagamentos que
+// quita as dívidas do grupo do usuário: quem divide um espaço ou uma despesa
+// com ele. Só entram dívidas entre pessoas que ele pode ver; as demais não
+// são expostas nem afetam a sugestão.
+pub async fn simplify_debts(
+    pool: web::Data<MySqlPool>,
+    user_id: web::ReqData<String>,
+) -> impl Responder {
+    let uid = user_id.into_inner();
+
+    let visible = match visible_parties(pool.get_ref(), &uid).await {
+        Ok(visible) => visible,
+        Err(e) => return database_error(e),
+    };
+    let group: Vec<String> = visible
+        .iter()
+        .filter_map(Party::user_id)
+        .map(str::to_string)
+        .collect();
+
+    let debts = match load_debts(pool.get_ref(), &group).await {
+        Ok(debts) => debts,
+        Err(e) => return database_error(e),
+    };
+    let debts: Vec<Debt> = debts
+        .into_iter()
+        .filter(|debt| visible.contains(&debt.creditor) && visible.contains(&debt.debtor))
+        .collect();
+
+    let payments = simplify(&debts);
+
+    let people = match load_people(
+        pool.get_ref(),
+        &uid,
+        payments.iter().flat_map(|(from, to, _)| [from, to]),
+    )
+    .await
+    {
+        Ok(people) => people,
+        Err(e) => return database_error(e),
+    };
+
+    let payments: Vec<SuggestedPayment> = payments
+        .iter()
+        .map(|(from, to, amount)| SuggestedPayment {
+            from: person(&people, from),
+            to: person(&people, to),
+            amount: *amount,
+        })
+        .collect();
+
+    HttpResponse::Ok().json(payments)
+}
+
+// GET /api/splits/settlements - Listar acertos do usuário
+pub async fn get_settlements(
+    pool: web::Data<MySqlPool>,
+    user_id: web::ReqData<String>,
+) -> impl Responder {
+    let uid = user_id.into_inner();
+
+    let settlements = sqlx::query_as::<_, Settlement>(
+        "SELECT * FROM split_settlements
+         WHERE from_user_id = ? OR to_user_id = ?
+         ORDER BY settled_at DESC, created_at DESC"
+    )
+    .bind(&uid)
+    .bind(&uid)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match settlements {
+        Ok(settlements) => HttpResponse::Ok().json(settlements),
+        Err(e) => database_error(e),
+    }
+}
+
+// POST /api/splits/settlements - Registrar o acerto com uma pessoa. O sentido
+// vem do saldo: se ela deve ao usuário, o acerto é uma receita; senão, uma despesa.
+// O pagamento a um usuário cadastrado fica pendente até ele confirmar.
+pub async fn settle_up(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    settle_data: web::Json<SettleUp>,
+) -> impl Responder {
+    let record_transaction = settle_data.record_transaction.unwrap_or(true);
+    if record_transaction {
+        if let Err(response) = workspace.require_editor() {
+            return response;
+        }
+    }
+
+    let uid = workspace.user_id.clone();
+    let counterpart = match (&settle_data.user_id, &settle_data.contact_id) {
+        (Some(user_id), None) if user_id == &uid => {
+            return bad_request("You cannot settle up with yourself");
+        }
+        (Some(user_id), None) => Party::User(user_id.clone()),
+        (None, Some(contact_id)) => Party::Contact(contact_id.clone()),
+        _ => return bad_request("Provide either user_id or contact_id"),
+    };
+
+    let debts = match load_debts(pool.get_ref(), std::slice::from_ref(&uid)).await {
+        Ok(debts) => debts,
+        Err(e) => return database_error(e),
+    };
+    let balance = balances_with(&Party::User(uid.clone()), &debts)
+        .get(&counterpart)
+        .copied()
+        .unwrap_or(Decimal::ZERO);
+
+    if balance.is_zero() {
+        return bad_request("Nothing to settle with this person");
+    }
+
+    // Quem deve a um usuário cadastrado só informa o pagamento; o credor confirma
+    let pending = balance.is_sign_negative() && counterpart.user_id().is_some();
+    let awaiting = if pending {
+        let awaiting = sqlx::query_scalar::<_, Option<Decimal>>(
+            "SELECT SUM(amount) FROM split_settlements
+             WHERE from_user_id = ? AND to_user_id = ? AND status = 'pending'"
+        )
+        .bind(&uid)
+        .bind(counterpart.user_id())
+        .fetch_one(pool.get_ref())
+        .await;
+
+        match awaiting {
+            Ok(awaiting) => awaiting.unwrap_or(Decimal::ZERO),
+            Err(e) => return database_error(e),
+        }
+    } else {
+        Decimal::ZERO
+    };
+
+    let outstanding = balance.abs() - awaiting;
+    if outstanding <= Decimal::ZERO {
+        return bad_request("Your payments to this person are awaiting confirmation");
+    }
+
+    let amount = match settle_data.amount {
+        None => outstanding,
+        Some(value) => match Decimal::from_f64_retain(value).map(|amount| amount.round_dp(2)) {
+            Some(amount) if amount > Decimal::ZERO => amount,
+            _ => return bad_request("Invalid amount"),
+        },
+    };
+
+    if amount > outstanding {
+        return bad_request("Amount exceeds the outstanding balance");
+    }
+
+    if let Some(account_id) = &settle_data.account_id {
+        match account_exists(pool.get_ref(), &workspace.id, account_id).await {
+            Ok(true) => {}
+            Ok(false) => {
+                return HttpResponse::NotFound().json(serde_json::json!({
+                    "error": "Account not found"
+                }));
+            }
+            Err(e) => return database_error(e),
+        }
+    }
+
+    let people = match load_people(pool.get_ref(), &uid, [&counterpart, &Party::User(uid.clone())].into_iter()).await {
+        Ok(people) => people,
+        Err(e) => return database_error(e),
+    };
+    let other = person(&people, &counterpart);
+    let me = person(&people, &Party::User(uid.clone()));
+
+    let me_party = Party::User(uid.clone());
+    let (from, to, transaction_type, description) = if balance.is_sign_positive() {
+        (&counterpart, &me_party, "income", format!("Acerto de {}", other.name))
+    } else {
+        (&me_party, &counterpart, "expense", format!("Acerto com {}", other.name))
+    };
+
+    let settled_at = settle_data.date.unwrap_or_else(|| Utc::now().date_naive());
+    let settlement_id = uuid::Uuid::new_v4().to_string();
+
+    // O acerto só devolve dinheiro já gasto: não dispara alertas de orçamento
+    // nem regras de poupança automática
+    let result = async {
+        let mut tx = pool.begin().await?;
+
+        let transaction_id = if record_transaction {
+            let transaction_id = uuid::Uuid::new_v4().to_string();
+            sqlx::query(
+                "INSERT INTO transactions (id, workspace_id, user_id, description, amount, type, account_id, date, recurring)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, FALSE)"
+            )
+            .bind(&transaction_id)
+            .bind(&workspace.id)
+            .bind(&uid)
+            .bind(&description)
+            .bind(amount)
+            .bind(transaction_type)
+            .bind(&settle_data.account_id)
+            .bind(settled_at.and_hms_opt(0, 0, 0).unwrap())
+            .execute(&mut *tx)
+            .await?;
+            Some(transaction_id)
+        } else {
+            None
+        };
+
+        sqlx::query(
+            "INSERT INTO split_settlements
+             (id, from_user_id, from_contact_id, to_user_id, to_contact_id, amount, transaction_id, created_by, status, settled_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(&settlement_id)
+        .bind(from.user_id())
+        .bind(from.contact_id())
+        .bind(to.user_id())
+        .bind(to.contact_id())
+        .bind(amount)
+        .bind(&transaction_id)
+        .bind(&uid)
+        .bind(if pending { "pending" } else { "confirmed" })
+        .bind(settled_at)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await
+    }
+    .await;
+
+    if let Err(e) = result {
+        eprintln!("Database error: {}", e);
+        return HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to record settlement"
+        }));
+    }
+
+    if let Some(user_id) = counterpart.user_id() {
+        let (title, message) = if pending {
+            (
+                "Acerto aguardando confirmação",
+                format!("{} informou um pagamento de R$ {} para você. Confirme ou recuse o acerto.", me.name, amount),
+            )
+        } else {
+            ("Acerto registrado", format!("{} registrou um acerto de R$ {} com você.", me.name, amount))
+        };
+        if let Err(e) = notify(pool.get_ref(), user_id, title, &message, "info").await {
+            eprintln!("Failed to notify settlement counterpart: {}", e);
+        }
+    }
+
+    let settlement = sqlx::query_as::<_, Settlement>("SELECT * FROM split_settlements WHERE id = ?")
+        .bind(&settlement_id)
+        .fetch_one(pool.get_ref())
+        .await;
+
+    match settlement {
+        Ok(settlement) => HttpResponse::Created().json(settlement),
+        Err(e) => database_error(e),
+    }
+}
+
+// Confirma ou recusa um acerto pendente informado a favor do usuário. Na
+// recusa, a despesa registrada por quem informou o pagamento é excluída.
+async fn respond_settlement(
+    pool: &MySqlPool,
+    user_id: &str,
+    settlement_id: &str,
+    confirm: bool,
+) -> Result<Option<Settlement>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let settlement = sqlx::query_as::<_, Settlement>(
+        "SELECT * FROM split_settlements WHERE id = ? AND to_user_id = ? AND status = 'pending' FOR UPDATE"
+    )
+    .bind(settlement_id)
+    .bind(user_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(settlement) = settlement else {
+        return Ok(None);
+    };
+
+    sqlx::query("UPDATE split_settlements SET status = ?, responded_at = NOW() WHERE id = ?")
+        .bind(if confirm { "confirmed" } else { "declined" })
+        .bind(&settlement.id)
+        .execute(&mut *tx)
+        .await?;
+
+    if let (false, Some(transaction_id)) = (confirm, &settlement.transaction_id) {
+        sqlx::query("DELETE FROM transactions WHERE id = ? AND user_id = ?")
+            .bind(transaction_id)
+            .bind(&settlement.created_by)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+
+    let settlement = sqlx::query_as::<_, Settlement>("SELECT * FROM split_settlements WHERE id = ?")
+        .bind(&settlement.id)
+        .fetch_one(pool)
+        .await?;
+
+    let name = sqlx::query_scalar::<_, String>("SELECT full_name FROM users WHERE id = ?")
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+    let (title, message) = if confirm {
+        ("Acerto confirmado", format!("{} confirmou o seu pagamento de R$ {}.", name, settlement.amount))
+    } else {
+        ("Acerto recusado", format!("{} recusou o seu pagamento de R$ {}.", name, settlement.amount))
+    };
+    if let Err(e) = notify(pool, &settlement.created_by, title, &message, "info").await {
+        eprintln!("Failed to notify settlement author: {}", e);
+    }
+
+    Ok(Some(settlement))
+}
+
+// POST /api/splits/settlements/{id}/confirm - Confirmar um pagamento recebido
+pub async fn confirm_settlement(
+    pool: web::Data<MySqlPool>,
+    user_id: web::ReqData<String>,
+    settlement_id: web::Path<String>,
+) -> impl Responder {
+    match respond_settlement(pool.get_ref(), &user_id, &settlement_id, true).await {
+        Ok(Some(settlement)) => HttpResponse::Ok().json(settlement),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Pending settlement not found"
+        })),
+        Err(e) => database_error(e),
+    }
+}
+
+// POST /api/splits/settlements/{id}/decline - Recusar um pagamento não recebido
+pub async fn decline_settlement(
+    pool: web::Data<MySqlPool>,
+    user_id: web::ReqData<String>,
+    settlement_id: web::Path<String>,
+) -> impl Responder {
+    match respond_settlement(pool.get_ref(), &user_id, &settlement_id, false).await {
+        Ok(Some(settlement)) => HttpResponse::Ok().json(settlement),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Pending settlement not found"
+        })),
+        Err(e) => database_error(e),
+    }
+}
+
+// DELETE /api/splits/settlements/{id} - Desfazer um acerto registrado pelo
+// usuário (a transação criada por ele também é excluída)
+pub async fn delete_settlement(
+    pool: web::Data<MySqlPool>,
+    user_id: web::ReqData<String>,
+    settlement_id: web::Path<String>,
+) -> impl Responder {
+    let uid = user_id.into_inner();
+
+    let result = async {
+        let mut tx = pool.begin().await?;
+
+        let settlement = sqlx::query_as::<_, Settlement>(
+            "SELECT * FROM split_settlements WHERE id = ? AND created_by = ? FOR UPDATE"
+        )
+        .bind(settlement_id.into_inner())
+        .bind(&uid)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(settlement) = settlement else {
+            return Ok(false);
+        };
+
+        sqlx::query("DELETE FROM split_settlements WHERE id = ?")
+            .bind(&settlement.id)
+            .execute(&mut *tx)
+            .await?;
+
+        if let Some(transaction_id) = &settlement.transaction_id {
+            sqlx::query("DELETE FROM transactions WHERE id = ? AND user_id = ?")
+                .bind(transaction_id)
+                .bind(&uid)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(true)
+    }
+    .await;
+
+    match result {
+        Ok(true) => HttpResponse::Ok().json(serde_json::json!({
+            "message": "Settlement deleted successfully"
+        })),
+        Ok(false) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Settlement not found"
+        })),
+        Err(e) => database_error(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cents(value: i64) -> Decimal {
+        Decimal::new(value, 2)
+    }
+
+    fn user(id: &str) -> Party {
+        Party::User(id.to_string())
+    }
+
+    fn debt(debtor: &str, creditor: &str, amount: Decimal) -> Debt {
+        Debt {
+            creditor: user(creditor),
+            debtor: user(debtor),
+            amount,
+        }
+    }
+
+    fn participant(amount: Option<f64>, percent: Option<f64>) -> SplitParticipant {
+        SplitParticipant {
+            email: None,
+            name: Some("Contato".to_string()),
+            amount,
+            percent,
+        }
+    }
+
+    #[test]
+    fn equal_split_gives_remainder_cents_to_the_first_participant() {
+        let participants: Vec<_> = (0..3).map(|_| participant(None, None)).collect();
+        let shares = share_amounts("equal", cents(10000), &participants).unwrap();
+        assert_eq!(
+            shares,
+            vec![(cents(3334), None), (cents(3333), None), (cents(3333), None)]
+        );
+    }
+
+    #[test]
+    fn percent_split_gives_remainder_cents_to_the_first_participant() {
+        let participants = vec![
+            participant(None, Some(33.33)),
+            participant(None, Some(33.33)),
+            participant(None, Some(33.34)),
+        ];
+        let shares = share_amounts("percent", cents(1000), &participants).unwrap();
+        let amounts: Vec<Decimal> = shares.iter().map(|(amount, _)| *amount).collect();
+        assert_eq!(amounts, vec![cents(334), cents(333), cents(333)]);
+        assert_eq!(shares[2].1, Some(cents(3334)));
+    }
+
+    #[test]
+    fn percents_must_add_up_to_one_hundred() {
+        let participants = vec![participant(None, Some(50.0)), participant(None, Some(40.0))];
+        assert_eq!(
+            share_amounts("percent", cents(1000), &participants),
+            Err("Percents must add up to 100")
+        );
+    }
+
+    #[test]
+    fn exact_amounts_must_add_up_to_the_total() {
+        let participants = vec![participant(Some(30.0), None), participant(Some(60.0), None)];
+        assert_eq!(
+            share_amounts("exact", cents(10000), &participants),
+            Err("Share amounts must add up to the transaction amount")
+        );
+
+        let participants = vec![participant(Some(40.0), None), participant(Some(60.0), None)];
+        assert_eq!(
+            share_amounts("exact", cents(10000), &participants),
+            Ok(vec![(cents(4000), None), (cents(6000), None)])
+        );
+    }
+
+    #[test]
+    fn exact_amounts_cannot_be_missing_or_negative() {
+        let participants = vec![participant(Some(110.0), None), participant(Some(-10.0), None)];
+        assert_eq!(
+            share_amounts("exact", cents(10000), &participants),
+            Err("Each participant needs a non-negative amount")
+        );
+
+        let participants = vec![participant(Some(100.0), None), participant(None, None)];
+        assert!(share_amounts("exact", cents(10000), &participants).is_err());
+    }
+
+    #[test]
+    fn even_cycle_simplifies_to_no_payments() {
+        let debts = vec![
+            debt("ana", "bruno", cents(5000)),
+            debt("bruno", "carla", cents(5000)),
+            debt("carla", "ana", cents(5000)),
+        ];
+        assert!(simplify(&debts).is_empty());
+    }
+
+    #[test]
+    fn uneven_cycle_simplifies_to_the_net_balances() {
+        // Ana deve 50 a Bruno, Bruno deve 30 a Carla e Carla deve 20 a Ana:
+        // saldos líquidos Ana -30, Bruno +20, Carla +10
+        let debts = vec![
+            debt("ana", "bruno", cents(5000)),
+            debt("bruno", "carla", cents(3000)),
+            debt("carla", "ana", cents(2000)),
+        ];
+        assert_eq!(
+            simplify(&debts),
+            vec![
+                (user("ana"), user("bruno"), cents(2000)),
+                (user("ana"), user("carla"), cents(1000)),
+            ]
+        );
+    }
+
+    #[test]
+    fn balances_net_debts_in_both_directions() {
+        let debts = vec![
+            debt("bruno", "ana", cents(5000)),
+            debt("ana", "bruno", cents(2000)),
+            debt("ana", "carla", cents(1500)),
+            debt("bruno", "carla", cents(9900)),
+        ];
+        let balances = balances_with(&user("ana"), &debts);
+        assert_eq!(balances.len(), 2);
+        assert_eq!(balances[&user("bruno")], cents(3000));
+        assert_eq!(balances[&user("carla")], cents(-1500));
+    }
+}
@@ -0,0 +1,371 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, MySqlPool};
+use std::collections::HashMap;
+use validator::Validate;
+
+use crate::middleware::workspace::Workspace;
+use crate::models::Transaction;
+
+const MAX_TAGS_PER_TRANSACTION: usize = 20;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Tag {
+    pub id: String,
+    pub workspace_id: String,
+    pub name: String,
+    pub transaction_count: i64,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct SaveTag {
+    #[validate(length(min = 1, max = 50))]
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MergeTag {
+    // Tag que recebe as transações; a tag da URL é excluída
+    pub into_tag_id: String,
+}
+
+// Nomes sem espaços nas pontas e sem repetição (ignorando maiúsculas)
+pub fn normalize_tags(names: &[String]) -> Result<Vec<String>, &'static str> {
+    let mut tags: Vec<String> = Vec::new();
+    for name in names {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > 50 {
+            return Err("Tag names must have 1 to 50 characters");
+        }
+        if !tags.iter().any(|tag| tag.to_lowercase() == name.to_lowercase()) {
+            tags.push(name.to_string());
+        }
+    }
+
+    if tags.len() > MAX_TAGS_PER_TRANSACTION {
+        return Err("A transaction can have at most 20 tags");
+    }
+    Ok(tags)
+}
+
+// Substitui as tags da transação, criando no espaço as que ainda não existem.
+// Roda na transação de quem grava a própria transação.
+pub async fn set_transaction_tags(
+    tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+    workspace_id: &str,
+    transaction_id: &str,
+    names: &[String],
+) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM transaction_tags WHERE transaction_id = ?")
+        .bind(transaction_id)
+        .execute(&mut **tx)
+        .await?;
+
+    for name in names {
+        sqlx::query("INSERT IGNORE INTO tags (id, workspace_id, name) VALUES (?, ?, ?)")
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(workspace_id)
+            .bind(name)
+            .execute(&mut **tx)
+            .await?;
+
+        sqlx::query(
+            "INSERT IGNORE INTO transaction_tags (transaction_id, tag_id)
+             SELECT ?, id FROM tags WHERE workspace_id = ? AND name = ?"
+        )
+        .bind(transaction_id)
+        .bind(workspace_id)
+        .bind(name)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(())
+}
+
+// Preenche `tags` de cada transação com os pares (transaction_id, nome)
+// carregados junto com a listagem
+pub fn attach_tags(transactions: &mut [Transaction], rows: Vec<(String, String)>) {
+    let mut tags: HashMap<String, Vec<String>> = HashMap::new();
+    for (transaction_id, name) in rows {
+        tags.entry(transaction_id).or_default().push(name);
+    }
+
+    for transaction in transactions.iter_mut() {
+        transaction.tags = tags.remove(&transaction.id).unwrap_or_default();
+    }
+}
+
+// Nomes das tags de uma transação
+pub async fn transaction_tags(
+    pool: &MySqlPool,
+    transaction_id: &str,
+) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>(
+        "SELECT g.name FROM transaction_tags tt
+         JOIN tags g ON g.id = tt.tag_id
+         WHERE tt.transaction_id = ?
+         ORDER BY g.name"
+    )
+    .bind(transaction_id)
+    .fetch_all(pool)
+    .await
+}
+
+fn database_error(e: sqlx::Error) -> HttpResponse {
+    eprintln!("Database error: {}", e);
+    HttpResponse::InternalServerError().json(serde_json::json!({
+        "error": "Database error"
+    }))
+}
+
+fn tag_not_found() -> HttpResponse {
+    HttpResponse::NotFound().json(serde_json::json!({
+        "error": "Tag not found"
+    }))
+}
+
+async fn find_tag(
+    pool: &MySqlPool,
+    workspace_id: &str,
+    tag_id: &str,
+) -> Result<Option<Tag>, sqlx::Error> {
+    sqlx::query_as::<_, Tag>(
+        "SELECT g.id, g.workspace_id, g.name,
+                (SELECT COUNT(*) FROM transaction_tags tt WHERE tt.tag_id = g.id) AS transaction_count,
+                g.created_at
+         FROM tags g
+         WHERE g.id = ? AND g.workspace_id = ?"
+    )
+    .bind(tag_id)
+    .bind(workspace_id)
+    .fetch_optional(pool)
+    .await
+}
+
+// GET /api/tags - Listar tags do espaço com o número de transações
+pub async fn get_all(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+) -> impl Responder {
+    let tags = sqlx::query_as::<_, Tag>(
+        "SELECT g.id, g.workspace_id, g.name, COUNT(tt.transaction_id) AS transaction_count, g.created_at
+         FROM tags g
+         LEFT JOIN transaction_tags tt ON tt.tag_id = g.id
+         WHERE g.workspace_id = ?
+         GROUP BY g.id, g.workspace_id, g.name, g.created_at
+         ORDER BY g.name ASC"
+    )
+    .bind(&workspace.id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match tags {
+        Ok(tags) => HttpResponse::Ok().json(tags),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to fetch tags"
+            }))
+        }
+    }
+}
+
+// POST /api/tags - Criar tag
+pub async fn create(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    tag_data: web::Json<SaveTag>,
+) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    if let Err(errors) = tag_data.validate() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Validation failed",
+            "details": errors.to_string()
+        }));
+    }
+
+    let name = tag_data.name.trim();
+    if name.is_empty() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Tag name cannot be blank"
+        }));
+    }
+
+    let tag_id = uuid::Uuid::new_v4().to_string();
+    let result = sqlx::query("INSERT INTO tags (id, workspace_id, name) VALUES (?, ?, ?)")
+        .bind(&tag_id)
+        .bind(&workspace.id)
+        .bind(name)
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(_) => match find_tag(pool.get_ref(), &workspace.id, &tag_id).await {
+            Ok(Some(tag)) => HttpResponse::Created().json(tag),
+            Ok(None) => tag_not_found(),
+            Err(e) => database_error(e),
+        },
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            HttpResponse::Conflict().json(serde_json::json!({
+                "error": "A tag with this name already exists"
+            }))
+        }
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to create tag"
+            }))
+        }
+    }
+}
+
+// PUT /api/tags/{id} - Renomear tag
+pub async fn update(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    tag_id: web::Path<String>,
+    tag_data: web::Json<SaveTag>,
+) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    if let Err(errors) = tag_data.validate() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Validation failed",
+            "details": errors.to_string()
+        }));
+    }
+
+    let name = tag_data.name.trim();
+    if name.is_empty() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Tag name cannot be blank"
+        }));
+    }
+
+    let tag_id = tag_id.into_inner();
+    let result = sqlx::query("UPDATE tags SET name = ? WHERE id = ? AND workspace_id = ?")
+        .bind(name)
+        .bind(&tag_id)
+        .bind(&workspace.id)
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(_) => match find_tag(pool.get_ref(), &workspace.id, &tag_id).await {
+            Ok(Some(tag)) => HttpResponse::Ok().json(tag),
+            Ok(None) => tag_not_found(),
+            Err(e) => database_error(e),
+        },
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            HttpResponse::Conflict().json(serde_json::json!({
+                "error": "A tag with this name already exists; merge the tags instead"
+            }))
+        }
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to update tag"
+            }))
+        }
+    }
+}
+
+// POST /api/tags/{id}/merge - Juntar a tag em outra: as transações passam
+// para `into_tag_id` e a tag da URL é excluída
+pub async fn merge(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    tag_id: web::Path<String>,
+    merge_data: web::Json<MergeTag>,
+) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    let tag_id = tag_id.into_inner();
+    if tag_id == merge_data.into_tag_id {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Cannot merge a tag into itself"
+        }));
+    }
+
+    for id in [&tag_id, &merge_data.into_tag_id] {
+        match find_tag(pool.get_ref(), &workspace.id, id).await {
+            Ok(Some(_)) => {}
+            Ok(None) => return tag_not_found(),
+            Err(e) => return database_error(e),
+        }
+    }
+
+    let result = async {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            "INSERT IGNORE INTO transaction_tags (transaction_id, tag_id)
+             SELECT transaction_id, ? FROM transaction_tags WHERE tag_id = ?"
+        )
+        .bind(&merge_data.into_tag_id)
+        .bind(&tag_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM tags WHERE id = ?")
+            .bind(&tag_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await
+    }
+    .await;
+
+    match result {
+        Ok(_) => match find_tag(pool.get_ref(), &workspace.id, &merge_data.into_tag_id).await {
+            Ok(Some(tag)) => HttpResponse::Ok().json(tag),
+            Ok(None) => tag_not_found(),
+            Err(e) => database_error(e),
+        },
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to merge tags"
+            }))
+        }
+    }
+}
+
+// DELETE /api/tags/{id} - Excluir tag (as transações são mantidas)
+pub async fn delete(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    tag_id: web::Path<String>,
+) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    let result = sqlx::query("DELETE FROM tags WHERE id = ? AND workspace_id = ?")
+        .bind(tag_id.into_inner())
+        .bind(&workspace.id)
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(result) if result.rows_affected() > 0 => HttpResponse::Ok().json(serde_json::json!({
+            "message": "Tag deleted successfully"
+        })),
+        Ok(_) => tag_not_found(),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to delete tag"
+            }))
+        }
+    }
+}
@@ -1,29 +1,177 @@
 use actix_web::{web, HttpResponse, Responder};
 use chrono::Utc;
 use rust_decimal::Decimal;
-use serde::Deserialize;
-use sqlx::MySqlPool;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, MySqlPool};
+use validator::Validate;
 
+use crate::handlers::categories::category_exists;
+use crate::handlers::{accounts, autosave, budgets, tags};
+use crate::middleware::workspace::Workspace;
 use crate::models::{CreateTransaction, Transaction};
 
 #[derive(Debug, Deserialize)]
+pub struct TransactionFilters {
+    // Nome da tag
+    pub tag: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
 pub struct UpdateTransaction {
     pub description: Option<String>,
+    // Texto vazio apaga as observações
+    #[validate(length(max = 5000))]
+    pub notes: Option<String>,
     pub amount: Option<f64>,
     pub transaction_type: Option<String>,
     pub category_id: Option<String>,
+    pub account_id: Option<String>,
+    // Substitui as tags da transação
+    pub tags: Option<Vec<String>>,
+}
+
+// Linha de uma transação dividida entre categorias
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct TransactionLine {
+    pub id: String,
+    pub transaction_id: String,
+    pub category_id: Option<String>,
+    pub amount: Decimal,
+    pub memo: Option<String>,
+    pub position: u32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct SaveLine {
+    pub category_id: Option<String>,
+    pub amount: f64,
+    #[validate(length(max = 255))]
+    pub memo: Option<String>,
 }
 
-// GET /api/transactions - Listar todas
+#[derive(Debug, Deserialize, Validate)]
+pub struct SaveLines {
+    #[validate(length(min = 2, max = 50))]
+    #[validate]
+    pub lines: Vec<SaveLine>,
+}
+
+// Verifica se a conta informada pertence ao espaço de trabalho
+async fn check_account(
+    pool: &MySqlPool,
+    workspace_id: &str,
+    account_id: &Option<String>,
+) -> Result<(), HttpResponse> {
+    let Some(account_id) = account_id else {
+        return Ok(());
+    };
+
+    match accounts::account_exists(pool, workspace_id, account_id).await {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Account not found"
+        }))),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            Err(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            })))
+        }
+    }
+}
+
+// Dispara os avisos de orçamento de cada categoria da despesa (a da
+// transação ou as das linhas, quando ela está dividida)
+pub async fn check_budget_alerts(pool: &MySqlPool, transaction: &Transaction) {
+    if transaction.transaction_type != "expense" {
+        return;
+    }
+
+    let categories = sqlx::query_scalar::<_, String>(
+        "SELECT DISTINCT category_id FROM transaction_lines
+         WHERE transaction_id = ? AND category_id IS NOT NULL"
+    )
+    .bind(&transaction.id)
+    .fetch_all(pool)
+    .await;
+
+    let categories = match categories {
+        Ok(categories) => categories,
+        Err(e) => {
+            eprintln!("Failed to check budget alerts: {}", e);
+            return;
+        }
+    };
+
+    for category_id in &categories {
+        if let Err(e) = budgets::check_alerts(
+            pool,
+            &transaction.workspace_id,
+            category_id,
+            transaction.date.date_naive(),
+        )
+        .await
+        {
+            eprintln!("Failed to check budget alerts: {}", e);
+        }
+    }
+}
+
+async fn load_lines(
+    pool: &MySqlPool,
+    transaction_id: &str,
+) -> Result<Vec<TransactionLine>, sqlx::Error> {
+    sqlx::query_as::<_, TransactionLine>(
+        "SELECT * FROM transaction_splits WHERE transaction_id = ? ORDER BY position"
+    )
+    .bind(transaction_id)
+    .fetch_all(pool)
+    .await
+}
+
+// Filtro da listagem de transações: espaço de trabalho e, opcionalmente, uma
+// tag. Parâmetros: workspace_id, tag, tag.
+const LIST_FILTER: &str = "t.workspace_id = ? AND (? IS NULL OR EXISTS (
+    SELECT 1 FROM transaction_tags ft JOIN tags fg ON fg.id = ft.tag_id
+    WHERE ft.transaction_id = t.id AND fg.name = ?
+))";
+
+// GET /api/transactions?tag= - Listar todas (opcionalmente só as de uma tag)
 pub async fn get_all(
     pool: web::Data<MySqlPool>,
-    user_id: web::ReqData<String>,
+    workspace: Workspace,
+    filters: web::Query<TransactionFilters>,
 ) -> impl Responder {
-    let transactions = sqlx::query_as::<_, Transaction>(
-        "SELECT * FROM transactions WHERE user_id = ? ORDER BY date DESC"
-    )
-    .bind(user_id.into_inner())
-    .fetch_all(pool.get_ref())
+    let tag = filters.tag.as_deref().map(str::trim);
+
+    let transactions = async {
+        let mut transactions = sqlx::query_as::<_, Transaction>(&format!(
+            "SELECT * FROM transactions t WHERE {LIST_FILTER} ORDER BY t.date DESC"
+        ))
+        .bind(&workspace.id)
+        .bind(tag)
+        .bind(tag)
+        .fetch_all(pool.get_ref())
+        .await?;
+
+        // Só as tags das transações listadas, com o mesmo filtro da listagem
+        let tag_rows = sqlx::query_as::<_, (String, String)>(&format!(
+            "SELECT tt.transaction_id, g.name FROM transaction_tags tt
+             JOIN tags g ON g.id = tt.tag_id
+             JOIN transactions t ON t.id = tt.transaction_id
+             WHERE {LIST_FILTER}
+             ORDER BY g.name"
+        ))
+        .bind(&workspace.id)
+        .bind(tag)
+        .bind(tag)
+        .fetch_all(pool.get_ref())
+        .await?;
+
+        tags::attach_tags(&mut transactions, tag_rows);
+        Ok::<_, sqlx::Error>(transactions)
+    }
     .await;
 
     match transactions {
@@ -40,15 +188,24 @@ pub async fn get_all(
 // GET /api/transactions/{id} - Buscar por ID
 pub async fn get_by_id(
     pool: web::Data<MySqlPool>,
-    user_id: web::ReqData<String>,
+    workspace: Workspace,
     transaction_id: web::Path<String>,
 ) -> impl Responder {
-    let transaction = sqlx::query_as::<_, Transaction>(
-        "SELECT * FROM transactions WHERE id = ? AND user_id = ?"
-    )
-    .bind(transaction_id.into_inner())
-    .bind(user_id.into_inner())
-    .fetch_optional(pool.get_ref())
+    let transaction = async {
+        let transaction = sqlx::query_as::<_, Transaction>(
+            "SELECT * FROM transactions WHERE id = ? AND workspace_id = ?"
+        )
+        .bind(transaction_id.into_inner())
+        .bind(&workspace.id)
+        .fetch_optional(pool.get_ref())
+        .await?;
+
+        let Some(mut transaction) = transaction else {
+            return Ok(None);
+        };
+        transaction.tags = tags::transaction_tags(pool.get_ref(), &transaction.id).await?;
+        Ok::<_, sqlx::Error>(Some(transaction))
+    }
     .await;
 
     match transaction {
@@ -68,9 +225,29 @@ pub async fn get_by_id(
 // POST /api/transactions - Criar nova
 pub async fn create(
     pool: web::Data<MySqlPool>,
-    user_id: web::ReqData<String>,
+    workspace: Workspace,
     transaction_data: web::Json<CreateTransaction>,
 ) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    if let Err(errors) = transaction_data.validate() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Validation failed",
+            "details": errors.to_string()
+        }));
+    }
+
+    let tag_names = match tags::normalize_tags(transaction_data.tags.as_deref().unwrap_or_default()) {
+        Ok(names) => names,
+        Err(error) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": error
+            }));
+        }
+    };
+
     let amount = Decimal::from_f64_retain(transaction_data.amount);
     if amount.is_none() || transaction_data.amount == 0.0 {
         return HttpResponse::BadRequest().json(serde_json::json!({
@@ -78,35 +255,58 @@ pub async fn create(
         }));
     }
 
+    if let Err(response) = check_account(pool.get_ref(), &workspace.id, &transaction_data.account_id).await {
+        return response;
+    }
+
     let transaction_id = uuid::Uuid::new_v4().to_string();
     let date = transaction_data.date
         .map(|d| d.and_hms_opt(0, 0, 0).unwrap())
         .unwrap_or_else(|| Utc::now().naive_utc());
 
-    let result = sqlx::query(
-        "INSERT INTO transactions (id, user_id, description, amount, type, category_id, date, recurring)
-         VALUES (?, ?, ?, ?, ?, ?, ?, FALSE)"
-    )
-    .bind(&transaction_id)
-    .bind(user_id.into_inner())
-    .bind(&transaction_data.description)
-    .bind(amount.unwrap())
-    .bind(&transaction_data.transaction_type)
-    .bind(&transaction_data.category_id)
-    .bind(date)
-    .execute(pool.get_ref())
+    // A transação e as tags são gravadas juntas
+    let result = async {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            "INSERT INTO transactions (id, workspace_id, user_id, description, notes, amount, type, category_id, account_id, date, recurring)
+             VALUES (?, ?, ?, ?, NULLIF(?, ''), ?, ?, ?, ?, ?, FALSE)"
+        )
+        .bind(&transaction_id)
+        .bind(&workspace.id)
+        .bind(&workspace.user_id)
+        .bind(&transaction_data.description)
+        .bind(&transaction_data.notes)
+        .bind(amount.unwrap())
+        .bind(&transaction_data.transaction_type)
+        .bind(&transaction_data.category_id)
+        .bind(&transaction_data.account_id)
+        .bind(date)
+        .execute(&mut *tx)
+        .await?;
+
+        tags::set_transaction_tags(&mut tx, &workspace.id, &transaction_id, &tag_names).await?;
+
+        tx.commit().await
+    }
     .await;
 
     match result {
         Ok(_) => {
-            let transaction = sqlx::query_as::<_, Transaction>(
+            let mut transaction = sqlx::query_as::<_, Transaction>(
                 "SELECT * FROM transactions WHERE id = ?"
             )
             .bind(&transaction_id)
             .fetch_one(pool.get_ref())
             .await
             .unwrap();
-            
+            transaction.tags = tag_names;
+
+            check_budget_alerts(pool.get_ref(), &transaction).await;
+            if let Err(e) = autosave::apply_rules(pool.get_ref(), &transaction).await {
+                eprintln!("Failed to apply auto-save rules: {}", e);
+            }
+
             HttpResponse::Created().json(transaction)
         }
         Err(e) => {
@@ -118,22 +318,53 @@ pub async fn create(
     }
 }
 
+// Resultado da atualização feita dentro da transação do banco
+enum UpdateOutcome {
+    Updated,
+    NotFound,
+    // A alteração deixaria a transação em desacordo com as linhas ou a divisão
+    Rejected(&'static str),
+}
+
 // PUT /api/transactions/{id} - Atualizar
 pub async fn update(
     pool: web::Data<MySqlPool>,
-    user_id: web::ReqData<String>,
+    workspace: Workspace,
     transaction_id: web::Path<String>,
     update_data: web::Json<UpdateTransaction>,
 ) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    if let Err(errors) = update_data.validate() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Validation failed",
+            "details": errors.to_string()
+        }));
+    }
+
+    let tag_names = match update_data.tags.as_deref().map(tags::normalize_tags).transpose() {
+        Ok(names) => names,
+        Err(error) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": error
+            }));
+        }
+    };
+
     let mut updates = Vec::new();
 
     if let Some(desc) = &update_data.description {
         updates.push(format!("description = '{}'", desc));
     }
-    if let Some(amount) = update_data.amount {
-        if let Some(decimal) = Decimal::from_f64_retain(amount) {
-            updates.push(format!("amount = {}", decimal));
-        }
+    // Observações são texto livre: vão como parâmetro em vez de no SQL
+    if update_data.notes.is_some() {
+        updates.push("notes = NULLIF(?, '')".to_string());
+    }
+    let new_amount = update_data.amount.and_then(Decimal::from_f64_retain);
+    if let Some(decimal) = new_amount {
+        updates.push(format!("amount = {}", decimal));
     }
     if let Some(t_type) = &update_data.transaction_type {
         updates.push(format!("type = '{}'", t_type));
@@ -142,30 +373,130 @@ pub async fn update(
         updates.push(format!("category_id = '{}'", cat_id));
     }
 
-    if updates.is_empty() {
+    if let Err(response) = check_account(pool.get_ref(), &workspace.id, &update_data.account_id).await {
+        return response;
+    }
+    if update_data.account_id.is_some() {
+        updates.push("account_id = ?".to_string());
+    }
+
+    if updates.is_empty() && tag_names.is_none() {
         return HttpResponse::BadRequest().json(serde_json::json!({
             "error": "No fields to update"
         }));
     }
 
-    let query = format!(
-        "UPDATE transactions SET {} WHERE id = '{}' AND user_id = '{}'",
-        updates.join(", "),
-        transaction_id.into_inner(),
-        user_id.into_inner()
-    );
+    let transaction_id = transaction_id.into_inner();
+
+    // As conferências com as linhas rodam com a transação bloqueada, na mesma
+    // transação do banco que grava a alteração
+    let result = async {
+        let mut tx = pool.begin().await?;
 
-    let result = sqlx::query(&query).execute(pool.get_ref()).await;
+        let found = sqlx::query_scalar::<_, String>(
+            "SELECT id FROM transactions WHERE id = ? AND workspace_id = ? FOR UPDATE"
+        )
+        .bind(&transaction_id)
+        .bind(&workspace.id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if found.is_none() {
+            return Ok(UpdateOutcome::NotFound);
+        }
+
+        // Despesas divididas com outras pessoas precisam manter valor e tipo
+        if new_amount.is_some() || update_data.transaction_type.is_some() {
+            let split_amount = sqlx::query_scalar::<_, Decimal>(
+                "SELECT t.amount FROM expense_splits s
+                 JOIN transactions t ON t.id = s.transaction_id
+                 WHERE s.transaction_id = ?"
+            )
+            .bind(&transaction_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            if let Some(amount) = split_amount {
+                let amount_changed = new_amount.is_some_and(|new_amount| new_amount.round_dp(2) != amount);
+                let type_changed = update_data
+                    .transaction_type
+                    .as_deref()
+                    .is_some_and(|t_type| t_type != "expense");
+
+                if amount_changed || type_changed {
+                    return Ok(UpdateOutcome::Rejected(
+                        "This expense is split with other people; update or delete the split first"
+                    ));
+                }
+            }
+        }
+
+        // Transações divididas precisam continuar fechando com as linhas
+        if let Some(decimal) = new_amount {
+            let lines_total = sqlx::query_scalar::<_, Option<Decimal>>(
+                "SELECT SUM(amount) FROM transaction_splits WHERE transaction_id = ?"
+            )
+            .bind(&transaction_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            if lines_total.is_some_and(|total| total != decimal.round_dp(2)) {
+                return Ok(UpdateOutcome::Rejected(
+                    "Amount must match the sum of the split lines; update or delete the lines first"
+                ));
+            }
+        }
+
+        if !updates.is_empty() {
+            let query = format!(
+                "UPDATE transactions SET {} WHERE id = '{}' AND workspace_id = '{}'",
+                updates.join(", "),
+                transaction_id,
+                workspace.id
+            );
+
+            let mut query = sqlx::query(&query);
+            if let Some(notes) = &update_data.notes {
+                query = query.bind(notes);
+            }
+            if let Some(account_id) = &update_data.account_id {
+                query = query.bind(account_id);
+            }
+            query.execute(&mut *tx).await?;
+        }
+
+        if let Some(names) = &tag_names {
+            tags::set_transaction_tags(&mut tx, &workspace.id, &transaction_id, names).await?;
+        }
+
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(UpdateOutcome::Updated)
+    }
+    .await;
 
     match result {
-        Ok(result) if result.rows_affected() > 0 => {
+        Ok(UpdateOutcome::Updated) => {
+            let transaction = sqlx::query_as::<_, Transaction>(
+                "SELECT * FROM transactions WHERE id = ?"
+            )
+            .bind(&transaction_id)
+            .fetch_optional(pool.get_ref())
+            .await;
+
+            if let Ok(Some(transaction)) = transaction {
+                check_budget_alerts(pool.get_ref(), &transaction).await;
+            }
+
             HttpResponse::Ok().json(serde_json::json!({
                 "message": "Transaction updated successfully"
             }))
         }
-        Ok(_) => HttpResponse::NotFound().json(serde_json::json!({
+        Ok(UpdateOutcome::NotFound) => HttpResponse::NotFound().json(serde_json::json!({
             "error": "Transaction not found"
         })),
+        Ok(UpdateOutcome::Rejected(error)) => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": error
+        })),
         Err(e) => {
             eprintln!("Database error: {}", e);
             HttpResponse::InternalServerError().json(serde_json::json!({
@@ -178,30 +509,280 @@ pub async fn update(
 // DELETE /api/transactions/{id} - Deletar
 pub async fn delete(
     pool: web::Data<MySqlPool>,
-    user_id: web::ReqData<String>,
+    workspace: Workspace,
+    transaction_id: web::Path<String>,
+) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    let transaction_id = transaction_id.into_inner();
+
+    let result = async {
+        let mut tx = pool.begin().await?;
+
+        let exists = sqlx::query_scalar::<_, String>(
+            "SELECT id FROM transactions WHERE id = ? AND workspace_id = ? FOR UPDATE"
+        )
+        .bind(&transaction_id)
+        .bind(&workspace.id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if exists.is_none() {
+            return Ok(false);
+        }
+
+        autosave::revert_contributions(&mut tx, &transaction_id).await?;
+
+        sqlx::query("DELETE FROM transactions WHERE id = ?")
+            .bind(&transaction_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(true)
+    }
+    .await;
+
+    match result {
+        Ok(true) => {
+            HttpResponse::Ok().json(serde_json::json!({
+                "message": "Transaction deleted successfully"
+            }))
+        }
+        Ok(false) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Transaction not found"
+        })),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to delete transaction"
+            }))
+        }
+    }
+}
+
+// GET /api/transactions/{id}/lines - Linhas da transação dividida entre categorias
+pub async fn get_lines(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    transaction_id: web::Path<String>,
+) -> impl Responder {
+    let transaction = sqlx::query_as::<_, Transaction>(
+        "SELECT * FROM transactions WHERE id = ? AND workspace_id = ?"
+    )
+    .bind(transaction_id.into_inner())
+    .bind(&workspace.id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    let transaction = match transaction {
+        Ok(Some(transaction)) => transaction,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Transaction not found"
+            }));
+        }
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            }));
+        }
+    };
+
+    match load_lines(pool.get_ref(), &transaction.id).await {
+        Ok(lines) => HttpResponse::Ok().json(lines),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to fetch split lines"
+            }))
+        }
+    }
+}
+
+// PUT /api/transactions/{id}/lines - Dividir a transação entre categorias
+// (substitui as linhas anteriores; a soma precisa ser o valor da transação)
+pub async fn save_lines(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
+    transaction_id: web::Path<String>,
+    lines_data: web::Json<SaveLines>,
+) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
+    if let Err(errors) = lines_data.validate() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Validation failed",
+            "details": errors.to_string()
+        }));
+    }
+
+    let transaction = sqlx::query_as::<_, Transaction>(
+        "SELECT * FROM transactions WHERE id = ? AND workspace_id = ?"
+    )
+    .bind(transaction_id.into_inner())
+    .bind(&workspace.id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    let transaction = match transaction {
+        Ok(Some(transaction)) => transaction,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Transaction not found"
+            }));
+        }
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            }));
+        }
+    };
+
+    let mut amounts = Vec::with_capacity(lines_data.lines.len());
+    for line in &lines_data.lines {
+        match Decimal::from_f64_retain(line.amount).map(|amount| amount.round_dp(2)) {
+            Some(amount) if amount > Decimal::ZERO => amounts.push(amount),
+            _ => {
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": "Each line needs a positive amount"
+                }));
+            }
+        }
+    }
+
+    if amounts.iter().sum::<Decimal>() != transaction.amount {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Split lines must add up to the transaction amount",
+            "transaction_amount": transaction.amount
+        }));
+    }
+
+    for category_id in lines_data.lines.iter().filter_map(|line| line.category_id.as_ref()) {
+        match category_exists(pool.get_ref(), &workspace.id, category_id).await {
+            Ok(true) => {}
+            Ok(false) => {
+                return HttpResponse::NotFound().json(serde_json::json!({
+                    "error": "Category not found"
+                }));
+            }
+            Err(e) => {
+                eprintln!("Database error: {}", e);
+                return HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": "Database error"
+                }));
+            }
+        }
+    }
+
+    // O valor é conferido de novo com a transação bloqueada, caso ela tenha
+    // mudado desde a leitura acima
+    let result = async {
+        let mut tx = pool.begin().await?;
+
+        let locked_amount = sqlx::query_scalar::<_, Decimal>(
+            "SELECT amount FROM transactions WHERE id = ? FOR UPDATE"
+        )
+        .bind(&transaction.id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if locked_amount != transaction.amount {
+            return Ok(false);
+        }
+
+        sqlx::query("DELETE FROM transaction_splits WHERE transaction_id = ?")
+            .bind(&transaction.id)
+            .execute(&mut *tx)
+            .await?;
+
+        for (position, (line, amount)) in lines_data.lines.iter().zip(&amounts).enumerate() {
+            sqlx::query(
+                "INSERT INTO transaction_splits (id, transaction_id, category_id, amount, memo, position)
+                 VALUES (?, ?, ?, ?, ?, ?)"
+            )
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(&transaction.id)
+            .bind(&line.category_id)
+            .bind(amount)
+            .bind(&line.memo)
+            .bind(position as u32)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(true)
+    }
+    .await;
+
+    match result {
+        Ok(true) => {}
+        Ok(false) => {
+            return HttpResponse::Conflict().json(serde_json::json!({
+                "error": "The transaction amount changed; send the lines again"
+            }));
+        }
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to split transaction"
+            }));
+        }
+    }
+
+    check_budget_alerts(pool.get_ref(), &transaction).await;
+
+    match load_lines(pool.get_ref(), &transaction.id).await {
+        Ok(lines) => HttpResponse::Ok().json(lines),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to fetch split lines"
+            }))
+        }
+    }
+}
+
+// DELETE /api/transactions/{id}/lines - Juntar as linhas de volta (a transação
+// volta a contar inteira na própria categoria)
+pub async fn delete_lines(
+    pool: web::Data<MySqlPool>,
+    workspace: Workspace,
     transaction_id: web::Path<String>,
 ) -> impl Responder {
+    if let Err(response) = workspace.require_editor() {
+        return response;
+    }
+
     let result = sqlx::query(
-        "DELETE FROM transactions WHERE id = ? AND user_id = ?"
+        "DELETE s FROM transaction_splits s
+         JOIN transactions t ON t.id = s.transaction_id
+         WHERE s.transaction_id = ? AND t.workspace_id = ?"
     )
     .bind(transaction_id.into_inner())
-    .bind(user_id.into_inner())
+    .bind(&workspace.id)
     .execute(pool.get_ref())
     .await;
 
     match result {
         Ok(result) if result.rows_affected() > 0 => {
             HttpResponse::Ok().json(serde_json::json!({
-                "message": "Transaction deleted successfully"
+                "message": "Split lines deleted successfully"
             }))
         }
         Ok(_) => HttpResponse::NotFound().json(serde_json::json!({
-            "error": "Transaction not found"
+            "error": "This transaction has no split lines"
         })),
         Err(e) => {
             eprintln!("Database error: {}", e);
             HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Failed to delete transaction"
+                "error": "Failed to delete split lines"
             }))
         }
     }
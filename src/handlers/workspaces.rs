@@ -0,0 +1,809 @@
+use actix_web::{web, HttpResponse, Responder};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, MySqlPool};
+use validator::Validate;
+
+use crate::handlers::notifications::notify;
+use crate::middleware::workspace::{ensure_personal_workspace, member_role, WorkspaceRole};
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct WorkspaceDetails {
+    pub id: String,
+    pub name: String,
+    pub personal: bool,
+    pub created_by: Option<String>,
+    pub envelope_start: Option<NaiveDate>,
+    pub role: String,
+    pub member_count: i64,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct WorkspaceMember {
+    pub user_id: String,
+    pub full_name: String,
+    pub email: String,
+    pub role: String,
+    pub joined_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct WorkspaceInvitation {
+    pub id: String,
+    pub workspace_id: String,
+    pub workspace_name: String,
+    pub email: String,
+    pub role: String,
+    pub status: String,
+    pub invited_by: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub responded_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct SaveWorkspace {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateMemberRole {
+    pub role: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateInvitation {
+    #[validate(email)]
+    pub email: String,
+    pub role: Option<String>,
+}
+
+const INVITATION_COLUMNS: &str =
+    "i.id, i.workspace_id, w.name AS workspace_name, i.email, i.role, i.status,
+     i.invited_by, i.created_at, i.responded_at";
+
+fn database_error(e: sqlx::Error) -> HttpResponse {
+    eprintln!("Database error: {}", e);
+    HttpResponse::InternalServerError().json(serde_json::json!({
+        "error": "Database error"
+    }))
+}
+
+fn workspace_not_found() -> HttpResponse {
+    HttpResponse::NotFound().json(serde_json::json!({
+        "error": "Workspace not found"
+    }))
+}
+
+fn owner_only() -> HttpResponse {
+    HttpResponse::Forbidden().json(serde_json::json!({
+        "error": "Only workspace owners can do this"
+    }))
+}
+
+fn parse_role(role: &str) -> Result<WorkspaceRole, HttpResponse> {
+    WorkspaceRole::parse(role).ok_or_else(|| {
+        HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "role must be one of: owner, editor, viewer"
+        }))
+    })
+}
+
+// Ids dos membros do espaço de trabalho (destinatários dos avisos)
+pub async fn member_ids(pool: &MySqlPool, workspace_id: &str) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>("SELECT user_id FROM workspace_members WHERE workspace_id = ?")
+        .bind(workspace_id)
+        .fetch_all(pool)
+        .await
+}
+
+// Papel do usuário no espaço da rota; 404 quando ele não é membro
+async fn membership(
+    pool: &MySqlPool,
+    workspace_id: &str,
+    user_id: &str,
+) -> Result<WorkspaceRole, HttpResponse> {
+    match member_role(pool, workspace_id, user_id).await {
+        Ok(Some(role)) => Ok(role),
+        Ok(None) => Err(workspace_not_found()),
+        Err(e) => Err(database_error(e)),
+    }
+}
+
+async fn find_workspace(
+    pool: &MySqlPool,
+    workspace_id: &str,
+    user_id: &str,
+) -> Result<Option<WorkspaceDetails>, sqlx::Error> {
+    sqlx::query_as::<_, WorkspaceDetails>(
+        "SELECT w.id, w.name, w.personal, w.created_by, w.envelope_start, m.role,
+                (SELECT COUNT(*) FROM workspace_members c WHERE c.workspace_id = w.id) AS member_count,
+                w.created_at, w.updated_at
+         FROM workspaces w
+         JOIN workspace_members m ON m.workspace_id = w.id AND m.user_id = ?
+         WHERE w.id = ?"
+    )
+    .bind(user_id)
+    .bind(workspace_id)
+    .fetch_optional(pool)
+    .await
+}
+
+enum MemberChange {
+    Done,
+    NotFound,
+    LastOwner,
+}
+
+// Trava os donos do espaço (FOR UPDATE) e devolve o papel atual do membro,
+// para que a contagem de donos e a escrita seguinte não corram em paralelo.
+async fn locked_member(
+    tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+    workspace_id: &str,
+    member_id: &str,
+) -> Result<(Option<WorkspaceRole>, usize), sqlx::Error> {
+    let owners = sqlx::query_scalar::<_, String>(
+        "SELECT user_id FROM workspace_members WHERE workspace_id = ? AND role = 'owner' FOR UPDATE"
+    )
+    .bind(workspace_id)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    let role = sqlx::query_scalar::<_, String>(
+        "SELECT role FROM workspace_members WHERE workspace_id = ? AND user_id = ? FOR UPDATE"
+    )
+    .bind(workspace_id)
+    .bind(member_id)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    Ok((role.as_deref().and_then(WorkspaceRole::parse), owners.len()))
+}
+
+fn last_owner() -> HttpResponse {
+    HttpResponse::BadRequest().json(serde_json::json!({
+        "error": "A workspace must keep at least one owner"
+    }))
+}
+
+fn member_not_found() -> HttpResponse {
+    HttpResponse::NotFound().json(serde_json::json!({
+        "error": "Member not found"
+    }))
+}
+
+// GET /api/workspaces - Espaços de trabalho do usuário
+pub async fn get_all(
+    pool: web::Data<MySqlPool>,
+    user_id: web::ReqData<String>,
+) -> impl Responder {
+    let uid = user_id.into_inner();
+
+    if let Err(e) = ensure_personal_workspace(pool.get_ref(), &uid).await {
+        return database_error(e);
+    }
+
+    let workspaces = sqlx::query_as::<_, WorkspaceDetails>(
+        "SELECT w.id, w.name, w.personal, w.created_by, w.envelope_start, m.role,
+                (SELECT COUNT(*) FROM workspace_members c WHERE c.workspace_id = w.id) AS member_count,
+                w.created_at, w.updated_at
+         FROM workspaces w
+         JOIN workspace_members m ON m.workspace_id = w.id
+         WHERE m.user_id = ?
+         ORDER BY w.personal DESC, w.name ASC"
+    )
+    .bind(&uid)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match workspaces {
+        Ok(workspaces) => HttpResponse::Ok().json(workspaces),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to fetch workspaces"
+            }))
+        }
+    }
+}
+
+// POST /api/workspaces - Criar espaço compartilhado (o criador vira owner)
+pub async fn create(
+    pool: web::Data<MySqlPool>,
+    user_id: web::ReqData<String>,
+    workspace_data: web::Json<SaveWorkspace>,
+) -> impl Responder {
+    if let Err(errors) = workspace_data.validate() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Validation failed",
+            "details": errors.to_string()
+        }));
+    }
+
+    let uid = user_id.into_inner();
+    let workspace_id = uuid::Uuid::new_v4().to_string();
+
+    let result = async {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("INSERT INTO workspaces (id, name, personal, created_by) VALUES (?, ?, FALSE, ?)")
+            .bind(&workspace_id)
+            .bind(workspace_data.name.trim())
+            .bind(&uid)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            "INSERT INTO workspace_members (workspace_id, user_id, role) VALUES (?, ?, 'owner')"
+        )
+        .bind(&workspace_id)
+        .bind(&uid)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await
+    }
+    .await;
+
+    if let Err(e) = result {
+        eprintln!("Database error: {}", e);
+        return HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to create workspace"
+        }));
+    }
+
+    match find_workspace(pool.get_ref(), &workspace_id, &uid).await {
+        Ok(Some(workspace)) => HttpResponse::Created().json(workspace),
+        Ok(None) => workspace_not_found(),
+        Err(e) => database_error(e),
+    }
+}
+
+// PUT /api/workspaces/{id} - Renomear espaço (somente owners)
+pub async fn update(
+    pool: web::Data<MySqlPool>,
+    user_id: web::ReqData<String>,
+    workspace_id: web::Path<String>,
+    workspace_data: web::Json<SaveWorkspace>,
+) -> impl Responder {
+    if let Err(errors) = workspace_data.validate() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Validation failed",
+            "details": errors.to_string()
+        }));
+    }
+
+    let uid = user_id.into_inner();
+    let workspace_id = workspace_id.into_inner();
+
+    match membership(pool.get_ref(), &workspace_id, &uid).await {
+        Ok(WorkspaceRole::Owner) => {}
+        Ok(_) => return owner_only(),
+        Err(response) => return response,
+    }
+
+    let result = sqlx::query("UPDATE workspaces SET name = ? WHERE id = ?")
+        .bind(workspace_data.name.trim())
+        .bind(&workspace_id)
+        .execute(pool.get_ref())
+        .await;
+
+    if let Err(e) = result {
+        eprintln!("Database error: {}", e);
+        return HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to update workspace"
+        }));
+    }
+
+    match find_workspace(pool.get_ref(), &workspace_id, &uid).await {
+        Ok(Some(workspace)) => HttpResponse::Ok().json(workspace),
+        Ok(None) => workspace_not_found(),
+        Err(e) => database_error(e),
+    }
+}
+
+// DELETE /api/workspaces/{id} - Excluir espaço compartilhado e todos os seus dados
+pub async fn delete(
+    pool: web::Data<MySqlPool>,
+    user_id: web::ReqData<String>,
+    workspace_id: web::Path<String>,
+) -> impl Responder {
+    let uid = user_id.into_inner();
+    let workspace_id = workspace_id.into_inner();
+
+    let workspace = match find_workspace(pool.get_ref(), &workspace_id, &uid).await {
+        Ok(Some(workspace)) => workspace,
+        Ok(None) => return workspace_not_found(),
+        Err(e) => return database_error(e),
+    };
+
+    if workspace.role != WorkspaceRole::Owner.as_str() {
+        return owner_only();
+    }
+    if workspace.personal {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "The personal workspace cannot be deleted"
+        }));
+    }
+
+    let result = sqlx::query("DELETE FROM workspaces WHERE id = ? AND personal = FALSE")
+        .bind(&workspace_id)
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(_) => HttpResponse::Ok().json(serde_json::json!({
+            "message": "Workspace deleted successfully"
+        })),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to delete workspace"
+            }))
+        }
+    }
+}
+
+// GET /api/workspaces/{id}/members - Membros do espaço
+pub async fn get_members(
+    pool: web::Data<MySqlPool>,
+    user_id: web::ReqData<String>,
+    workspace_id: web::Path<String>,
+) -> impl Responder {
+    let workspace_id = workspace_id.into_inner();
+
+    if let Err(response) = membership(pool.get_ref(), &workspace_id, &user_id).await {
+        return response;
+    }
+
+    let members = sqlx::query_as::<_, WorkspaceMember>(
+        "SELECT m.user_id, u.full_name, u.email, m.role, m.created_at AS joined_at
+         FROM workspace_members m
+         JOIN users u ON u.id = m.user_id
+         WHERE m.workspace_id = ?
+         ORDER BY FIELD(m.role, 'owner', 'editor', 'viewer'), u.full_name ASC"
+    )
+    .bind(&workspace_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match members {
+        Ok(members) => HttpResponse::Ok().json(members),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to fetch workspace members"
+            }))
+        }
+    }
+}
+
+// PUT /api/workspaces/{id}/members/{user_id} - Alterar papel de um membro (somente owners)
+pub async fn update_member(
+    pool: web::Data<MySqlPool>,
+    user_id: web::ReqData<String>,
+    path: web::Path<(String, String)>,
+    role_data: web::Json<UpdateMemberRole>,
+) -> impl Responder {
+    let (workspace_id, member_id) = path.into_inner();
+
+    match membership(pool.get_ref(), &workspace_id, &user_id).await {
+        Ok(WorkspaceRole::Owner) => {}
+        Ok(_) => return owner_only(),
+        Err(response) => return response,
+    }
+
+    let role = match parse_role(&role_data.role) {
+        Ok(role) => role,
+        Err(response) => return response,
+    };
+
+    let result = async {
+        let mut tx = pool.begin().await?;
+
+        let outcome = match locked_member(&mut tx, &workspace_id, &member_id).await? {
+            (None, _) => MemberChange::NotFound,
+            (Some(WorkspaceRole::Owner), owners) if role != WorkspaceRole::Owner && owners <= 1 => {
+                MemberChange::LastOwner
+            }
+            (Some(_), _) => {
+                sqlx::query(
+                    "UPDATE workspace_members SET role = ? WHERE workspace_id = ? AND user_id = ?"
+                )
+                .bind(role.as_str())
+                .bind(&workspace_id)
+                .bind(&member_id)
+                .execute(&mut *tx)
+                .await?;
+                MemberChange::Done
+            }
+        };
+
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(outcome)
+    }
+    .await;
+
+    match result {
+        Ok(MemberChange::Done) => HttpResponse::Ok().json(serde_json::json!({
+            "message": "Member role updated successfully",
+            "role": role.as_str()
+        })),
+        Ok(MemberChange::NotFound) => member_not_found(),
+        Ok(MemberChange::LastOwner) => last_owner(),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to update member role"
+            }))
+        }
+    }
+}
+
+// DELETE /api/workspaces/{id}/members/{user_id} - Remover membro (owners) ou sair do espaço
+pub async fn remove_member(
+    pool: web::Data<MySqlPool>,
+    user_id: web::ReqData<String>,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let uid = user_id.into_inner();
+    let (workspace_id, member_id) = path.into_inner();
+
+    match membership(pool.get_ref(), &workspace_id, &uid).await {
+        Ok(WorkspaceRole::Owner) => {}
+        Ok(_) if member_id == uid => {}
+        Ok(_) => return owner_only(),
+        Err(response) => return response,
+    }
+
+    let result = async {
+        let mut tx = pool.begin().await?;
+
+        let outcome = match locked_member(&mut tx, &workspace_id, &member_id).await? {
+            (None, _) => MemberChange::NotFound,
+            (Some(WorkspaceRole::Owner), owners) if owners <= 1 => MemberChange::LastOwner,
+            (Some(_), _) => {
+                sqlx::query("DELETE FROM workspace_members WHERE workspace_id = ? AND user_id = ?")
+                    .bind(&workspace_id)
+                    .bind(&member_id)
+                    .execute(&mut *tx)
+                    .await?;
+                MemberChange::Done
+            }
+        };
+
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(outcome)
+    }
+    .await;
+
+    match result {
+        Ok(MemberChange::Done) => HttpResponse::Ok().json(serde_json::json!({
+            "message": "Member removed successfully"
+        })),
+        Ok(MemberChange::NotFound) => member_not_found(),
+        Ok(MemberChange::LastOwner) => last_owner(),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to remove member"
+            }))
+        }
+    }
+}
+
+// POST /api/workspaces/{id}/invitations - Convidar por e-mail (somente owners)
+pub async fn invite(
+    pool: web::Data<MySqlPool>,
+    user_id: web::ReqData<String>,
+    workspace_id: web::Path<String>,
+    invitation_data: web::Json<CreateInvitation>,
+) -> impl Responder {
+    if let Err(errors) = invitation_data.validate() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Validation failed",
+            "details": errors.to_string()
+        }));
+    }
+
+    let uid = user_id.into_inner();
+    let workspace_id = workspace_id.into_inner();
+
+    let workspace = match find_workspace(pool.get_ref(), &workspace_id, &uid).await {
+        Ok(Some(workspace)) => workspace,
+        Ok(None) => return workspace_not_found(),
+        Err(e) => return database_error(e),
+    };
+
+    if workspace.role != WorkspaceRole::Owner.as_str() {
+        return owner_only();
+    }
+    if workspace.personal {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Members cannot be invited to a personal workspace"
+        }));
+    }
+
+    let role = match parse_role(invitation_data.role.as_deref().unwrap_or("editor")) {
+        Ok(role) => role,
+        Err(response) => return response,
+    };
+    let email = invitation_data.email.trim().to_lowercase();
+
+    let invitee = sqlx::query_as::<_, (String, bool)>(
+        "SELECT u.id, EXISTS(
+             SELECT 1 FROM workspace_members m WHERE m.workspace_id = ? AND m.user_id = u.id
+         ) AS is_member
+         FROM users u WHERE u.email = ?"
+    )
+    .bind(&workspace_id)
+    .bind(&email)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    let invitee_id = match invitee {
+        Ok(Some((_, true))) => {
+            return HttpResponse::Conflict().json(serde_json::json!({
+                "error": "User is already a member of this workspace"
+            }));
+        }
+        Ok(Some((id, false))) => Some(id),
+        Ok(None) => None,
+        Err(e) => return database_error(e),
+    };
+
+    let pending = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM workspace_invitations
+         WHERE workspace_id = ? AND email = ? AND status = 'pending'"
+    )
+    .bind(&workspace_id)
+    .bind(&email)
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match pending {
+        Ok(0) => {}
+        Ok(_) => {
+            return HttpResponse::Conflict().json(serde_json::json!({
+                "error": "There is already a pending invitation for this email"
+            }));
+        }
+        Err(e) => return database_error(e),
+    }
+
+    let invitation_id = uuid::Uuid::new_v4().to_string();
+
+    let result = sqlx::query(
+        "INSERT INTO workspace_invitations (id, workspace_id, email, role, invited_by)
+         VALUES (?, ?, ?, ?, ?)"
+    )
+    .bind(&invitation_id)
+    .bind(&workspace_id)
+    .bind(&email)
+    .bind(role.as_str())
+    .bind(&uid)
+    .execute(pool.get_ref())
+    .await;
+
+    if let Err(e) = result {
+        eprintln!("Database error: {}", e);
+        return HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to create invitation"
+        }));
+    }
+
+    if let Some(invitee_id) = invitee_id {
+        let message = format!(
+            "Você foi convidado para o espaço \"{}\". Aceite o convite para acessar as finanças compartilhadas.",
+            workspace.name
+        );
+        if let Err(e) = notify(pool.get_ref(), &invitee_id, "Convite recebido", &message, "info").await {
+            eprintln!("Failed to notify invited user: {}", e);
+        }
+    }
+
+    let invitation = sqlx::query_as::<_, WorkspaceInvitation>(&format!(
+        "SELECT {} FROM workspace_invitations i JOIN workspaces w ON w.id = i.workspace_id
+         WHERE i.id = ?",
+        INVITATION_COLUMNS
+    ))
+    .bind(&invitation_id)
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match invitation {
+        Ok(invitation) => HttpResponse::Created().json(invitation),
+        Err(e) => database_error(e),
+    }
+}
+
+// GET /api/workspaces/{id}/invitations - Convites do espaço (somente owners)
+pub async fn get_invitations(
+    pool: web::Data<MySqlPool>,
+    user_id: web::ReqData<String>,
+    workspace_id: web::Path<String>,
+) -> impl Responder {
+    let workspace_id = workspace_id.into_inner();
+
+    match membership(pool.get_ref(), &workspace_id, &user_id).await {
+        Ok(WorkspaceRole::Owner) => {}
+        Ok(_) => return owner_only(),
+        Err(response) => return response,
+    }
+
+    let invitations = sqlx::query_as::<_, WorkspaceInvitation>(&format!(
+        "SELECT {} FROM workspace_invitations i JOIN workspaces w ON w.id = i.workspace_id
+         WHERE i.workspace_id = ?
+         ORDER BY i.created_at DESC",
+        INVITATION_COLUMNS
+    ))
+    .bind(&workspace_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match invitations {
+        Ok(invitations) => HttpResponse::Ok().json(invitations),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to fetch invitations"
+            }))
+        }
+    }
+}
+
+// DELETE /api/workspaces/{id}/invitations/{invitation_id} - Revogar convite pendente
+pub async fn revoke_invitation(
+    pool: web::Data<MySqlPool>,
+    user_id: web::ReqData<String>,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let (workspace_id, invitation_id) = path.into_inner();
+
+    match membership(pool.get_ref(), &workspace_id, &user_id).await {
+        Ok(WorkspaceRole::Owner) => {}
+        Ok(_) => return owner_only(),
+        Err(response) => return response,
+    }
+
+    let result = sqlx::query(
+        "UPDATE workspace_invitations SET status = 'revoked', responded_at = NOW()
+         WHERE id = ? AND workspace_id = ? AND status = 'pending'"
+    )
+    .bind(&invitation_id)
+    .bind(&workspace_id)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(result) if result.rows_affected() > 0 => HttpResponse::Ok().json(serde_json::json!({
+            "message": "Invitation revoked successfully"
+        })),
+        Ok(_) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Pending invitation not found"
+        })),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to revoke invitation"
+            }))
+        }
+    }
+}
+
+// GET /api/invitations - Convites pendentes para o e-mail do usuário
+pub async fn my_invitations(
+    pool: web::Data<MySqlPool>,
+    user_id: web::ReqData<String>,
+) -> impl Responder {
+    let invitations = sqlx::query_as::<_, WorkspaceInvitation>(&format!(
+        "SELECT {} FROM workspace_invitations i
+         JOIN workspaces w ON w.id = i.workspace_id
+         JOIN users u ON u.email = i.email
+         WHERE u.id = ? AND i.status = 'pending'
+         ORDER BY i.created_at DESC",
+        INVITATION_COLUMNS
+    ))
+    .bind(user_id.into_inner())
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match invitations {
+        Ok(invitations) => HttpResponse::Ok().json(invitations),
+        Err(e) => {
+            eprintln!("Database error: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to fetch invitations"
+            }))
+        }
+    }
+}
+
+// Responde a um convite pendente endereçado ao e-mail do usuário.
+// Retorna o id do espaço, ou None quando o convite não existe.
+async fn respond_invitation(
+    pool: &MySqlPool,
+    user_id: &str,
+    invitation_id: &str,
+    accept: bool,
+) -> Result<Option<String>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let invitation = sqlx::query_as::<_, (String, String)>(
+        "SELECT i.workspace_id, i.role
+         FROM workspace_invitations i
+         JOIN users u ON u.email = i.email
+         WHERE i.id = ? AND u.id = ? AND i.status = 'pending'
+         FOR UPDATE"
+    )
+    .bind(invitation_id)
+    .bind(user_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some((workspace_id, role)) = invitation else {
+        return Ok(None);
+    };
+
+    if accept {
+        sqlx::query(
+            "INSERT IGNORE INTO workspace_members (workspace_id, user_id, role) VALUES (?, ?, ?)"
+        )
+        .bind(&workspace_id)
+        .bind(user_id)
+        .bind(&role)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query(
+        "UPDATE workspace_invitations SET status = ?, responded_at = NOW() WHERE id = ?"
+    )
+    .bind(if accept { "accepted" } else { "declined" })
+    .bind(invitation_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Some(workspace_id))
+}
+
+// POST /api/invitations/{id}/accept - Aceitar convite
+pub async fn accept_invitation(
+    pool: web::Data<MySqlPool>,
+    user_id: web::ReqData<String>,
+    invitation_id: web::Path<String>,
+) -> impl Responder {
+    let uid = user_id.into_inner();
+
+    match respond_invitation(pool.get_ref(), &uid, &invitation_id, true).await {
+        Ok(Some(workspace_id)) => match find_workspace(pool.get_ref(), &workspace_id, &uid).await {
+            Ok(Some(workspace)) => HttpResponse::Ok().json(workspace),
+            Ok(None) => workspace_not_found(),
+            Err(e) => database_error(e),
+        },
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Pending invitation not found"
+        })),
+        Err(e) => database_error(e),
+    }
+}
+
+// POST /api/invitations/{id}/decline - Recusar convite
+pub async fn decline_invitation(
+    pool: web::Data<MySqlPool>,
+    user_id: web::ReqData<String>,
+    invitation_id: web::Path<String>,
+) -> impl Responder {
+    match respond_invitation(pool.get_ref(), &user_id, &invitation_id, false).await {
+        Ok(Some(_)) => HttpResponse::Ok().json(serde_json::json!({
+            "message": "Invitation declined"
+        })),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Pending invitation not found"
+        })),
+        Err(e) => database_error(e),
+    }
+}
@@ -2,10 +2,10 @@ mod db;
 mod handlers;
 mod middleware;
 mod models;
+mod scheduler;
 mod utils;
 
 use actix_cors::Cors;
-use actix_files as fs;
 use actix_web::{middleware::Logger, web, App, HttpResponse, HttpServer};
 use dotenv::dotenv;
 use std::env;
@@ -30,6 +30,8 @@ async fn main() -> std::io::Result<()> {
     // log::info!("🌐 Frontend available at http://{}:{}", host, port);
     log::info!("🔌 API available at http://{}:{}/api", host, port);
 
+    scheduler::start(pool.clone());
+
     HttpServer::new(move || {
         let cors = Cors::default()
             .allow_any_origin()
@@ -62,9 +64,31 @@ async fn main() -> std::io::Result<()> {
                         web::scope("/transactions")
                             .route("", web::get().to(handlers::transactions::get_all))
                             .route("", web::post().to(handlers::transactions::create))
+                            .route("/installments", web::get().to(handlers::installments::get_all))
+                            .route("/installments", web::post().to(handlers::installments::create))
+                            .route("/installments/{id}", web::get().to(handlers::installments::get_by_id))
+                            .route("/installments/{id}", web::delete().to(handlers::installments::cancel))
+                            .route("/installments/{id}/prepay", web::post().to(handlers::installments::prepay))
                             .route("/{id}", web::get().to(handlers::transactions::get_by_id))
                             .route("/{id}", web::put().to(handlers::transactions::update))
-                            .route("/{id}", web::delete().to(handlers::transactions::delete)),
+                            .route("/{id}", web::delete().to(handlers::transactions::delete))
+                            .route("/{id}/lines", web::get().to(handlers::transactions::get_lines))
+                            .route("/{id}/lines", web::put().to(handlers::transactions::save_lines))
+                            .route("/{id}/lines", web::delete().to(handlers::transactions::delete_lines))
+                            .route("/{id}/split", web::get().to(handlers::splits::get_split))
+                            .route("/{id}/split", web::put().to(handlers::splits::save_split))
+                            .route("/{id}/split", web::delete().to(handlers::splits::delete_split)),
+                    )
+                    // Accounts
+                    .service(
+                        web::scope("/accounts")
+                            .route("", web::get().to(handlers::accounts::get_all))
+                            .route("", web::post().to(handlers::accounts::create))
+                            .route("/{id}", web::put().to(handlers::accounts::update))
+                            .route("/{id}", web::delete().to(handlers::accounts::delete))
+                            .route("/{id}/statements", web::get().to(handlers::accounts::statements))
+                            .route("/{id}/statements/{month}", web::get().to(handlers::accounts::statement))
+                            .route("/{id}/statements/{month}/pay", web::post().to(handlers::accounts::pay_statement)),
                     )
                     // Categories
                     .service(
@@ -82,17 +106,104 @@ async fn main() -> std::io::Result<()> {
                             .route("/{id}", web::get().to(handlers::goals::get_by_id))
                             .route("/{id}", web::put().to(handlers::goals::update))
                             .route("/{id}", web::delete().to(handlers::goals::delete))
-                            .route("/{id}/progress", web::post().to(handlers::goals::add_progress)),
+                            .route("/{id}/progress", web::post().to(handlers::goals::add_progress))
+                            .route("/{id}/projection", web::get().to(handlers::goals::projection))
+                            .route("/{id}/members", web::get().to(handlers::goals::get_members))
+                            .route("/{id}/members", web::post().to(handlers::goals::invite_member))
+                            .route("/{id}/invitations", web::get().to(handlers::goals::get_invitations))
+                            .route("/{id}/invitations/{invitation_id}", web::delete().to(handlers::goals::revoke_invitation))
+                            .route("/{id}/members/{user_id}", web::delete().to(handlers::goals::remove_member))
+                            .route("/{id}/autosave", web::get().to(handlers::autosave::get_all))
+                            .route("/{id}/autosave", web::post().to(handlers::autosave::create))
+                            .route("/{id}/autosave/{rule_id}", web::put().to(handlers::autosave::update))
+                            .route("/{id}/autosave/{rule_id}", web::delete().to(handlers::autosave::delete))
+                            .route("/{id}/contributions", web::get().to(handlers::goals::get_contributions))
+                            .route("/{id}/contributions/{contribution_id}", web::delete().to(handlers::goals::delete_contribution)),
+                    )
+                    // Budgets
+                    .service(
+                        web::scope("/budgets")
+                            .route("", web::get().to(handlers::budgets::get_all))
+                            .route("", web::post().to(handlers::budgets::create))
+                            .route("/status", web::get().to(handlers::budgets::status))
+                            .route("/{id}", web::put().to(handlers::budgets::update))
+                            .route("/{id}", web::delete().to(handlers::budgets::delete)),
+                    )
+                    // Envelopes
+                    .service(
+                        web::scope("/envelopes")
+                            .route("", web::get().to(handlers::envelopes::get_all))
+                            .route("/settings", web::put().to(handlers::envelopes::update_settings))
+                            .route("/left-to-assign", web::get().to(handlers::envelopes::left_to_assign))
+                            .route("/assignments", web::get().to(handlers::envelopes::get_assignments))
+                            .route("/assign", web::post().to(handlers::envelopes::assign))
+                            .route("/move", web::post().to(handlers::envelopes::move_money)),
                     )
                     // Recurring Transactions
                     .service(
                         web::scope("/recurring")
                             .route("", web::get().to(handlers::recurring::get_all))
                             .route("", web::post().to(handlers::recurring::create))
+                            .route("/upcoming", web::get().to(handlers::recurring::upcoming))
+                            .route("/{id}/occurrences", web::get().to(handlers::recurring::occurrences))
+                            .route("/{id}/occurrences/{date}", web::put().to(handlers::recurring::upsert_exception))
+                            .route("/{id}/occurrences/{date}", web::delete().to(handlers::recurring::delete_exception))
+                            .route("/{id}/exceptions", web::get().to(handlers::recurring::get_exceptions))
                             .route("/{id}", web::put().to(handlers::recurring::update))
                             .route("/{id}", web::delete().to(handlers::recurring::delete))
                             .route("/generate", web::post().to(handlers::recurring::generate_pending)),
                     )
+                    // Reference rates
+                    .service(
+                        web::scope("/rates")
+                            .route("", web::get().to(handlers::rates::get_all))
+                            .route("/{code}", web::put().to(handlers::rates::update)),
+                    )
+                    // Tags
+                    .service(
+                        web::scope("/tags")
+                            .route("", web::get().to(handlers::tags::get_all))
+                            .route("", web::post().to(handlers::tags::create))
+                            .route("/{id}", web::put().to(handlers::tags::update))
+                            .route("/{id}", web::delete().to(handlers::tags::delete))
+                            .route("/{id}/merge", web::post().to(handlers::tags::merge)),
+                    )
+                    // Expense splitting and settle-ups
+                    .service(
+                        web::scope("/splits")
+                            .route("", web::get().to(handlers::splits::get_all))
+                            .route("/balances", web::get().to(handlers::splits::balances))
+                            .route("/simplify", web::get().to(handlers::splits::simplify_debts))
+                            .route("/settlements", web::get().to(handlers::splits::get_settlements))
+                            .route("/settlements", web::post().to(handlers::splits::settle_up))
+                            .route("/settlements/{id}", web::delete().to(handlers::splits::delete_settlement))
+                            .route("/settlements/{id}/confirm", web::post().to(handlers::splits::confirm_settlement))
+                            .route("/settlements/{id}/decline", web::post().to(handlers::splits::decline_settlement)),
+                    )
+                    // Workspaces
+                    .service(
+                        web::scope("/workspaces")
+                            .route("", web::get().to(handlers::workspaces::get_all))
+                            .route("", web::post().to(handlers::workspaces::create))
+                            .route("/{id}", web::put().to(handlers::workspaces::update))
+                            .route("/{id}", web::delete().to(handlers::workspaces::delete))
+                            .route("/{id}/members", web::get().to(handlers::workspaces::get_members))
+                            .route("/{id}/members/{user_id}", web::put().to(handlers::workspaces::update_member))
+                            .route("/{id}/members/{user_id}", web::delete().to(handlers::workspaces::remove_member))
+                            .route("/{id}/invitations", web::get().to(handlers::workspaces::get_invitations))
+                            .route("/{id}/invitations", web::post().to(handlers::workspaces::invite))
+                            .route("/{id}/invitations/{invitation_id}", web::delete().to(handlers::workspaces::revoke_invitation)),
+                    )
+                    // Workspace and goal invitations received by the current user
+                    .service(
+                        web::scope("/invitations")
+                            .route("", web::get().to(handlers::workspaces::my_invitations))
+                            .route("/goals", web::get().to(handlers::goals::my_invitations))
+                            .route("/goals/{id}/accept", web::post().to(handlers::goals::accept_invitation))
+                            .route("/goals/{id}/decline", web::post().to(handlers::goals::decline_invitation))
+                            .route("/{id}/accept", web::post().to(handlers::workspaces::accept_invitation))
+                            .route("/{id}/decline", web::post().to(handlers::workspaces::decline_invitation)),
+                    )
                     // Notifications
                     .service(
                         web::scope("/notifications")
@@ -100,6 +211,20 @@ async fn main() -> std::io::Result<()> {
                             .route("", web::post().to(handlers::notifications::create))
                             .route("/{id}/read", web::put().to(handlers::notifications::mark_as_read))
                             .route("/{id}", web::delete().to(handlers::notifications::delete)),
+                    )
+                    // Search
+                    .route("/search", web::get().to(handlers::search::search))
+                    // Dashboard
+                    .route("/dashboard", web::get().to(handlers::dashboard::summary))
+                    // Calendar
+                    .service(
+                        web::scope("/calendar")
+                            .route("/business-days", web::get().to(handlers::calendar::business_days)),
+                    )
+                    // Reports
+                    .service(
+                        web::scope("/reports")
+                            .route("/cashflow", web::get().to(handlers::reports::cashflow)),
                     ),
             )
             // Health check
@@ -56,8 +56,7 @@ where
 
             if let Some(auth_value) = auth_header {
                 if let Ok(auth_str) = auth_value.to_str() {
-                    if auth_str.starts_with("Bearer ") {
-                        let token = &auth_str[7..];
+                    if let Some(token) = auth_str.strip_prefix("Bearer ") {
 
                         match decode_jwt(token) {
                             Ok(claims) => {
@@ -0,0 +1,180 @@
+use actix_web::{
+    dev::Payload, error::InternalError, web, Error, FromRequest, HttpMessage, HttpRequest,
+    HttpResponse,
+};
+use futures_util::future::LocalBoxFuture;
+use sqlx::MySqlPool;
+
+// Header com o espaço de trabalho ativo; sem ele vale o espaço pessoal
+pub const WORKSPACE_HEADER: &str = "X-Workspace-Id";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WorkspaceRole {
+    Viewer,
+    Editor,
+    Owner,
+}
+
+impl WorkspaceRole {
+    pub fn parse(role: &str) -> Option<Self> {
+        match role {
+            "viewer" => Some(Self::Viewer),
+            "editor" => Some(Self::Editor),
+            "owner" => Some(Self::Owner),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Viewer => "viewer",
+            Self::Editor => "editor",
+            Self::Owner => "owner",
+        }
+    }
+}
+
+// Espaço de trabalho da requisição, já autorizado contra workspace_members.
+// `user_id` é o usuário autenticado; os dados pertencem a `id`.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    pub id: String,
+    pub user_id: String,
+    pub role: WorkspaceRole,
+}
+
+impl Workspace {
+    pub fn can_edit(&self) -> bool {
+        self.role >= WorkspaceRole::Editor
+    }
+
+    pub fn require_editor(&self) -> Result<(), HttpResponse> {
+        if self.can_edit() {
+            Ok(())
+        } else {
+            Err(HttpResponse::Forbidden().json(serde_json::json!({
+                "error": "Viewers cannot modify this workspace"
+            })))
+        }
+    }
+
+    pub fn require_owner(&self) -> Result<(), HttpResponse> {
+        if self.role == WorkspaceRole::Owner {
+            Ok(())
+        } else {
+            Err(HttpResponse::Forbidden().json(serde_json::json!({
+                "error": "Only workspace owners can do this"
+            })))
+        }
+    }
+}
+
+// Garante o espaço pessoal do usuário (mesmo id do usuário)
+pub async fn ensure_personal_workspace(pool: &MySqlPool, user_id: &str) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        "INSERT IGNORE INTO workspaces (id, name, personal, created_by)
+         VALUES (?, 'Pessoal', TRUE, ?)"
+    )
+    .bind(user_id)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "INSERT IGNORE INTO workspace_members (workspace_id, user_id, role)
+         VALUES (?, ?, 'owner')"
+    )
+    .bind(user_id)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await
+}
+
+pub async fn member_role(
+    pool: &MySqlPool,
+    workspace_id: &str,
+    user_id: &str,
+) -> Result<Option<WorkspaceRole>, sqlx::Error> {
+    let role = sqlx::query_scalar::<_, String>(
+        "SELECT role FROM workspace_members WHERE workspace_id = ? AND user_id = ?"
+    )
+    .bind(workspace_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(role.as_deref().and_then(WorkspaceRole::parse))
+}
+
+fn reject(response: HttpResponse) -> Error {
+    let message = match response.status().as_u16() {
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        _ => "Workspace error",
+    };
+    InternalError::from_response(message, response).into()
+}
+
+impl FromRequest for Workspace {
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let pool = req.app_data::<web::Data<MySqlPool>>().cloned();
+        let user_id = req.extensions().get::<String>().cloned();
+        let requested = req
+            .headers()
+            .get(WORKSPACE_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty());
+
+        Box::pin(async move {
+            let (pool, user_id) = match (pool, user_id) {
+                (Some(pool), Some(user_id)) => (pool, user_id),
+                _ => {
+                    return Err(reject(HttpResponse::Unauthorized().json(serde_json::json!({
+                        "error": "Missing or invalid authorization header"
+                    }))));
+                }
+            };
+            let workspace_id = requested.unwrap_or_else(|| user_id.clone());
+
+            let database_error = |e: sqlx::Error| {
+                eprintln!("Database error: {}", e);
+                reject(HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": "Database error"
+                })))
+            };
+
+            let mut role = member_role(pool.get_ref(), &workspace_id, &user_id)
+                .await
+                .map_err(database_error)?;
+
+            // O cadastro cria o espaço pessoal logo depois do usuário; se essa
+            // etapa falhou, ele é criado aqui. Os dados de contas anteriores aos
+            // espaços de trabalho são movidos pelo script migrate_schema.sql.
+            if role.is_none() && workspace_id == user_id {
+                ensure_personal_workspace(pool.get_ref(), &user_id)
+                    .await
+                    .map_err(database_error)?;
+                role = Some(WorkspaceRole::Owner);
+            }
+
+            match role {
+                Some(role) => Ok(Workspace {
+                    id: workspace_id,
+                    user_id,
+                    role,
+                }),
+                None => Err(reject(HttpResponse::Forbidden().json(serde_json::json!({
+                    "error": "You are not a member of this workspace"
+                })))),
+            }
+        })
+    }
+}
@@ -15,6 +15,8 @@ pub struct User {
     pub cpf: String,
     pub birth_date: NaiveDate,
     pub phone: String,
+    pub timezone: String,
+    pub is_admin: bool,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
@@ -43,8 +45,10 @@ pub struct LoginRequest {
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct Transaction {
     pub id: String,
+    pub workspace_id: String,
     pub user_id: String,
     pub description: String,
+    pub notes: Option<String>,
     pub amount: Decimal,
     #[sqlx(rename = "type")]
     pub transaction_type: String,
@@ -52,23 +56,37 @@ pub struct Transaction {
     pub date: chrono::DateTime<chrono::Utc>,
     pub recurring: bool,
     pub recurring_id: Option<String>,
+    pub occurrence_date: Option<NaiveDate>,
+    pub installment_plan_id: Option<String>,
+    pub installment_number: Option<u32>,
+    pub account_id: Option<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
+    // Nomes das tags; preenchido só pelas rotas de transações
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Validate)]
 pub struct CreateTransaction {
     #[validate(length(min = 1, max = 255))]
     pub description: String,
+    #[validate(length(max = 5000))]
+    pub notes: Option<String>,
     pub amount: f64,
     pub transaction_type: String,
     pub category_id: Option<String>,
+    pub account_id: Option<String>,
     pub date: Option<chrono::NaiveDate>,
+    // Nomes das tags; as que não existem são criadas
+    pub tags: Option<Vec<String>>,
 }
 
 // Category models
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct Category {
     pub id: String,
+    pub workspace_id: Option<String>,
     pub user_id: Option<String>,
     pub name: String,
     pub icon: String,
@@ -0,0 +1,164 @@
+use chrono::Utc;
+use sqlx::MySqlPool;
+use std::env;
+use std::time::{Duration, Instant};
+
+use crate::handlers::accounts::send_due_reminders;
+use crate::handlers::goals::send_goal_reminders;
+use crate::handlers::notifications::notify;
+use crate::handlers::recurring::{catchup_limit, generate_for_workspace};
+use crate::handlers::workspaces::member_ids;
+
+// Nome do advisory lock (GET_LOCK) que elege a réplica que executa os jobs
+const LEADER_LOCK: &str = "alpha_bank_scheduler";
+const DEFAULT_INTERVAL_SECS: u64 = 3600;
+
+// Intervalo entre execuções; RECURRING_SCHEDULER_INTERVAL_SECS=0 desativa
+fn interval_from_env() -> Option<Duration> {
+    let secs = env::var("RECURRING_SCHEDULER_INTERVAL_SECS")
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_INTERVAL_SECS);
+
+    if secs == 0 {
+        None
+    } else {
+        Some(Duration::from_secs(secs))
+    }
+}
+
+pub fn start(pool: MySqlPool) {
+    let Some(interval) = interval_from_env() else {
+        log::info!("⏰ Scheduler disabled");
+        return;
+    };
+
+    log::info!("⏰ Scheduler running every {}s", interval.as_secs());
+
+    actix_web::rt::spawn(async move {
+        let mut ticker = actix_web::rt::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = run_once(&pool).await {
+                log::error!("Scheduler run failed: {}", e);
+            }
+        }
+    });
+}
+
+// Executa os jobs somente se esta réplica conseguir o lock. O lock pertence à
+// conexão, por isso ela é mantida até o RELEASE_LOCK.
+async fn run_once(pool: &MySqlPool) -> Result<(), sqlx::Error> {
+    let mut conn = pool.acquire().await?;
+
+    let acquired = sqlx::query_scalar::<_, Option<i64>>("SELECT GET_LOCK(?, 0)")
+        .bind(LEADER_LOCK)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    if acquired != Some(1) {
+        log::debug!("Scheduler lock held by another replica, skipping run");
+        return Ok(());
+    }
+
+    let result = generate_recurring(pool).await;
+    let reminders = card_due_reminders(pool).await;
+    let goals = goal_reminders(pool).await;
+
+    sqlx::query("SELECT RELEASE_LOCK(?)")
+        .bind(LEADER_LOCK)
+        .execute(&mut *conn)
+        .await?;
+
+    result.and(reminders).and(goals)
+}
+
+// Avisa sobre faturas de cartão que vencem nos próximos dias
+async fn card_due_reminders(pool: &MySqlPool) -> Result<(), sqlx::Error> {
+    let sent = send_due_reminders(pool, Utc::now().date_naive()).await?;
+    if sent > 0 {
+        log::info!("💳 Card due reminders: {} notifications sent", sent);
+    }
+    Ok(())
+}
+
+// Avisos de marcos e prazos das metas
+async fn goal_reminders(pool: &MySqlPool) -> Result<(), sqlx::Error> {
+    let sent = send_goal_reminders(pool, Utc::now().date_naive()).await?;
+    if sent > 0 {
+        log::info!("🎯 Goal reminders: {} notifications sent", sent);
+    }
+    Ok(())
+}
+
+// Gera as transações recorrentes pendentes de todos os espaços de trabalho
+async fn generate_recurring(pool: &MySqlPool) -> Result<(), sqlx::Error> {
+    let started = Instant::now();
+    let today = Utc::now().date_naive();
+    let limit = catchup_limit();
+
+    let workspace_ids = sqlx::query_scalar::<_, String>(
+        "SELECT DISTINCT workspace_id FROM recurring_transactions WHERE active = TRUE"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut generated = 0;
+    let mut affected_workspaces = 0;
+    let mut capped = 0;
+    let mut failures = 0;
+
+    for workspace_id in &workspace_ids {
+        let summary = match generate_for_workspace(pool, workspace_id, today, limit).await {
+            Ok(summary) => summary,
+            Err(e) => {
+                log::error!("Recurring generation failed for workspace {}: {}", workspace_id, e);
+                failures += 1;
+                continue;
+            }
+        };
+
+        capped += summary.capped.len();
+        if summary.generated == 0 {
+            continue;
+        }
+
+        generated += summary.generated;
+        affected_workspaces += 1;
+
+        let message = if summary.generated == 1 {
+            "1 transação recorrente foi lançada automaticamente.".to_string()
+        } else {
+            format!(
+                "{} transações recorrentes foram lançadas automaticamente.",
+                summary.generated
+            )
+        };
+
+        let members = match member_ids(pool, workspace_id).await {
+            Ok(members) => members,
+            Err(e) => {
+                log::error!("Failed to load members of workspace {}: {}", workspace_id, e);
+                continue;
+            }
+        };
+
+        for user_id in &members {
+            if let Err(e) = notify(pool, user_id, "Transações recorrentes", &message, "info").await {
+                log::error!("Failed to notify user {}: {}", user_id, e);
+            }
+        }
+    }
+
+    log::info!(
+        "🔁 Recurring scheduler: {} workspaces checked, {} affected, {} transactions generated, {} rules capped, {} failures in {}ms",
+        workspace_ids.len(),
+        affected_workspaces,
+        generated,
+        capped,
+        failures,
+        started.elapsed().as_millis()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,244 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::Serialize;
+
+// Calendário de feriados nacionais usado pelos bancos (FEBRABAN): além dos
+// feriados fixos, Carnaval, Sexta-feira Santa e Corpus Christi, que dependem
+// da data da Páscoa.
+#[derive(Debug, Clone, Serialize)]
+pub struct Holiday {
+    pub date: NaiveDate,
+    pub name: &'static str,
+}
+
+const FIXED_HOLIDAYS: [(u32, u32, &str); 8] = [
+    (1, 1, "Confraternização Universal"),
+    (4, 21, "Tiradentes"),
+    (5, 1, "Dia do Trabalho"),
+    (9, 7, "Independência do Brasil"),
+    (10, 12, "Nossa Senhora Aparecida"),
+    (11, 2, "Finados"),
+    (11, 15, "Proclamação da República"),
+    (12, 25, "Natal"),
+];
+
+// Lei 14.759/2023: feriado nacional a partir de 2024
+const CONSCIENCIA_NEGRA_SINCE: i32 = 2024;
+
+// Domingo de Páscoa (algoritmo de Meeus/Jones/Butcher, calendário gregoriano)
+pub fn easter(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+// Feriados nacionais do ano, em ordem de data
+pub fn national_holidays(year: i32) -> Vec<Holiday> {
+    let mut holidays: Vec<Holiday> = FIXED_HOLIDAYS
+        .iter()
+        .filter_map(|(month, day, name)| {
+            NaiveDate::from_ymd_opt(year, *month, *day).map(|date| Holiday { date, name })
+        })
+        .collect();
+
+    if year >= CONSCIENCIA_NEGRA_SINCE {
+        if let Some(date) = NaiveDate::from_ymd_opt(year, 11, 20) {
+            holidays.push(Holiday {
+                date,
+                name: "Dia Nacional de Zumbi e da Consciência Negra",
+            });
+        }
+    }
+
+    if let Some(easter) = easter(year) {
+        let moving: [(i64, &str); 4] = [
+            (-48, "Carnaval (segunda-feira)"),
+            (-47, "Carnaval (terça-feira)"),
+            (-2, "Sexta-feira Santa"),
+            (60, "Corpus Christi"),
+        ];
+        holidays.extend(moving.iter().filter_map(|(offset, name)| {
+            easter
+                .checked_add_signed(Duration::days(*offset))
+                .map(|date| Holiday { date, name })
+        }));
+    }
+
+    holidays.sort_by_key(|holiday| holiday.date);
+    holidays
+}
+
+pub fn holiday_name(date: NaiveDate) -> Option<&'static str> {
+    national_holidays(date.year())
+        .into_iter()
+        .find(|holiday| holiday.date == date)
+        .map(|holiday| holiday.name)
+}
+
+pub fn is_business_day(date: NaiveDate) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && holiday_name(date).is_none()
+}
+
+// Para onde mover uma data que cai em fim de semana ou feriado
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BusinessDayShift {
+    None,
+    Forward,
+    Backward,
+}
+
+impl BusinessDayShift {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "none" => Some(BusinessDayShift::None),
+            "forward" => Some(BusinessDayShift::Forward),
+            "backward" => Some(BusinessDayShift::Backward),
+            _ => None,
+        }
+    }
+
+    pub fn apply(&self, date: NaiveDate) -> NaiveDate {
+        let step = |date: NaiveDate| match self {
+            BusinessDayShift::None => None,
+            BusinessDayShift::Forward => date.succ_opt(),
+            BusinessDayShift::Backward => date.pred_opt(),
+        };
+
+        let mut adjusted = date;
+        while !is_business_day(adjusted) {
+            match step(adjusted) {
+                Some(next) => adjusted = next,
+                None => return date,
+            }
+        }
+        adjusted
+    }
+
+    // Mantém só a primeira de cada grupo de datas que cai no mesmo dia útil
+    // (com Forward, sábado, domingo e segunda viram a mesma segunda-feira).
+    // `dates` vem em ordem e `previous` é a data anterior a elas, já tratada.
+    pub fn distinct(&self, dates: &[NaiveDate], previous: Option<NaiveDate>) -> Vec<NaiveDate> {
+        let mut last = previous.map(|date| self.apply(date));
+        dates
+            .iter()
+            .copied()
+            .filter(|date| {
+                let adjusted = self.apply(*date);
+                let first = last != Some(adjusted);
+                last = Some(adjusted);
+                first
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn easter_dates() {
+        let known = [
+            date(1818, 3, 22),
+            date(2000, 4, 23),
+            date(2019, 4, 21),
+            date(2024, 3, 31),
+            date(2025, 4, 20),
+            date(2026, 4, 5),
+            date(2038, 4, 25),
+        ];
+        for expected in known {
+            assert_eq!(easter(expected.year()), Some(expected));
+        }
+    }
+
+    #[test]
+    fn national_holidays_of_2025() {
+        let holidays: Vec<(NaiveDate, &str)> = national_holidays(2025)
+            .into_iter()
+            .map(|holiday| (holiday.date, holiday.name))
+            .collect();
+
+        assert_eq!(
+            holidays,
+            vec![
+                (date(2025, 1, 1), "Confraternização Universal"),
+                (date(2025, 3, 3), "Carnaval (segunda-feira)"),
+                (date(2025, 3, 4), "Carnaval (terça-feira)"),
+                (date(2025, 4, 18), "Sexta-feira Santa"),
+                (date(2025, 4, 21), "Tiradentes"),
+                (date(2025, 5, 1), "Dia do Trabalho"),
+                (date(2025, 6, 19), "Corpus Christi"),
+                (date(2025, 9, 7), "Independência do Brasil"),
+                (date(2025, 10, 12), "Nossa Senhora Aparecida"),
+                (date(2025, 11, 2), "Finados"),
+                (date(2025, 11, 15), "Proclamação da República"),
+                (date(2025, 11, 20), "Dia Nacional de Zumbi e da Consciência Negra"),
+                (date(2025, 12, 25), "Natal"),
+            ]
+        );
+    }
+
+    #[test]
+    fn consciencia_negra_is_national_from_2024() {
+        assert_eq!(holiday_name(date(2023, 11, 20)), None);
+        assert_eq!(national_holidays(2023).len(), 12);
+        assert_eq!(
+            holiday_name(date(2024, 11, 20)),
+            Some("Dia Nacional de Zumbi e da Consciência Negra")
+        );
+        assert_eq!(national_holidays(2024).len(), 13);
+    }
+
+    #[test]
+    fn shifts_skip_weekends_and_holidays() {
+        let forward = BusinessDayShift::Forward;
+        let backward = BusinessDayShift::Backward;
+
+        // Sábado, 15/11/2025 (Proclamação da República)
+        assert_eq!(forward.apply(date(2025, 11, 15)), date(2025, 11, 17));
+        assert_eq!(backward.apply(date(2025, 11, 15)), date(2025, 11, 14));
+        // Segunda-feira, 21/04/2025 (Tiradentes), depois da Sexta-feira Santa
+        assert_eq!(forward.apply(date(2025, 4, 21)), date(2025, 4, 22));
+        assert_eq!(backward.apply(date(2025, 4, 21)), date(2025, 4, 17));
+        // Dias úteis não mudam
+        assert_eq!(forward.apply(date(2025, 4, 22)), date(2025, 4, 22));
+        assert_eq!(BusinessDayShift::None.apply(date(2025, 11, 15)), date(2025, 11, 15));
+    }
+
+    #[test]
+    fn distinct_keeps_one_date_per_business_day() {
+        // Sexta 14/11 a terça 18/11/2025, com o feriado de sábado no meio
+        let days: Vec<NaiveDate> = (14..=18).map(|day| date(2025, 11, day)).collect();
+
+        assert_eq!(
+            BusinessDayShift::Forward.distinct(&days, None),
+            vec![date(2025, 11, 14), date(2025, 11, 15), date(2025, 11, 18)]
+        );
+        assert_eq!(
+            BusinessDayShift::Backward.distinct(&days, None),
+            vec![date(2025, 11, 14), date(2025, 11, 17), date(2025, 11, 18)]
+        );
+        assert_eq!(
+            BusinessDayShift::Backward.distinct(&days[1..], Some(date(2025, 11, 14))),
+            vec![date(2025, 11, 17), date(2025, 11, 18)]
+        );
+        assert_eq!(BusinessDayShift::None.distinct(&days, None), days);
+    }
+}
@@ -1,5 +1,11 @@
+pub mod holidays;
+pub mod recurrence;
+pub mod rrule;
+
 use bcrypt::{hash, verify, DEFAULT_COST};
+use chrono::{Datelike, FixedOffset, NaiveDate};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Deserializer};
 use std::env;
 
 use crate::models::Claims;
@@ -78,3 +84,48 @@ pub fn validate_cpf(cpf: &str) -> bool {
 
     second_digit == cpf.chars().nth(10).unwrap().to_digit(10).unwrap() as usize
 }
+
+// Converte "YYYY-MM" no intervalo [primeiro dia do mês, primeiro dia do mês seguinte)
+pub fn parse_month(month: &str) -> Option<(NaiveDate, NaiveDate)> {
+    let start = NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d").ok()?;
+    let end = if start.month() == 12 {
+        NaiveDate::from_ymd_opt(start.year() + 1, 1, 1)?
+    } else {
+        NaiveDate::from_ymd_opt(start.year(), start.month() + 1, 1)?
+    };
+    Some((start, end))
+}
+
+// Converte um deslocamento "+HH:MM" / "-HH:MM" (ex.: "-03:00" para Brasília)
+pub fn parse_utc_offset(offset: &str) -> Option<FixedOffset> {
+    let sign = match offset.chars().next()? {
+        '+' => 1,
+        '-' => -1,
+        _ => return None,
+    };
+    let (hours, minutes) = offset[1..].split_once(':')?;
+    if hours.len() != 2
+        || minutes.len() != 2
+        || !hours.chars().chain(minutes.chars()).all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if hours > 14 || minutes > 59 {
+        return None;
+    }
+
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+// Distingue campo ausente (None) de campo enviado como null (Some(None)) em
+// atualizações parciais. Usar com #[serde(default, deserialize_with = ...)].
+pub fn double_option<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
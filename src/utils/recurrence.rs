@@ -0,0 +1,342 @@
+use chrono::{Datelike, Days, Months, NaiveDate};
+
+use crate::utils::rrule::RRule;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Frequency {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "daily" => Some(Frequency::Daily),
+            "weekly" => Some(Frequency::Weekly),
+            "monthly" => Some(Frequency::Monthly),
+            "yearly" => Some(Frequency::Yearly),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Frequency::Daily => "daily",
+            Frequency::Weekly => "weekly",
+            Frequency::Monthly => "monthly",
+            Frequency::Yearly => "yearly",
+        }
+    }
+}
+
+// Agenda de uma recorrência: a cada `interval` dias/semanas/meses/anos a
+// partir de `start`. Cada ocorrência é calculada a partir da data âncora, e
+// não da ocorrência anterior, para que o dia 31 volte a ser 31 depois de
+// fevereiro (31/01 -> 28/02 ou 29/02 -> 31/03). Com uma RRULE que tenha
+// partes BYxxx, as ocorrências são expandidas período a período.
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    pub start: NaiveDate,
+    pub frequency: Frequency,
+    pub interval: u32,
+    // Última data permitida (inclusive)
+    pub end: Option<NaiveDate>,
+    // Número máximo de ocorrências contadas a partir de `start`
+    pub count: Option<u32>,
+    pub rule: Option<RRule>,
+}
+
+// Limite de max_occurrences e da contagem de ocorrências restantes
+pub const MAX_REMAINING: u32 = 1200;
+
+// Períodos seguidos sem nenhuma ocorrência antes de considerar a regra
+// esgotada (ex.: BYMONTH=2;BYMONTHDAY=30 nunca acontece)
+const MAX_EMPTY_DAYS: u32 = 3000;
+const MIN_EMPTY_PERIODS: u32 = 9;
+
+impl Schedule {
+    pub fn new(start: NaiveDate, frequency: Frequency, interval: u32) -> Self {
+        Schedule {
+            start,
+            frequency,
+            interval: interval.max(1),
+            end: None,
+            count: None,
+            rule: None,
+        }
+    }
+
+    // COUNT e UNTIL da regra valem como limites, assim como `until`/`limit`
+    pub fn from_rrule(start: NaiveDate, rule: RRule) -> Self {
+        let schedule = Schedule::new(start, rule.frequency, rule.interval)
+            .until(rule.until)
+            .limit(rule.count);
+
+        Schedule {
+            rule: rule.has_by_rules().then_some(rule),
+            ..schedule
+        }
+    }
+
+    // Com mais de um limite, vale o mais restritivo
+    pub fn until(mut self, end: Option<NaiveDate>) -> Self {
+        self.end = match (self.end, end) {
+            (Some(current), Some(end)) => Some(current.min(end)),
+            (current, end) => current.or(end),
+        };
+        self
+    }
+
+    pub fn limit(mut self, count: Option<u32>) -> Self {
+        self.count = match (self.count, count) {
+            (Some(current), Some(count)) => Some(current.min(count)),
+            (current, count) => current.or(count),
+        };
+        self
+    }
+
+    pub fn is_bounded(&self) -> bool {
+        self.end.is_some() || self.count.is_some()
+    }
+
+    // n-ésima ocorrência (0 = data inicial) sem partes BYxxx. Meses mais curtos
+    // usam o último dia do mês, o que também cobre 29/02 em anos não bissextos.
+    fn nth(&self, n: u32) -> Option<NaiveDate> {
+        if self.count.is_some_and(|count| n >= count) {
+            return None;
+        }
+
+        let step = n.checked_mul(self.interval)?;
+        let date = match self.frequency {
+            Frequency::Daily => self.start.checked_add_days(Days::new(step as u64)),
+            Frequency::Weekly => self.start.checked_add_days(Days::new(step as u64 * 7)),
+            Frequency::Monthly => self.start.checked_add_months(Months::new(step)),
+            Frequency::Yearly => self
+                .start
+                .checked_add_months(Months::new(step.checked_mul(12)?)),
+        }?;
+
+        match self.end {
+            Some(end) if date > end => None,
+            _ => Some(date),
+        }
+    }
+
+    // Índice aproximado (nunca maior que o real) da primeira ocorrência após `date`
+    fn estimate_index(&self, date: NaiveDate) -> u32 {
+        if date < self.start {
+            return 0;
+        }
+
+        let elapsed = match self.frequency {
+            Frequency::Daily => (date - self.start).num_days(),
+            Frequency::Weekly => (date - self.start).num_days() / 7,
+            Frequency::Monthly => {
+                (date.year() - self.start.year()) as i64 * 12 + date.month() as i64
+                    - self.start.month() as i64
+            }
+            Frequency::Yearly => (date.year() - self.start.year()) as i64,
+        };
+
+        (elapsed / self.interval as i64 - 1).clamp(0, u32::MAX as i64) as u32
+    }
+
+    // Ocorrências estritamente posteriores a `after` (ou desde o início)
+    pub fn occurrences_after(&self, after: Option<NaiveDate>) -> Box<dyn Iterator<Item = NaiveDate> + '_> {
+        let rule = match &self.rule {
+            Some(rule) => rule,
+            None => {
+                let first = after.map(|date| self.estimate_index(date)).unwrap_or(0);
+                return Box::new(
+                    (first..)
+                        .map_while(move |n| self.nth(n))
+                        .filter(move |date| after.is_none_or(|after| *date > after)),
+                );
+            }
+        };
+
+        // Com limite de ocorrências é preciso contar desde o início
+        let first = match (self.count, after) {
+            (None, Some(date)) => self.estimate_index(date),
+            _ => 0,
+        };
+        let occurrences = self
+            .rule_occurrences(rule, first)
+            .take(self.count.map_or(usize::MAX, |count| count as usize))
+            .take_while(move |date| self.end.is_none_or(|end| *date <= end))
+            .filter(move |date| after.is_none_or(|after| *date > after));
+
+        Box::new(occurrences)
+    }
+
+    // Expande a regra período a período a partir do período `first`
+    fn rule_occurrences<'a>(&'a self, rule: &'a RRule, first: u32) -> impl Iterator<Item = NaiveDate> + 'a {
+        let max_empty = (match self.frequency {
+            Frequency::Daily => MAX_EMPTY_DAYS,
+            Frequency::Weekly => MAX_EMPTY_DAYS / 7,
+            Frequency::Monthly => MAX_EMPTY_DAYS / 30,
+            Frequency::Yearly => MAX_EMPTY_DAYS / 365,
+        } / self.interval)
+            .max(MIN_EMPTY_PERIODS);
+
+        let mut period = first;
+        let mut empty = 0;
+        let mut pending = Vec::new().into_iter();
+
+        std::iter::from_fn(move || loop {
+            if let Some(date) = pending.next() {
+                return Some(date);
+            }
+            if empty >= max_empty {
+                return None;
+            }
+
+            let start = rule.period(self.start, period)?;
+            if self.end.is_some_and(|end| start > end) {
+                return None;
+            }
+            period = period.checked_add(1)?;
+
+            let dates = rule.expand(start, self.start);
+            if dates.is_empty() {
+                empty += 1;
+            } else {
+                empty = 0;
+                pending = dates.into_iter();
+            }
+        })
+    }
+
+    pub fn next_after(&self, after: Option<NaiveDate>) -> Option<NaiveDate> {
+        self.occurrences_after(after).next()
+    }
+
+    // Indica se `date` é uma das ocorrências da agenda
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.next_after(date.pred_opt()) == Some(date)
+    }
+
+    // Quantidade de ocorrências após `after`; None para agendas sem fim ou com
+    // mais de MAX_REMAINING ocorrências pela frente (a contagem para ali)
+    pub fn remaining_after(&self, after: Option<NaiveDate>) -> Option<u32> {
+        if !self.is_bounded() {
+            return None;
+        }
+        let count = self
+            .occurrences_after(after)
+            .take(MAX_REMAINING as usize + 1)
+            .count() as u32;
+        (count <= MAX_REMAINING).then_some(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn first(schedule: &Schedule, n: usize) -> Vec<NaiveDate> {
+        schedule.occurrences_after(None).take(n).collect()
+    }
+
+    #[test]
+    fn monthly_from_the_31st_returns_to_the_31st() {
+        let schedule = Schedule::new(date(2025, 1, 31), Frequency::Monthly, 1);
+
+        assert_eq!(
+            first(&schedule, 4),
+            vec![date(2025, 1, 31), date(2025, 2, 28), date(2025, 3, 31), date(2025, 4, 30)]
+        );
+        assert_eq!(schedule.nth(13), Some(date(2026, 2, 28)));
+
+        let leap = Schedule::new(date(2024, 1, 31), Frequency::Monthly, 1);
+        assert_eq!(leap.nth(1), Some(date(2024, 2, 29)));
+    }
+
+    #[test]
+    fn yearly_from_february_29th_uses_the_28th_outside_leap_years() {
+        let schedule = Schedule::new(date(2024, 2, 29), Frequency::Yearly, 1);
+
+        assert_eq!(
+            first(&schedule, 5),
+            vec![
+                date(2024, 2, 29),
+                date(2025, 2, 28),
+                date(2026, 2, 28),
+                date(2027, 2, 28),
+                date(2028, 2, 29),
+            ]
+        );
+    }
+
+    #[test]
+    fn interval_count_skips_periods_from_the_anchor() {
+        let monthly = Schedule::new(date(2025, 1, 31), Frequency::Monthly, 2);
+        assert_eq!(
+            first(&monthly, 3),
+            vec![date(2025, 1, 31), date(2025, 3, 31), date(2025, 5, 31)]
+        );
+
+        let weekly = Schedule::new(date(2025, 1, 6), Frequency::Weekly, 3);
+        assert_eq!(
+            first(&weekly, 3),
+            vec![date(2025, 1, 6), date(2025, 1, 27), date(2025, 2, 17)]
+        );
+
+        let yearly = Schedule::new(date(2024, 2, 29), Frequency::Yearly, 4);
+        assert_eq!(yearly.nth(1), Some(date(2028, 2, 29)));
+    }
+
+    #[test]
+    fn estimate_index_never_skips_an_occurrence() {
+        let schedules = [
+            Schedule::new(date(2025, 1, 31), Frequency::Monthly, 1),
+            Schedule::new(date(2025, 1, 31), Frequency::Monthly, 3),
+            Schedule::new(date(2024, 2, 29), Frequency::Yearly, 1),
+            Schedule::new(date(2025, 1, 6), Frequency::Weekly, 2),
+            Schedule::new(date(2025, 1, 1), Frequency::Daily, 10),
+        ];
+
+        for schedule in &schedules {
+            let all = first(schedule, 60);
+            let mut after = schedule.start.pred_opt().unwrap();
+            while after < *all.last().unwrap() {
+                let expected = all.iter().find(|d| **d > after).copied();
+                let index = schedule.estimate_index(after) as usize;
+
+                assert!(all[index] <= expected.unwrap(), "{:?} after {}", schedule, after);
+                assert_eq!(schedule.next_after(Some(after)), expected, "{:?} after {}", schedule, after);
+                after = after.succ_opt().unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn remaining_stops_counting_past_the_cap() {
+        let daily = Schedule::new(date(2025, 1, 1), Frequency::Daily, 1);
+        assert_eq!(daily.clone().until(Some(date(2025, 1, 10))).remaining_after(None), Some(10));
+        assert_eq!(daily.clone().until(Some(date(9999, 12, 31))).remaining_after(None), None);
+        assert_eq!(daily.limit(Some(MAX_REMAINING)).remaining_after(None), Some(MAX_REMAINING));
+    }
+
+    #[test]
+    fn count_and_end_limit_the_occurrences() {
+        let schedule = Schedule::new(date(2025, 1, 31), Frequency::Monthly, 1)
+            .limit(Some(3))
+            .until(Some(date(2025, 12, 31)));
+        assert_eq!(schedule.nth(3), None);
+        assert_eq!(schedule.remaining_after(Some(date(2025, 2, 28))), Some(1));
+
+        let schedule = Schedule::new(date(2025, 1, 31), Frequency::Monthly, 1)
+            .until(Some(date(2025, 4, 29)));
+        assert_eq!(
+            first(&schedule, 10),
+            vec![date(2025, 1, 31), date(2025, 2, 28), date(2025, 3, 31)]
+        );
+    }
+}
@@ -0,0 +1,573 @@
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+use std::fmt;
+
+use crate::utils::recurrence::Frequency;
+
+// Subconjunto da RRULE do iCalendar (RFC 5545) aceito nas recorrências:
+// FREQ, INTERVAL, COUNT, UNTIL, BYDAY, BYMONTHDAY, BYMONTH e BYSETPOS.
+// Exemplos: "FREQ=MONTHLY;BYDAY=2FR" (toda segunda sexta-feira),
+// "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1" (último dia útil) e
+// "FREQ=MONTHLY;BYMONTHDAY=1,15" (dias 1 e 15).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RRule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub count: Option<u32>,
+    pub until: Option<NaiveDate>,
+    pub by_day: Vec<WeekdayRule>,
+    pub by_month_day: Vec<i32>,
+    pub by_month: Vec<u32>,
+    pub by_set_pos: Vec<i32>,
+}
+
+// Dia da semana com ordinal opcional (2FR = segunda sexta, -1SU = último domingo)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeekdayRule {
+    pub ordinal: Option<i32>,
+    pub weekday: Weekday,
+}
+
+const MAX_INTERVAL: u32 = 999;
+
+impl RRule {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        let value = value
+            .strip_prefix("RRULE:")
+            .or_else(|| value.strip_prefix("rrule:"))
+            .unwrap_or(value);
+
+        let mut frequency = None;
+        let mut interval = None;
+        let mut count = None;
+        let mut until = None;
+        let mut by_day = None;
+        let mut by_month_day = None;
+        let mut by_month = None;
+        let mut by_set_pos = None;
+
+        for part in value.split(';').filter(|part| !part.is_empty()) {
+            let (key, val) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid RRULE part: {}", part))?;
+            let key = key.trim().to_ascii_uppercase();
+            let val = val.trim().to_ascii_uppercase();
+
+            let duplicate = match key.as_str() {
+                "FREQ" => frequency.replace(parse_frequency(&val)?).is_some(),
+                "INTERVAL" => interval
+                    .replace(parse_number(&key, &val, 1, MAX_INTERVAL as i32)? as u32)
+                    .is_some(),
+                "COUNT" => count
+                    .replace(parse_number(&key, &val, 1, i32::MAX)? as u32)
+                    .is_some(),
+                "UNTIL" => until.replace(parse_until(&val)?).is_some(),
+                "BYDAY" => by_day
+                    .replace(parse_list(&val, parse_weekday_rule)?)
+                    .is_some(),
+                "BYMONTHDAY" => by_month_day
+                    .replace(parse_list(&val, |v| parse_signed(&key, v, 31))?)
+                    .is_some(),
+                "BYMONTH" => by_month
+                    .replace(parse_list(&val, |v| {
+                        parse_number(&key, v, 1, 12).map(|month| month as u32)
+                    })?)
+                    .is_some(),
+                "BYSETPOS" => by_set_pos
+                    .replace(parse_list(&val, |v| parse_signed(&key, v, 366))?)
+                    .is_some(),
+                "WKST" if val == "MO" => false,
+                "WKST" => return Err("Only WKST=MO is supported".to_string()),
+                _ => return Err(format!("Unsupported RRULE part: {}", key)),
+            };
+
+            if duplicate {
+                return Err(format!("Duplicate RRULE part: {}", key));
+            }
+        }
+
+        let rule = RRule {
+            frequency: frequency.ok_or("FREQ is required")?,
+            interval: interval.unwrap_or(1),
+            count,
+            until,
+            by_day: by_day.unwrap_or_default(),
+            by_month_day: by_month_day.unwrap_or_default(),
+            by_month: by_month.unwrap_or_default(),
+            by_set_pos: by_set_pos.unwrap_or_default(),
+        };
+
+        if rule.count.is_some() && rule.until.is_some() {
+            return Err("COUNT and UNTIL cannot be used together".to_string());
+        }
+        if matches!(rule.frequency, Frequency::Daily | Frequency::Weekly)
+            && rule.by_day.iter().any(|day| day.ordinal.is_some())
+        {
+            return Err("BYDAY ordinals require FREQ=MONTHLY or FREQ=YEARLY".to_string());
+        }
+        if rule.frequency == Frequency::Weekly && !rule.by_month_day.is_empty() {
+            return Err("BYMONTHDAY cannot be used with FREQ=WEEKLY".to_string());
+        }
+        if !rule.by_set_pos.is_empty() && !rule.has_by_rules_besides_set_pos() {
+            return Err("BYSETPOS requires BYDAY, BYMONTHDAY or BYMONTH".to_string());
+        }
+
+        Ok(rule)
+    }
+
+    // Sem BYxxx a regra equivale ao atalho de frequência simples
+    pub fn has_by_rules(&self) -> bool {
+        self.has_by_rules_besides_set_pos() || !self.by_set_pos.is_empty()
+    }
+
+    fn has_by_rules_besides_set_pos(&self) -> bool {
+        !self.by_day.is_empty() || !self.by_month_day.is_empty() || !self.by_month.is_empty()
+    }
+
+    // Início do k-ésimo período a partir de `start`: o próprio dia, a semana
+    // (começando na segunda-feira), o mês ou o ano
+    pub fn period(&self, start: NaiveDate, k: u32) -> Option<NaiveDate> {
+        let step = k.checked_mul(self.interval)?;
+        match self.frequency {
+            Frequency::Daily => start.checked_add_days(Days::new(step as u64)),
+            Frequency::Weekly => start
+                .checked_sub_days(Days::new(start.weekday().num_days_from_monday() as u64))?
+                .checked_add_days(Days::new(step as u64 * 7)),
+            Frequency::Monthly => start
+                .with_day(1)?
+                .checked_add_months(Months::new(step)),
+            Frequency::Yearly => NaiveDate::from_ymd_opt(start.year(), 1, 1)?
+                .checked_add_months(Months::new(step.checked_mul(12)?)),
+        }
+    }
+
+    // Ocorrências do período iniciado em `period`, em ordem, já com BYSETPOS
+    // aplicado e sem datas anteriores a `start`
+    pub fn expand(&self, period: NaiveDate, start: NaiveDate) -> Vec<NaiveDate> {
+        let mut dates = match self.frequency {
+            Frequency::Daily => {
+                if self.matches_month(period) && self.matches_month_day(period) && self.matches_weekday(period) {
+                    vec![period]
+                } else {
+                    Vec::new()
+                }
+            }
+            Frequency::Weekly => {
+                let weekdays: Vec<Weekday> = if self.by_day.is_empty() {
+                    vec![start.weekday()]
+                } else {
+                    self.by_day.iter().map(|day| day.weekday).collect()
+                };
+                weekdays
+                    .into_iter()
+                    .filter_map(|weekday| {
+                        period.checked_add_days(Days::new(weekday.num_days_from_monday() as u64))
+                    })
+                    .filter(|date| self.matches_month(*date))
+                    .collect()
+            }
+            Frequency::Monthly => {
+                if self.matches_month(period) {
+                    self.expand_month(period, start)
+                } else {
+                    Vec::new()
+                }
+            }
+            Frequency::Yearly => {
+                if !self.by_day.is_empty() && self.by_month.is_empty() && self.by_month_day.is_empty() {
+                    // BYDAY no escopo do ano (20MO = 20ª segunda-feira do ano)
+                    let next_year = period.checked_add_months(Months::new(12));
+                    next_year
+                        .map(|next_year| weekdays_between(period, next_year, &self.by_day))
+                        .unwrap_or_default()
+                } else {
+                    // Sem BYMONTH, BYMONTHDAY vale para todos os meses do ano
+                    let months: Vec<u32> = if !self.by_month.is_empty() {
+                        self.by_month.clone()
+                    } else if !self.by_month_day.is_empty() {
+                        (1..=12).collect()
+                    } else {
+                        vec![start.month()]
+                    };
+                    months
+                        .into_iter()
+                        .filter_map(|month| period.with_month(month))
+                        .flat_map(|month| self.expand_month(month, start))
+                        .collect()
+                }
+            }
+        };
+
+        dates.sort();
+        dates.dedup();
+
+        if !self.by_set_pos.is_empty() {
+            let len = dates.len() as i32;
+            let mut selected: Vec<NaiveDate> = self
+                .by_set_pos
+                .iter()
+                .filter_map(|pos| {
+                    let index = if *pos > 0 { pos - 1 } else { len + pos };
+                    (0..len).contains(&index).then(|| dates[index as usize])
+                })
+                .collect();
+            selected.sort();
+            selected.dedup();
+            dates = selected;
+        }
+
+        dates.retain(|date| *date >= start);
+        dates
+    }
+
+    // Dias do mês iniciado em `month` segundo BYMONTHDAY e BYDAY
+    fn expand_month(&self, month: NaiveDate, start: NaiveDate) -> Vec<NaiveDate> {
+        let next_month = match month.checked_add_months(Months::new(1)) {
+            Some(next_month) => next_month,
+            None => return Vec::new(),
+        };
+        let by_weekday = || weekdays_between(month, next_month, &self.by_day);
+
+        if !self.by_month_day.is_empty() {
+            let days: Vec<NaiveDate> = self
+                .by_month_day
+                .iter()
+                .filter_map(|day| month_day(month, next_month, *day))
+                .collect();
+            if self.by_day.is_empty() {
+                days
+            } else {
+                let weekdays = by_weekday();
+                days.into_iter().filter(|date| weekdays.contains(date)).collect()
+            }
+        } else if !self.by_day.is_empty() {
+            by_weekday()
+        } else {
+            // Dias inexistentes no mês (31/02) são ignorados, como na RFC 5545
+            month.with_day(start.day()).into_iter().collect()
+        }
+    }
+
+    fn matches_month(&self, date: NaiveDate) -> bool {
+        self.by_month.is_empty() || self.by_month.contains(&date.month())
+    }
+
+    fn matches_month_day(&self, date: NaiveDate) -> bool {
+        if self.by_month_day.is_empty() {
+            return true;
+        }
+        let month = date.with_day(1).unwrap();
+        let next_month = month.checked_add_months(Months::new(1));
+        next_month.is_some_and(|next_month| {
+            self.by_month_day
+                .iter()
+                .any(|day| month_day(month, next_month, *day) == Some(date))
+        })
+    }
+
+    fn matches_weekday(&self, date: NaiveDate) -> bool {
+        self.by_day.is_empty() || self.by_day.iter().any(|day| day.weekday == date.weekday())
+    }
+}
+
+impl fmt::Display for RRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FREQ={}", self.frequency.as_str().to_ascii_uppercase())?;
+        if self.interval > 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={}", count)?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%d"))?;
+        }
+        if !self.by_day.is_empty() {
+            let days: Vec<String> = self
+                .by_day
+                .iter()
+                .map(|day| {
+                    let ordinal = day.ordinal.map(|n| n.to_string()).unwrap_or_default();
+                    format!("{}{}", ordinal, weekday_code(day.weekday))
+                })
+                .collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        if !self.by_month_day.is_empty() {
+            write!(f, ";BYMONTHDAY={}", join(&self.by_month_day))?;
+        }
+        if !self.by_month.is_empty() {
+            write!(f, ";BYMONTH={}", join(&self.by_month))?;
+        }
+        if !self.by_set_pos.is_empty() {
+            write!(f, ";BYSETPOS={}", join(&self.by_set_pos))?;
+        }
+        Ok(())
+    }
+}
+
+// Datas em [from, to) que atendem às regras de dia da semana; ordinais contam
+// a partir do início (positivos) ou do fim (negativos) do intervalo
+fn weekdays_between(from: NaiveDate, to: NaiveDate, rules: &[WeekdayRule]) -> Vec<NaiveDate> {
+    let mut dates = Vec::new();
+
+    for rule in rules {
+        let matching: Vec<NaiveDate> = from
+            .iter_days()
+            .take_while(|date| *date < to)
+            .filter(|date| date.weekday() == rule.weekday)
+            .collect();
+
+        match rule.ordinal {
+            None => dates.extend(matching),
+            Some(n) => {
+                let index = if n > 0 { n - 1 } else { matching.len() as i32 + n };
+                if (0..matching.len() as i32).contains(&index) {
+                    dates.push(matching[index as usize]);
+                }
+            }
+        }
+    }
+
+    dates
+}
+
+// Dia do mês (negativo conta do fim: -1 = último dia); None se não existir
+fn month_day(month: NaiveDate, next_month: NaiveDate, day: i32) -> Option<NaiveDate> {
+    if day > 0 {
+        month.with_day(day as u32)
+    } else {
+        let date = next_month.checked_sub_days(Days::new((-day) as u64))?;
+        (date >= month).then_some(date)
+    }
+}
+
+fn parse_frequency(value: &str) -> Result<Frequency, String> {
+    Frequency::parse(&value.to_ascii_lowercase()).ok_or_else(|| format!("Unsupported FREQ: {}", value))
+}
+
+fn parse_number(key: &str, value: &str, min: i32, max: i32) -> Result<i32, String> {
+    value
+        .parse::<i32>()
+        .ok()
+        .filter(|n| (min..=max).contains(n))
+        .ok_or_else(|| format!("Invalid {}: {}", key, value))
+}
+
+// Valores entre -max e max, exceto zero
+fn parse_signed(key: &str, value: &str, max: i32) -> Result<i32, String> {
+    parse_number(key, value, -max, max)
+        .ok()
+        .filter(|n| *n != 0)
+        .ok_or_else(|| format!("Invalid {}: {}", key, value))
+}
+
+fn parse_until(value: &str) -> Result<NaiveDate, String> {
+    // Aceita data (20251231) ou data e hora (20251231T235959Z); a hora é ignorada
+    let date = value.split('T').next().unwrap_or_default();
+    NaiveDate::parse_from_str(date, "%Y%m%d").map_err(|_| format!("Invalid UNTIL: {}", value))
+}
+
+fn parse_list<T>(value: &str, parse: impl Fn(&str) -> Result<T, String>) -> Result<Vec<T>, String> {
+    value.split(',').map(|item| parse(item.trim())).collect()
+}
+
+fn parse_weekday_rule(value: &str) -> Result<WeekdayRule, String> {
+    let invalid = || format!("Invalid BYDAY: {}", value);
+    if value.len() < 2 || !value.is_char_boundary(value.len() - 2) {
+        return Err(invalid());
+    }
+
+    let (ordinal, code) = value.split_at(value.len() - 2);
+    let weekday = match code {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return Err(invalid()),
+    };
+
+    let ordinal = if ordinal.is_empty() {
+        None
+    } else {
+        Some(parse_signed("BYDAY", ordinal.trim_start_matches('+'), 53).map_err(|_| invalid())?)
+    };
+
+    Ok(WeekdayRule { ordinal, weekday })
+}
+
+fn weekday_code(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+fn join<T: ToString>(values: &[T]) -> String {
+    values.iter().map(ToString::to_string).collect::<Vec<_>>().join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::recurrence::Schedule;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn occurrences(rule: &str, start: NaiveDate, n: usize) -> Vec<NaiveDate> {
+        let schedule = Schedule::from_rrule(start, RRule::parse(rule).unwrap());
+        schedule.occurrences_after(None).take(n).collect()
+    }
+
+    #[test]
+    fn last_friday_of_the_month() {
+        assert_eq!(
+            occurrences("FREQ=MONTHLY;BYDAY=-1FR", date(2025, 1, 1), 4),
+            vec![date(2025, 1, 31), date(2025, 2, 28), date(2025, 3, 28), date(2025, 4, 25)]
+        );
+    }
+
+    #[test]
+    fn last_weekday_of_the_month_with_bysetpos() {
+        assert_eq!(
+            occurrences("FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1", date(2025, 1, 1), 5),
+            vec![
+                date(2025, 1, 31),
+                date(2025, 2, 28),
+                date(2025, 3, 31),
+                date(2025, 4, 30),
+                date(2025, 5, 30),
+            ]
+        );
+    }
+
+    // Exemplos da seção 3.8.5.3 da RFC 5545
+    #[test]
+    fn rfc_monthly_examples() {
+        assert_eq!(
+            occurrences("FREQ=MONTHLY;COUNT=10;BYDAY=1FR", date(1997, 9, 5), 20),
+            vec![
+                date(1997, 9, 5),
+                date(1997, 10, 3),
+                date(1997, 11, 7),
+                date(1997, 12, 5),
+                date(1998, 1, 2),
+                date(1998, 2, 6),
+                date(1998, 3, 6),
+                date(1998, 4, 3),
+                date(1998, 5, 1),
+                date(1998, 6, 5),
+            ]
+        );
+        assert_eq!(
+            occurrences("FREQ=MONTHLY;UNTIL=19971224T000000Z;BYDAY=1FR", date(1997, 9, 5), 20),
+            vec![date(1997, 9, 5), date(1997, 10, 3), date(1997, 11, 7), date(1997, 12, 5)]
+        );
+        assert_eq!(
+            occurrences("FREQ=MONTHLY;INTERVAL=2;COUNT=10;BYDAY=1SU,-1SU", date(1997, 9, 7), 20),
+            vec![
+                date(1997, 9, 7),
+                date(1997, 9, 28),
+                date(1997, 11, 2),
+                date(1997, 11, 30),
+                date(1998, 1, 4),
+                date(1998, 1, 25),
+                date(1998, 3, 1),
+                date(1998, 3, 29),
+                date(1998, 5, 3),
+                date(1998, 5, 31),
+            ]
+        );
+        assert_eq!(
+            occurrences("FREQ=MONTHLY;BYMONTHDAY=-3", date(1997, 9, 28), 6),
+            vec![
+                date(1997, 9, 28),
+                date(1997, 10, 29),
+                date(1997, 11, 28),
+                date(1997, 12, 29),
+                date(1998, 1, 29),
+                date(1998, 2, 26),
+            ]
+        );
+        assert_eq!(
+            occurrences("FREQ=MONTHLY;COUNT=3;BYDAY=TU,WE,TH;BYSETPOS=3", date(1997, 9, 4), 10),
+            vec![date(1997, 9, 4), date(1997, 10, 7), date(1997, 11, 6)]
+        );
+    }
+
+    #[test]
+    fn rfc_yearly_examples() {
+        assert_eq!(
+            occurrences("FREQ=YEARLY;BYDAY=20MO", date(1997, 5, 19), 3),
+            vec![date(1997, 5, 19), date(1998, 5, 18), date(1999, 5, 17)]
+        );
+        assert_eq!(
+            occurrences("FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=29", date(2024, 1, 1), 2),
+            vec![date(2024, 2, 29), date(2028, 2, 29)]
+        );
+    }
+
+    #[test]
+    fn yearly_bymonthday_without_bymonth_expands_to_every_month() {
+        assert_eq!(
+            occurrences("FREQ=YEARLY;BYMONTHDAY=1", date(2025, 3, 15), 3),
+            vec![date(2025, 4, 1), date(2025, 5, 1), date(2025, 6, 1)]
+        );
+        assert_eq!(
+            occurrences("FREQ=YEARLY;BYMONTHDAY=31", date(2025, 11, 1), 3),
+            vec![date(2025, 12, 31), date(2026, 1, 31), date(2026, 3, 31)]
+        );
+    }
+
+    #[test]
+    fn count_counts_from_the_start_date() {
+        let schedule = Schedule::from_rrule(
+            date(2025, 1, 1),
+            RRule::parse("FREQ=MONTHLY;COUNT=3;BYMONTHDAY=1,15").unwrap(),
+        );
+
+        assert_eq!(
+            schedule.occurrences_after(Some(date(2025, 1, 20))).collect::<Vec<_>>(),
+            vec![date(2025, 2, 1)]
+        );
+        assert_eq!(schedule.remaining_after(None), Some(3));
+    }
+
+    #[test]
+    fn parse_normalizes_the_rule() {
+        let rule = RRule::parse("rrule:freq=monthly;interval=1;byday=+2fr").unwrap();
+        assert_eq!(rule.to_string(), "FREQ=MONTHLY;BYDAY=2FR");
+
+        let rule = RRule::parse("FREQ=WEEKLY;INTERVAL=2;UNTIL=20251231T235959Z;BYDAY=MO,WE").unwrap();
+        assert_eq!(rule.to_string(), "FREQ=WEEKLY;INTERVAL=2;UNTIL=20251231;BYDAY=MO,WE");
+    }
+
+    #[test]
+    fn parse_rejects_invalid_rules() {
+        for rule in [
+            "BYDAY=MO",
+            "FREQ=HOURLY",
+            "FREQ=MONTHLY;COUNT=2;UNTIL=20251231",
+            "FREQ=WEEKLY;BYDAY=1MO",
+            "FREQ=WEEKLY;BYMONTHDAY=1",
+            "FREQ=MONTHLY;BYSETPOS=1",
+            "FREQ=MONTHLY;BYMONTHDAY=0",
+            "FREQ=MONTHLY;BYMONTHDAY=32",
+            "FREQ=MONTHLY;FREQ=YEARLY",
+            "FREQ=MONTHLY;WKST=SU",
+            "FREQ=MONTHLY;INTERVAL=0",
+        ] {
+            assert!(RRule::parse(rule).is_err(), "{}", rule);
+        }
+    }
+}